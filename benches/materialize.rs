@@ -0,0 +1,82 @@
+//! 物化阶段的基准：`cargo bench --bench materialize`
+//!
+//! 同一棵 `Json` 树分别用逐个 `append` 的朴素写法与 `Json::into_py_object`
+//! 转成 Python 对象，对比两者的中位耗时。
+use llm_json_utils::repair::{repair_to_value, Json, RepairOptions};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const MIN_SAMPLES: usize = 10;
+const TARGET_TIME: Duration = Duration::from_secs(1);
+
+/// 对照组：空 list 逐个 append、dict 逐个 set_item
+fn append_builder(py: Python<'_>, value: Json) -> PyResult<PyObject> {
+    Ok(match value {
+        Json::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(append_builder(py, item)?)?;
+            }
+            list.into()
+        }
+        Json::Object(members) => {
+            let dict = PyDict::new(py);
+            for (key, value) in members {
+                dict.set_item(key, append_builder(py, value)?)?;
+            }
+            dict.into()
+        }
+        leaf => leaf.into_py_object(py)?,
+    })
+}
+
+fn wide_array() -> String {
+    let items: Vec<String> = (0..200_000).map(|i| i.to_string()).collect();
+    format!("[{}]", items.join(","))
+}
+
+fn records() -> String {
+    let items: Vec<String> = (0..20_000)
+        .map(|i| format!("{{\"id\": {i}, \"tags\": [\"a\", \"b\", \"c\"], \"score\": {i}.5}}"))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+fn bench(
+    py: Python<'_>,
+    name: &str,
+    tree: &Json,
+    build: fn(Python<'_>, Json) -> PyResult<PyObject>,
+) {
+    let mut samples = Vec::new();
+    let started = Instant::now();
+    while samples.len() < MIN_SAMPLES || started.elapsed() < TARGET_TIME {
+        let value = tree.clone();
+        let t = Instant::now();
+        black_box(build(py, value).unwrap());
+        samples.push(t.elapsed());
+    }
+    samples.sort();
+    let median = samples[samples.len() / 2];
+    println!(
+        "{name:<24} {:>10.3} ms/iter ({} samples)",
+        median.as_secs_f64() * 1e3,
+        samples.len()
+    );
+}
+
+fn main() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions::default();
+    Python::with_gil(|py| {
+        for (name, input) in [("wide_array", wide_array()), ("records", records())] {
+            let tree = repair_to_value(&input, &opts).unwrap().value;
+            bench(py, &format!("{name}/append"), &tree, append_builder);
+            bench(py, &format!("{name}/presized"), &tree, |py, tree| {
+                tree.into_py_object(py)
+            });
+        }
+    });
+}
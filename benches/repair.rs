@@ -0,0 +1,110 @@
+//! 纯 Rust 修复路径的基准：`cargo bench --bench repair [-- 名称过滤]`
+//!
+//! 只依赖标准库计时，不引入额外依赖；每个用例取多轮的中位数，
+//! 修改解析器前后各跑一次对比即可。
+use llm_json_utils::repair::{repair_to_value, RepairOptions};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// 每个用例至少采样的轮数与总时长
+const MIN_SAMPLES: usize = 10;
+const TARGET_TIME: Duration = Duration::from_secs(1);
+
+/// 用例名与生成输入的函数
+type Case = (&'static str, fn() -> String);
+
+fn number_array() -> String {
+    let items: Vec<String> = (0..100_000)
+        .map(|i| match i % 3 {
+            0 => i.to_string(),
+            1 => format!("-{i}.25"),
+            _ => format!("{i}e-3"),
+        })
+        .collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// 对象套数组，共 500 层，接近 512 层上限；超过上限会退化成逐个起点重试，测不到正常路径
+fn nested_objects() -> String {
+    let depth = 250;
+    let mut out = String::new();
+    for i in 0..depth {
+        out.push_str(&format!("{{\"level{i}\": [1, "));
+    }
+    out.push_str("null");
+    for _ in 0..depth {
+        out.push_str("]}");
+    }
+    out
+}
+
+fn string_heavy() -> String {
+    let items: Vec<String> = (0..10_000)
+        .map(|i| format!("\"item {i}: caf\\u00e9 \\\"quoted\\\" 中文\\n tab\\t end\""))
+        .collect();
+    format!("{{\"strings\": [{}]}}", items.join(",\n"))
+}
+
+/// 不含转义的长文本，LLM 输出里最常见的字符串形态
+fn plain_text() -> String {
+    let sentence = "The quick brown fox jumps over the lazy dog, 敏捷的棕色狐狸. ";
+    let items: Vec<String> = (0..5_000)
+        .map(|i| format!("{{\"id\": {i}, \"text\": \"{}\"}}", sentence.repeat(8)))
+        .collect();
+    format!("[{}]", items.join(",\n"))
+}
+
+/// LLM 风格的回复：前言、代码块、注释、尾逗号、截断；前言让它走跳过前缀的路径
+fn llm_transcript() -> String {
+    let mut body = String::from("Sure! Here is the data you asked for:\n\n```json\n{\n");
+    for i in 0..2_000 {
+        body.push_str(&format!(
+            "  // record {i}\n  \"key_{i}\": {{'name': 'n{i}', \"tags\": [\"a\", \"b\",], /* note */ \"ok\": True}},\n"
+        ));
+    }
+    body.push_str("  \"last\": [1, 2, 3");
+    body
+}
+
+fn bench(name: &str, input: &str, opts: &RepairOptions) {
+    // 先确认输入能解析，避免测到的是错误路径
+    repair_to_value(input, opts).unwrap_or_else(|e| panic!("{name}: {e}"));
+
+    let mut samples = Vec::new();
+    let started = Instant::now();
+    while samples.len() < MIN_SAMPLES || started.elapsed() < TARGET_TIME {
+        let t = Instant::now();
+        black_box(repair_to_value(black_box(input), opts).ok());
+        samples.push(t.elapsed());
+    }
+    samples.sort();
+    let median = samples[samples.len() / 2];
+    let throughput = input.len() as f64 / median.as_secs_f64() / (1024.0 * 1024.0);
+    println!(
+        "{name:<16} {:>10.3} ms/iter {throughput:>9.1} MiB/s ({} samples, {} bytes)",
+        median.as_secs_f64() * 1e3,
+        samples.len(),
+        input.len()
+    );
+}
+
+fn main() {
+    // cargo bench 会传入 `--bench` 等参数，只把非 `-` 开头的参数当作名称过滤
+    let filter: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect();
+    let opts = RepairOptions::default();
+    let cases: [Case; 5] = [
+        ("number_array", number_array),
+        ("nested_objects", nested_objects),
+        ("string_heavy", string_heavy),
+        ("plain_text", plain_text),
+        ("llm_transcript", llm_transcript),
+    ];
+    for (name, make) in cases {
+        if filter.is_empty() || filter.iter().any(|f| name.contains(f.as_str())) {
+            bench(name, &make(), &opts);
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! 任意字节喂给纯 Rust 修复路径：允许返回错误，不允许 panic
+//!
+//! 运行：`cargo +nightly fuzz run repair`（在 `fuzz/` 目录下）
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use llm_json_utils::repair::{repair_to_value, repair_to_values, RepairOptions};
+
+fuzz_target!(|data: &[u8]| {
+    // 首字节选择选项组合，其余是输入
+    let Some((&flags, rest)) = data.split_first() else {
+        return;
+    };
+    let Ok(text) = std::str::from_utf8(rest) else {
+        return;
+    };
+    let opts = RepairOptions {
+        json5_numbers: flags & 1 != 0,
+        assume_object: flags & 2 != 0,
+        skip_separators: flags & 4 != 0,
+        lenient_missing_values: flags & 8 != 0,
+        leading_zero_as_string: flags & 16 != 0,
+        coerce_keys: flags & 32 != 0,
+        ..Default::default()
+    };
+    if let Ok(repaired) = repair_to_value(text, &opts) {
+        repaired.value.to_json_string(None, false);
+    }
+    let _ = repair_to_values(text, &opts);
+});
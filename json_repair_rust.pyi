@@ -1,4 +1,864 @@
-from typing import Any
+from typing import Any, Callable, Iterable, Literal, Mapping, Protocol, TypedDict, TypeVar
 
-def repair_json(json_str: str, /) -> Any: ...
+class SupportsWrite(Protocol):
+    def write(self, text: str, /) -> object: ...
 
+LeafTag = Literal["null", "bool", "int", "float", "str"]
+
+_T = TypeVar("_T")
+
+class BatchStats(TypedDict):
+    total: int
+    succeeded: int
+    failed: int
+    truncated: int
+    repairs: dict[str, int]
+
+class RepairReport(TypedDict):
+    valid: bool
+    truncated: bool
+    depth: int
+    value_count: int
+
+class JsonRepairError(ValueError):
+    offset: int
+    line: int
+    column: int
+
+class JsonSyntaxError(JsonRepairError): ...
+class JsonTruncatedError(JsonRepairError): ...
+class JsonDepthError(JsonRepairError): ...
+
+def repair_json(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> Any: ...
+
+def repair_json_or_none(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> Any | None: ...
+
+def repair_json_batch(
+    texts: Iterable[str],
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> list[Any | None]: ...
+
+def repair_json_batch_parallel(
+    texts: Iterable[str],
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> list[Any | None]: ...
+
+def repair_json_batch_stats(
+    texts: Iterable[str],
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+) -> BatchStats: ...
+
+def repair_json_verbose(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> tuple[Any, list[tuple[int, str, str]]]: ...
+
+def repair_json_with_comments(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> tuple[Any, list[tuple[int, str]]]: ...
+
+def repair_json_status(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> tuple[Any, bool]: ...
+
+def parse_value_at(
+    json_str: str,
+    offset: int,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> tuple[Any, int]: ...
+
+def can_repair(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> bool: ...
+
+def repair_report(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> RepairReport: ...
+
+def repair_json_concatenated(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> list[Any]: ...
+
+def extract_all_json(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> list[Any]: ...
+
+def repair_last_json(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> Any: ...
+
+def repair_to_json_str(
+    json_str: str,
+    /,
+    indent: int | None = None,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    escape_forward_slashes: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+) -> str: ...
+
+def repair_to_writer(
+    json_str: str,
+    writer: SupportsWrite,
+    /,
+    indent: int | None = None,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    escape_forward_slashes: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+) -> None: ...
+
+def repair_and_normalize(
+    json_str: str,
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    escape_forward_slashes: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> tuple[Any, str]: ...
+
+def parse_into(
+    json_str: str,
+    cls: type[_T],
+    /,
+    *,
+    json5_numbers: bool = False,
+    assume_object: bool = False,
+    comment_styles: Iterable[str] = ("hash", "slash", "block"),
+    bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+    skip_separators: bool = False,
+    skip_zero_width: bool = False,
+    strip_html: bool = False,
+    strip_markdown_emphasis: bool = False,
+    skip_leading_garbage: bool = False,
+    lenient_separators: bool = False,
+    escape_forward_slashes: bool = False,
+    lenient_missing_values: bool = False,
+    lenient_commas: bool = False,
+    extra_commas: Literal["ignore", "error"] = "ignore",
+    lenient_brackets: bool = False,
+    js_literals: bool = False,
+    auto_unstringify: bool = False,
+    leading_zero_as_string: bool = False,
+    coerce_whole_floats: bool = False,
+    raw_numbers: bool = False,
+    normalize_unicode_digits: bool = False,
+    normalize_unicode_minus: bool = False,
+    coerce_keys: bool = False,
+    sort_keys: bool = False,
+    unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+    control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+    bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+    line_continuations: bool = False,
+    max_bytes: int | None = None,
+    max_depth: int | None = None,
+    max_items: int | None = None,
+    intern_keys: bool = False,
+    value_hook: Callable[[Any, LeafTag], Any] | None = None,
+    object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+) -> _T: ...
+
+class JsonRepairer:
+    def __init__(
+        self,
+        *,
+        json5_numbers: bool = False,
+        assume_object: bool = False,
+        comment_styles: Iterable[str] = ("hash", "slash", "block"),
+        bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+        skip_separators: bool = False,
+        skip_zero_width: bool = False,
+        strip_html: bool = False,
+        strip_markdown_emphasis: bool = False,
+        skip_leading_garbage: bool = False,
+        lenient_separators: bool = False,
+        lenient_missing_values: bool = False,
+        lenient_commas: bool = False,
+        extra_commas: Literal["ignore", "error"] = "ignore",
+        lenient_brackets: bool = False,
+        js_literals: bool = False,
+        auto_unstringify: bool = False,
+        leading_zero_as_string: bool = False,
+        coerce_whole_floats: bool = False,
+        raw_numbers: bool = False,
+        normalize_unicode_digits: bool = False,
+        normalize_unicode_minus: bool = False,
+        coerce_keys: bool = False,
+        sort_keys: bool = False,
+        unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+        control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+        bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+        line_continuations: bool = False,
+        max_bytes: int | None = None,
+        max_depth: int | None = None,
+        max_items: int | None = None,
+        intern_keys: bool = False,
+        value_hook: Callable[[Any, LeafTag], Any] | None = None,
+        object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+    ) -> None: ...
+    def repair(self, json_str: str, /) -> Any: ...
+    def repair_verbose(self, json_str: str, /) -> tuple[Any, list[tuple[int, str, str]]]: ...
+    def repair_status(self, json_str: str, /) -> tuple[Any, bool]: ...
+
+class StreamingParser:
+    def __init__(
+        self,
+        *,
+        json5_numbers: bool = False,
+        assume_object: bool = False,
+        comment_styles: Iterable[str] = ("hash", "slash", "block"),
+        bigint_mode: Literal["python_int", "float", "string"] = "python_int",
+        skip_separators: bool = False,
+        skip_zero_width: bool = False,
+        strip_html: bool = False,
+        strip_markdown_emphasis: bool = False,
+        skip_leading_garbage: bool = False,
+        lenient_separators: bool = False,
+        lenient_missing_values: bool = False,
+        lenient_commas: bool = False,
+        extra_commas: Literal["ignore", "error"] = "ignore",
+        lenient_brackets: bool = False,
+        js_literals: bool = False,
+        auto_unstringify: bool = False,
+        leading_zero_as_string: bool = False,
+        coerce_whole_floats: bool = False,
+        raw_numbers: bool = False,
+        normalize_unicode_digits: bool = False,
+        normalize_unicode_minus: bool = False,
+        coerce_keys: bool = False,
+        sort_keys: bool = False,
+        unclosed_comments: Literal["error", "recover", "consume"] = "consume",
+        control_char_policy: Literal["keep", "escape", "strip"] = "keep",
+        bad_unicode_policy: Literal["preserve", "replace", "error"] = "preserve",
+        line_continuations: bool = False,
+        max_bytes: int | None = None,
+        max_depth: int | None = None,
+        max_items: int | None = None,
+        intern_keys: bool = False,
+        value_hook: Callable[[Any, LeafTag], Any] | None = None,
+        object_pairs_hook: Callable[[list[tuple[str, Any]]], Any] | None = None,
+    ) -> None: ...
+    def feed(self, chunk: str, /) -> Any: ...
+    def finish(self) -> Any: ...
+
+class CompiledSchema: ...
+
+def compile_schema(schema: dict[str, Any], /) -> CompiledSchema: ...
+
+class JsonExtractor:
+    def __init__(
+        self,
+        schema: dict[str, Any] | CompiledSchema,
+        /,
+        *,
+        enum_fuzzy: bool = False,
+        enum_max_distance: int = 0,
+        key_normalization: Literal["exact", "trim", "lower", "snake"] = "exact",
+        normalize_unicode_digits: bool = False,
+        coerce_formats: bool = False,
+        truthy_words: Iterable[str] = (),
+        falsy_words: Iterable[str] = (),
+        coerce_scalar_to_array: bool = False,
+        parse_grouped_numbers: bool = False,
+        field_fallbacks: Mapping[str, Any] = {},
+        drop_mode: Literal["read", "write"] | None = None,
+        drop_deprecated: bool = False,
+    ) -> None: ...
+    def extract(self, text: bytes, /) -> Any: ...
+    def extract_verbose(self, text: bytes, /) -> tuple[Any, list[tuple[int, str, str]]]: ...
+
+def repair_and_validate(
+    text: str,
+    schema: dict[str, Any] | CompiledSchema,
+    /,
+    repair_options: Mapping[str, Any] | None = None,
+    *,
+    enum_fuzzy: bool = False,
+    enum_max_distance: int = 0,
+    key_normalization: Literal["exact", "trim", "lower", "snake"] = "exact",
+    normalize_unicode_digits: bool = False,
+    coerce_formats: bool = False,
+    truthy_words: Iterable[str] = (),
+    falsy_words: Iterable[str] = (),
+    coerce_scalar_to_array: bool = False,
+    parse_grouped_numbers: bool = False,
+    field_fallbacks: Mapping[str, Any] = {},
+    drop_mode: Literal["read", "write"] | None = None,
+    drop_deprecated: bool = False,
+) -> Any: ...
@@ -2,65 +2,409 @@
 use crate::structural::schema::SchemaNode;
 use crate::utils::cursor::Cursor;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use std::sync::Arc;
 
-mod repair;
+pub mod repair;
 pub mod structural;
 pub mod utils;
 
-/// 严格修复 JSON 字符串
+/// 严格修复 JSON 字符串，关键字参数见 `repair::RepairOptions`
 #[pyfunction]
-pub fn repair_json(py: Python, text: &str) -> PyResult<PyObject> {
-    repair::repair_json(py, text)
+#[pyo3(signature = (text, **options))]
+pub fn repair_json(py: Python, text: &str, options: Option<&PyDict>) -> PyResult<PyObject> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_json(py, text, &opts)
+}
+
+/// 同 `repair_json`，无法修复时返回 None 而不是抛出 `ValueError`
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn repair_json_or_none(py: Python, text: &str, options: Option<&PyDict>) -> PyResult<PyObject> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_json_or_none(py, text, &opts)
+}
+
+/// 批量修复，按输入顺序返回 list，无法修复的元素为 None
+#[pyfunction]
+#[pyo3(signature = (texts, **options))]
+fn repair_json_batch(
+    py: Python,
+    texts: Vec<&str>,
+    options: Option<&PyDict>,
+) -> PyResult<Vec<PyObject>> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_json_batch(py, &texts, &opts)
+}
+
+/// 批量修复但只返回统计：成功、失败、截断的个数，以及每类修复涉及的输入个数
+#[pyfunction]
+#[pyo3(signature = (texts, **options))]
+fn repair_json_batch_stats(
+    py: Python,
+    texts: Vec<&str>,
+    options: Option<&PyDict>,
+) -> PyResult<PyObject> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_json_batch_stats(py, &texts, &opts)
+}
+
+/// 同 `repair_json_batch`，但在释放 GIL 后用多个线程解析，适合大批量输入
+#[pyfunction]
+#[pyo3(signature = (texts, **options))]
+fn repair_json_batch_parallel(
+    py: Python,
+    texts: Vec<&str>,
+    options: Option<&PyDict>,
+) -> PyResult<Vec<PyObject>> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_json_batch_parallel(py, &texts, &opts)
+}
+
+/// `(offset, kind, detail)`
+type RepairTuple = (usize, &'static str, String);
+
+/// 修复并返回 `(value, repairs)`，repairs 为 `(offset, kind, detail)` 列表，offset 为 UTF-8 字节偏移
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn repair_json_verbose(
+    py: Python,
+    text: &str,
+    options: Option<&PyDict>,
+) -> PyResult<(PyObject, Vec<RepairTuple>)> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    let (value, repairs) = repair::repair_json_verbose(py, text, &opts)?;
+    Ok((value, repair_tuples(repairs)))
+}
+
+/// 修复并返回 `(value, comments)`，comments 为被跳过注释的 `(offset, text)` 列表，text 不含注释符、已去掉首尾空白
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn repair_json_with_comments(
+    py: Python,
+    text: &str,
+    options: Option<&PyDict>,
+) -> PyResult<(PyObject, Vec<(usize, String)>)> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    let (value, comments) = repair::repair_json_with_comments(py, text, &opts)?;
+    let comments = comments.into_iter().map(|c| (c.offset, c.text)).collect();
+    Ok((value, comments))
+}
+
+/// 从字节偏移 `offset` 处解析一个值，返回 `(value, end_offset)`；不向后寻找其他起点
+#[pyfunction]
+#[pyo3(signature = (text, offset, **options))]
+fn parse_value_at(
+    py: Python,
+    text: &str,
+    offset: usize,
+    options: Option<&PyDict>,
+) -> PyResult<(PyObject, usize)> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::parse_value_at(py, text, offset, &opts)
+}
+
+fn repair_tuples(repairs: Vec<repair::Repair>) -> Vec<RepairTuple> {
+    repairs
+        .into_iter()
+        .map(|r| (r.offset, r.kind.as_str(), r.detail))
+        .collect()
+}
+
+/// 修复并返回 `(value, was_complete)`；有容器或字符串在 EOF 处被自动闭合时 `was_complete` 为 False
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn repair_json_status(
+    py: Python,
+    text: &str,
+    options: Option<&PyDict>,
+) -> PyResult<(PyObject, bool)> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_json_status(py, text, &opts)
+}
+
+/// 只判断能否修复，不构建 Python 对象
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn can_repair(py: Python, text: &str, options: Option<&PyDict>) -> PyResult<bool> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    Ok(repair::can_repair(py, text, &opts))
+}
+
+/// 返回 `{valid, truncated, depth, value_count}`，不构建值本身
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn repair_report(py: Python, text: &str, options: Option<&PyDict>) -> PyResult<PyObject> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_report(py, text, &opts)
+}
+
+/// 解析首尾相接的多个 JSON 值（如 `{"a":1}{"b":2}`），返回 list
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn repair_json_concatenated(
+    py: Python,
+    text: &str,
+    options: Option<&PyDict>,
+) -> PyResult<PyObject> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_json_concatenated(py, text, &opts)
+}
+
+/// 按出现顺序提取文本中所有的 JSON 对象/数组（含代码块内外），返回 list
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn extract_all_json(py: Python, text: &str, options: Option<&PyDict>) -> PyResult<PyObject> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::extract_all_json(py, text, &opts)
+}
+
+/// 修复文本中最后一个 JSON 对象/数组，优先取完整闭合的那个
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn repair_last_json(py: Python, text: &str, options: Option<&PyDict>) -> PyResult<PyObject> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_last_json(py, text, &opts)
+}
+
+/// 修复并返回 `(value, canonical)`，`canonical` 是同一修复结果的紧凑 JSON 文本
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn repair_and_normalize(
+    py: Python,
+    text: &str,
+    options: Option<&PyDict>,
+) -> PyResult<(PyObject, String)> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_and_normalize(py, text, &opts)
+}
+
+/// 修复后构造 `cls(**value)`；`cls` 有 `model_validate` 类方法（Pydantic）时改用它，关键字参数见 `repair::RepairOptions`
+#[pyfunction]
+#[pyo3(signature = (text, cls, **options))]
+fn parse_into(py: Python, text: &str, cls: &PyAny, options: Option<&PyDict>) -> PyResult<PyObject> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::parse_into(py, text, cls, &opts)
+}
+
+/// 修复后重新序列化为 JSON 文本；`indent` 同 `json.dumps`，其余关键字参数见 `repair::RepairOptions`
+#[pyfunction]
+#[pyo3(signature = (text, indent = None, **options))]
+fn repair_to_json_str(
+    py: Python,
+    text: &str,
+    indent: Option<usize>,
+    options: Option<&PyDict>,
+) -> PyResult<String> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_to_json_str(py, text, &opts, indent)
+}
+
+/// 修复后把 JSON 文本分块写给 `writer.write(str)`，适合直接写文件或 socket
+#[pyfunction]
+#[pyo3(signature = (text, writer, indent = None, **options))]
+fn repair_to_writer(
+    py: Python,
+    text: &str,
+    writer: &PyAny,
+    indent: Option<usize>,
+    options: Option<&PyDict>,
+) -> PyResult<()> {
+    let opts = repair::RepairOptions::from_kwargs(options)?;
+    repair::repair_to_writer(py, text, writer, &opts, indent)
+}
+
+/// 预先配置好选项的修复器，服务启动时构建一次，之后每次调用不必再传关键字参数
+#[pyclass]
+struct JsonRepairer {
+    options: repair::RepairOptions,
+}
+
+#[pymethods]
+impl JsonRepairer {
+    /// 关键字参数同 `repair_json`
+    #[new]
+    #[pyo3(signature = (**options))]
+    fn new(options: Option<&PyDict>) -> PyResult<Self> {
+        Ok(JsonRepairer {
+            options: repair::RepairOptions::from_kwargs(options)?,
+        })
+    }
+
+    /// 同 `repair_json(text, **options)`
+    fn repair(&self, py: Python, text: &str) -> PyResult<PyObject> {
+        repair::repair_json(py, text, &self.options)
+    }
+
+    /// 同 `repair_json_verbose(text, **options)`
+    fn repair_verbose(&self, py: Python, text: &str) -> PyResult<(PyObject, Vec<RepairTuple>)> {
+        let (value, repairs) = repair::repair_json_verbose(py, text, &self.options)?;
+        Ok((value, repair_tuples(repairs)))
+    }
+
+    /// 同 `repair_json_status(text, **options)`
+    fn repair_status(&self, py: Python, text: &str) -> PyResult<(PyObject, bool)> {
+        repair::repair_json_status(py, text, &self.options)
+    }
+}
+
+/// 分块喂入的流式修复器，供 LLM 逐 token 输出时渐进地展示结果
+#[pyclass]
+struct StreamingParser {
+    buffer: repair::StreamBuffer,
+    options: repair::RepairOptions,
+}
+
+#[pymethods]
+impl StreamingParser {
+    /// 关键字参数同 `repair_json`
+    #[new]
+    #[pyo3(signature = (**options))]
+    fn new(options: Option<&PyDict>) -> PyResult<Self> {
+        Ok(StreamingParser {
+            buffer: repair::StreamBuffer::default(),
+            options: repair::RepairOptions::from_kwargs(options)?,
+        })
+    }
+
+    /// 追加一块文本，返回目前为止能解析出的值（还没有时为 None）
+    fn feed(&mut self, py: Python, chunk: &str) -> PyResult<PyObject> {
+        self.buffer.feed(chunk);
+        repair::repair_partial(py, &mut self.buffer, &self.options)
+    }
+
+    /// 输入结束：按 `repair_json` 的规则解析全部文本（自动闭合未结束的容器），并清空缓冲区
+    fn finish(&mut self, py: Python) -> PyResult<PyObject> {
+        let text = self.buffer.take();
+        repair::repair_json(py, &text, &self.options)
+    }
+}
+
+/// 编译后的 Schema，可被多个提取器共享，避免重复遍历 dict
+#[pyclass]
+struct CompiledSchema {
+    root: Arc<SchemaNode>,
+}
+
+/// 编译一次 Schema，之后反复复用
+#[pyfunction]
+fn compile_schema(schema_obj: &PyAny) -> PyResult<CompiledSchema> {
+    let root = structural::compiler::compile(schema_obj)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid schema: {:?}", e)))?;
+    Ok(CompiledSchema {
+        root: Arc::new(root),
+    })
+}
+
+/// schema dict 或 `compile_schema` 的结果
+fn schema_root(schema_obj: &PyAny) -> PyResult<Arc<SchemaNode>> {
+    match schema_obj.extract::<PyRef<CompiledSchema>>() {
+        Ok(compiled) => Ok(compiled.root.clone()),
+        Err(_) => Ok(compile_schema(schema_obj)?.root),
+    }
+}
+
+/// 先宽松修复 `text`，再按 `schema` 校验修复出的值；`repair_options` 见 `repair::RepairOptions`，
+/// 其余关键字参数见 `structural::options::ExtractOptions`
+#[pyfunction]
+#[pyo3(signature = (text, schema, repair_options = None, **options))]
+fn repair_and_validate(
+    py: Python,
+    text: &str,
+    schema: &PyAny,
+    repair_options: Option<&PyDict>,
+    options: Option<&PyDict>,
+) -> PyResult<PyObject> {
+    let repair_opts = repair::RepairOptions::from_kwargs(repair_options)?;
+    let opts = structural::options::ExtractOptions::from_kwargs(options)?;
+    let root = schema_root(schema)?;
+    structural::validate::repair_and_validate(py, text, &root, &repair_opts, &opts)
 }
 
 /// 基于 Schema 的 JSON 提取器
 #[pyclass]
 struct JsonExtractor {
     root: Arc<SchemaNode>,
+    options: structural::options::ExtractOptions,
 }
 
 #[pymethods]
 impl JsonExtractor {
+    /// 接受 schema dict 或 `compile_schema` 的结果，关键字参数见 `structural::options::ExtractOptions`
     #[new]
-    fn new(schema_obj: &PyAny) -> PyResult<Self> {
-        let root = structural::compiler::compile(schema_obj).map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!("Invalid schema: {:?}", e))
-        })?;
-        Ok(JsonExtractor {
-            root: Arc::new(root),
-        })
+    #[pyo3(signature = (schema_obj, **options))]
+    fn new(schema_obj: &PyAny, options: Option<&PyDict>) -> PyResult<Self> {
+        let options = structural::options::ExtractOptions::from_kwargs(options)?;
+        let root = schema_root(schema_obj)?;
+        Ok(JsonExtractor { root, options })
     }
 
     fn extract(&self, py: Python, text: &[u8]) -> PyResult<PyObject> {
+        self.extract_verbose(py, text).map(|(value, _)| value)
+    }
+
+    /// 返回 `(value, repairs)`；repairs 列出用上 `field_fallbacks` 的字段，格式同 `repair_json_verbose`
+    fn extract_verbose(&self, py: Python, text: &[u8]) -> PyResult<(PyObject, Vec<RepairTuple>)> {
         // 1. 大海捞针：寻找 JSON 起始
         let mut start_pos = 0;
+        let mut first_error = None;
         while let Some(idx) = memchr::memchr(b'{', &text[start_pos..]) {
             let abs_idx = start_pos + idx;
 
-            // 简单探测
-            let mut cursor = Cursor::new(&text[abs_idx..]);
+            // 游标覆盖整段输入，错误位置才是相对原文的行列
+            let mut cursor = Cursor::new(text);
+            cursor.advance(abs_idx);
 
             // 2. 执行解析
-            match structural::parser::parse_node(&mut cursor, &self.root, py, 0) {
-                Ok(obj) => return Ok(obj),
-                Err(_) => {
-                    // 解析失败，继续找下一个
+            match structural::parser::parse_root_verbose(&mut cursor, &self.root, &self.options, py)
+            {
+                Ok((obj, repairs)) => return Ok((obj, repair_tuples(repairs))),
+                // 嵌套超限时从更深的 `{` 重试只会拿到一棵子树，还要重复付出整段扫描
+                Err(e) if matches!(e.error, structural::parser::ParseError::RecursionLimit) => {
+                    return Err(e.into())
+                }
+                Err(e) => {
+                    // 解析失败，记下第一个候选的错误，继续找下一个
+                    first_error.get_or_insert(e);
                     start_pos = abs_idx + 1;
                     continue;
                 }
             }
         }
 
-        Err(pyo3::exceptions::PyValueError::new_err(
-            "No matching JSON found",
-        ))
+        Err(match first_error {
+            Some(e) => e.into(),
+            None => pyo3::exceptions::PyValueError::new_err("No matching JSON found"),
+        })
     }
 }
 
 #[pymodule]
-fn llm_json_utils(_py: Python, m: &PyModule) -> PyResult<()> {
+fn llm_json_utils(py: Python, m: &PyModule) -> PyResult<()> {
+    repair::register_exceptions(py, m)?;
     m.add_function(wrap_pyfunction!(repair_json, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_or_none, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_batch_parallel, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_batch_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_verbose, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_with_comments, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_status, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_value_at, m)?)?;
+    m.add_function(wrap_pyfunction!(can_repair, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_report, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_json_concatenated, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_all_json, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_last_json, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_to_json_str, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_to_writer, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_and_normalize, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_into, m)?)?;
+    m.add_class::<JsonRepairer>()?;
+    m.add_class::<StreamingParser>()?;
+    m.add_function(wrap_pyfunction!(compile_schema, m)?)?;
+    m.add_class::<CompiledSchema>()?;
     m.add_class::<JsonExtractor>()?;
+    m.add_function(wrap_pyfunction!(repair_and_validate, m)?)?;
     Ok(())
 }
@@ -0,0 +1,114 @@
+//! 修复失败时抛给 Python 的异常层级
+//!
+//! 三个具体异常都继承 `JsonRepairError`，后者又继承 `ValueError`，
+//! 所以原来 `except ValueError` 的调用方不受影响；实例上带有 `offset`、`line`、`column` 属性。
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::fmt;
+
+use crate::utils::cursor::Cursor;
+
+create_exception!(
+    llm_json_utils,
+    JsonRepairError,
+    PyValueError,
+    "Base class for every error raised when the input cannot be repaired."
+);
+create_exception!(
+    llm_json_utils,
+    JsonSyntaxError,
+    JsonRepairError,
+    "The input contains something that no repair rule can make sense of."
+);
+create_exception!(
+    llm_json_utils,
+    JsonTruncatedError,
+    JsonRepairError,
+    "The input ends in a place where no value can be completed."
+);
+create_exception!(
+    llm_json_utils,
+    JsonDepthError,
+    JsonRepairError,
+    "Containers are nested deeper than the parser allows."
+);
+
+/// 失败的类别，决定抛出哪个异常
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// 输入中间出现无法修复的内容
+    Syntax,
+    /// 输入在无法补全的位置结束
+    Truncated,
+    /// 嵌套超过上限
+    Depth,
+    /// 输入超过 `max_bytes` 或 `max_items`
+    TooLarge,
+}
+
+impl ErrorKind {
+    /// 触到安全上限：换个起点重试只会重复同样的工作，或只救回内层的片段
+    pub fn is_limit(self) -> bool {
+        matches!(self, ErrorKind::Depth | ErrorKind::TooLarge)
+    }
+}
+
+/// 修复失败：类别、出错的字节偏移与原因。
+///
+/// 换起点重试时每个失败的候选都会产生一个错误，行列要从输入开头数起，
+/// 所以只存偏移，只给真正抛出的那个错误用 `position` 算行列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairError {
+    pub kind: ErrorKind,
+    pub offset: usize,
+    pub message: String,
+}
+
+impl RepairError {
+    /// 出错处在 `src` 中从 1 开始的 (行, 列)，列按字符计；`src` 必须是产生该错误的输入
+    pub fn position(&self, src: &str) -> (usize, usize) {
+        let mut cursor = Cursor::new(src.as_bytes());
+        cursor.pos = self.offset;
+        let (_, line, column) = cursor.position();
+        (line, column)
+    }
+
+    /// 转成对应的 Python 异常，带上 `offset`、`line`、`column`
+    pub fn into_py_err(self, src: &str) -> PyErr {
+        let (line, column) = self.position(src);
+        let exception = match self.kind {
+            ErrorKind::Syntax => JsonSyntaxError::new_err(self.message),
+            ErrorKind::Truncated => JsonTruncatedError::new_err(self.message),
+            ErrorKind::Depth => JsonDepthError::new_err(self.message),
+            ErrorKind::TooLarge => JsonRepairError::new_err(self.message),
+        };
+        Python::with_gil(|py| {
+            let value = exception.value(py);
+            let located = value
+                .setattr("offset", self.offset)
+                .and_then(|_| value.setattr("line", line))
+                .and_then(|_| value.setattr("column", column));
+            match located {
+                Ok(()) => exception,
+                Err(e) => e,
+            }
+        })
+    }
+}
+
+impl fmt::Display for RepairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// 把异常类注册进模块
+pub fn register(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("JsonRepairError", py.get_type::<JsonRepairError>())?;
+    m.add("JsonSyntaxError", py.get_type::<JsonSyntaxError>())?;
+    m.add("JsonTruncatedError", py.get_type::<JsonTruncatedError>())?;
+    m.add("JsonDepthError", py.get_type::<JsonDepthError>())?;
+    Ok(())
+}
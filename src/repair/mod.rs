@@ -1,94 +1,572 @@
-use pyo3::exceptions::PyValueError;
+use crate::utils::cursor::Cursor;
+use crate::utils::unicode::{ascii_minus, ascii_number_char};
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList, PyString};
-use std::iter::Peekable;
-use std::str::Chars;
+use pyo3::types::PyDict;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::thread;
+
+mod error;
+mod options;
+mod serialize;
+mod sink;
+mod stream;
+mod value;
+
+pub use error::{
+    register as register_exceptions, ErrorKind, JsonDepthError, JsonRepairError, JsonSyntaxError,
+    JsonTruncatedError, RepairError,
+};
+pub use options::{
+    BadUnicodePolicy, BigIntMode, CommentStyles, ControlCharPolicy, ExtraCommas, RepairOptions,
+    UnclosedComments,
+};
+pub use sink::{JsonBuilder, PyBuilder, ValueSink};
+pub use stream::StreamBuffer;
+pub use value::Json;
+
+/// 超过该长度的输入在扫描期间释放 GIL；更短的输入不值得一次 GIL 往返
+const ALLOW_THREADS_MIN_LEN: usize = 16 * 1024;
+
+/// 默认的容器嵌套上限（`max_depth` 未指定时）；解析是递归下降，不设上限时 `[[[[...` 会耗尽栈直接让进程崩溃
+const MAX_DEPTH: usize = 512;
+
+/// 数字被截断时可能悬在末尾、尚未跟上数位的字符
+const DANGLING_NUMBER_CHARS: [char; 5] = ['.', 'e', 'E', '+', '-'];
+
+/// 修复类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepairKind {
+    /// JSON 起点之前的文本被跳过
+    SkippedPrefix,
+    /// JSON 结束之后的文本被忽略
+    IgnoredSuffix,
+    SkippedComment,
+    SkippedFence,
+    /// 多余的逗号（前导、连续或尾随）
+    ExtraComma,
+    /// EOF 处自动补上的 `}` / `]`
+    ClosedContainer,
+    /// EOF 处自动补上的引号
+    ClosedString,
+    /// 单引号字符串按双引号处理
+    SingleQuote,
+    /// `None` / `True` / `NaN` 等非标准字面量
+    Literal,
+    /// 原样保留的未知或损坏转义
+    InvalidEscape,
+    /// 顶层对象体缺少花括号，已整体包成对象
+    WrappedObject,
+    /// 整行的 `---` / `===` 分隔线被跳过
+    SkippedSeparator,
+    /// 缺失的对象值补为 null
+    MissingValue,
+    /// 数字/布尔/null key 转成了字符串
+    CoercedKey,
+    /// 没有闭合的 `/*` 块注释，按 `unclosed_comments` 的策略结束
+    UnclosedComment,
+    /// 字符串里的原始控制字符按 `control_char_policy` 转义或删除
+    ControlChar,
+    /// `lenient_commas` 下元素之间补上的逗号
+    MissingComma,
+    /// `lenient_brackets` 下用错的闭括号（`[1, 2}`）按当前容器的闭合处理，detail 为当前容器应有的闭括号
+    MismatchedBracket,
+    /// `normalize_unicode_digits` 下全角数字/符号转成了 ASCII，detail 为原文
+    NormalizedDigits,
+    /// 字符串里行尾的 `\` 与换行作为续行被删除
+    LineContinuation,
+    /// EOF 处被截断的数字（`3.`、`1e`）去掉悬空部分后按已写出的部分解析，detail 为原文
+    TruncatedNumber,
+    /// `strip_html` 下跳过的 `<pre>` / `</code>` 一类标签，detail 为标签原文
+    SkippedHtmlTag,
+    /// `auto_unstringify` 下按 JSON 重新解析的字符串值，detail 为解析出的类型（`object` / `array`）
+    Unstringified,
+    /// `strip_markdown_emphasis` 下 key 两侧跳过的 `*` / `_`，detail 为跳过的原文
+    StrippedEmphasis,
+    /// `skip_leading_garbage` 下顶层值之前跳过的悬空 `}` / `]` / `,`，detail 为跳过的原文
+    SkippedGarbage,
+    /// `lenient_separators` 下 key 与值之间的 `=>` / `=` 按 `:` 处理，detail 为原文
+    KeySeparator,
+    /// 结构化提取时字段值解析或校验失败，改用 `field_fallbacks` 中的兜底值，detail 为 `字段名: 错误`
+    FieldFallback,
+}
+
+impl RepairKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RepairKind::SkippedPrefix => "skipped_prefix",
+            RepairKind::IgnoredSuffix => "ignored_suffix",
+            RepairKind::SkippedComment => "skipped_comment",
+            RepairKind::SkippedFence => "skipped_fence",
+            RepairKind::ExtraComma => "extra_comma",
+            RepairKind::ClosedContainer => "closed_container",
+            RepairKind::ClosedString => "closed_string",
+            RepairKind::SingleQuote => "single_quote",
+            RepairKind::Literal => "literal",
+            RepairKind::InvalidEscape => "invalid_escape",
+            RepairKind::WrappedObject => "wrapped_object",
+            RepairKind::SkippedSeparator => "skipped_separator",
+            RepairKind::MissingValue => "missing_value",
+            RepairKind::CoercedKey => "coerced_key",
+            RepairKind::UnclosedComment => "unclosed_comment",
+            RepairKind::ControlChar => "control_char",
+            RepairKind::MissingComma => "missing_comma",
+            RepairKind::MismatchedBracket => "mismatched_bracket",
+            RepairKind::NormalizedDigits => "normalized_digits",
+            RepairKind::LineContinuation => "line_continuation",
+            RepairKind::TruncatedNumber => "truncated_number",
+            RepairKind::SkippedHtmlTag => "skipped_html_tag",
+            RepairKind::Unstringified => "unstringified",
+            RepairKind::StrippedEmphasis => "stripped_emphasis",
+            RepairKind::SkippedGarbage => "skipped_garbage",
+            RepairKind::KeySeparator => "key_separator",
+            RepairKind::FieldFallback => "field_fallback",
+        }
+    }
+}
+
+/// 一条修复记录：输入中的字节偏移、类别、涉及的原文或补全内容（跳过的前后缀只记字节数）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair {
+    pub offset: usize,
+    pub kind: RepairKind,
+    pub detail: String,
+}
+
+/// `capture_comments` 下保留的一条注释：起始符所在的字节偏移与去掉起止符、首尾空白后的正文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub offset: usize,
+    pub text: String,
+}
+
+/// 成功修复的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repaired {
+    pub value: Json,
+    /// 按偏移排序的修复记录
+    pub repairs: Vec<Repair>,
+    /// 按偏移排序的注释，未开启 `capture_comments` 时为空
+    pub comments: Vec<Comment>,
+}
+
+impl Repaired {
+    /// 输入是否完整：没有任何容器、字符串或数字是在 EOF 处被截断后补全的
+    pub fn was_complete(&self) -> bool {
+        !self.repairs.iter().any(|r| {
+            matches!(
+                r.kind,
+                RepairKind::ClosedContainer
+                    | RepairKind::ClosedString
+                    | RepairKind::TruncatedNumber
+            )
+        })
+    }
+}
+
+/// 不物化 Python 对象的修复概况，用作流水线里的廉价预检
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RepairReport {
+    pub valid: bool,
+    /// 有内容是在 EOF 处补全的，即 `was_complete` 的反面
+    pub truncated: bool,
+    pub depth: usize,
+    pub value_count: usize,
+}
+
+impl RepairReport {
+    /// 无法修复时各项均为默认值（`valid` 为 false）
+    pub fn scan(json_str: &str, opts: &RepairOptions) -> Self {
+        match repair_to_value(json_str, opts) {
+            Ok(repaired) => RepairReport {
+                valid: true,
+                truncated: !repaired.was_complete(),
+                depth: repaired.value.depth(),
+                value_count: repaired.value.value_count(),
+            },
+            Err(_) => RepairReport::default(),
+        }
+    }
+}
+
+/// 一批输入的修复统计，用来比较不同模型或提示词产出的 JSON 有多脏
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BatchStats {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 修复成功、但有内容是在 EOF 处补全的输入个数
+    pub truncated: usize,
+    /// 每类修复出现过的输入个数，同一输入里出现多次只算一次
+    pub kinds: HashMap<RepairKind, usize>,
+}
+
+impl BatchStats {
+    /// 多线程修复每个输入并汇总，全程不需要 GIL
+    pub fn collect(inputs: &[&str], opts: &RepairOptions) -> Self {
+        let mut stats = BatchStats {
+            total: inputs.len(),
+            ..Default::default()
+        };
+        for result in repair_to_value_batch(inputs, opts) {
+            match result {
+                Ok(repaired) => stats.add(&repaired),
+                Err(_) => stats.failed += 1,
+            }
+        }
+        stats
+    }
+
+    fn add(&mut self, repaired: &Repaired) {
+        self.succeeded += 1;
+        if !repaired.was_complete() {
+            self.truncated += 1;
+        }
+        let mut seen = Vec::new();
+        for repair in &repaired.repairs {
+            if !seen.contains(&repair.kind) {
+                seen.push(repair.kind);
+                *self.kinds.entry(repair.kind).or_default() += 1;
+            }
+        }
+    }
+}
+
+/// 流式续读点：某个容器里读完一个成员、游标停在其后的逗号上。
+/// 文本只会追加，逗号之前的内容不会再变，下次从这里接着解析即可
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    /// 逗号的偏移
+    comma: usize,
+    /// 逗号所在处从外到内各层已打开容器的闭括号
+    closers: Vec<char>,
+    /// 各层已读完的成员数；外层正在读的那个成员（装着内层容器）不计入
+    members: Vec<usize>,
+    /// 已计入 `max_items` 的个数
+    items: usize,
+}
 
 struct Parser<'a> {
-    chars: Peekable<Chars<'a>>,
+    src: &'a str,
+    pos: usize,
+    opts: &'a RepairOptions,
+    repairs: Vec<Repair>,
+    comments: Vec<Comment>,
+    /// 从外到内各层已打开容器的闭括号，长度即嵌套深度
+    closers: Vec<char>,
+    /// 已解析的值与 key 的个数，用于 `max_items`
+    items: usize,
+    /// 与 `closers` 对应的各层已读完的成员数；只有流式解析才跟踪，其余为 None
+    members: Option<Vec<usize>>,
+    /// 最近一个容器逗号处的续读点
+    checkpoint: Option<Checkpoint>,
+    /// 下一个进入的容器从“刚读完一个成员”处续读：不再读开括号，直接看分隔符
+    resumed: bool,
 }
 
 impl<'a> Parser<'a> {
-    fn new(source: &'a str) -> Self {
+    fn new(src: &'a str, pos: usize, opts: &'a RepairOptions) -> Self {
         Parser {
-            chars: source.chars().peekable(),
+            src,
+            pos,
+            opts,
+            repairs: Vec::new(),
+            comments: Vec::new(),
+            closers: Vec::new(),
+            items: 0,
+            members: None,
+            checkpoint: None,
+            resumed: false,
+        }
+    }
+
+    /// 游标之后尚未读取的输入
+    #[inline]
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    #[inline]
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn error(&self, message: impl Into<String>) -> RepairError {
+        // 停在输入末尾的错误说明输入被截断，而不是写错
+        let kind = if self.pos >= self.src.len() {
+            ErrorKind::Truncated
+        } else {
+            ErrorKind::Syntax
+        };
+        RepairError {
+            kind,
+            offset: self.pos.min(self.src.len()),
+            message: message.into(),
+        }
+    }
+
+    fn record(&mut self, offset: usize, kind: RepairKind, detail: impl Into<String>) {
+        self.repairs.push(Repair {
+            offset,
+            kind,
+            detail: detail.into(),
+        });
+    }
+
+    /// 推测解析前的位置与已有记录数，配合 `discard_since` 撤销推测期间的修复与注释
+    fn mark(&self) -> (usize, usize, usize) {
+        (self.pos, self.repairs.len(), self.comments.len())
+    }
+
+    fn discard_since(&mut self, (_, repairs, comments): (usize, usize, usize)) {
+        self.repairs.truncate(repairs);
+        self.comments.truncate(comments);
+    }
+
+    fn rewind(&mut self, mark: (usize, usize, usize)) {
+        self.pos = mark.0;
+        self.discard_since(mark);
+    }
+
+    /// 紧接上一个值再解析一个；失败时回到尝试前的状态
+    fn parse_next_value(&mut self) -> Option<Json> {
+        let mark = self.mark();
+        match self.next_value() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                self.rewind(mark);
+                None
+            }
+        }
+    }
+
+    /// 跳过上一个值之后的逗号、空白与注释，再解析一个值
+    fn next_value(&mut self) -> Result<Json, RepairError> {
+        self.skip_whitespace_and_comments()?;
+        if self.peek() == Some(',') {
+            self.bump();
+        }
+        self.skip_whitespace_and_comments()?;
+        self.skip_stray_closers()?;
+        self.parse_value()
+    }
+
+    /// 解析成功后收尾：记录被忽略的尾随文本，交出按偏移排序的修复记录
+    fn finish(self, value: Json) -> Repaired {
+        let (repairs, comments) = self.into_records();
+        Repaired {
+            value,
+            repairs,
+            comments,
+        }
+    }
+
+    fn into_records(mut self) -> (Vec<Repair>, Vec<Comment>) {
+        // 值已解析完，`unclosed_comments = Error` 下未闭合的尾随注释归入被忽略的后缀
+        let _ = self.skip_whitespace_and_comments();
+        let rest = self.src.len() - self.pos;
+        if rest > 0 {
+            self.record(self.pos, RepairKind::IgnoredSuffix, format!("{rest} bytes"));
         }
+        // 尾随逗号在容器闭合时才记录，按偏移排回输入顺序
+        self.repairs.sort_by_key(|r| r.offset);
+        (self.repairs, self.comments)
     }
 
-    fn skip_whitespace_and_comments(&mut self) {
+    fn skip_whitespace_and_comments(&mut self) -> Result<(), RepairError> {
         loop {
-            let Some(&ch) = self.chars.peek() else {
-                return;
+            let Some(ch) = self.peek() else {
+                return Ok(());
             };
+            let start = self.pos;
 
-            if ch.is_whitespace() {
-                self.chars.next();
+            if ch.is_whitespace() || (self.opts.skip_zero_width && is_zero_width(ch)) {
+                self.bump();
                 continue;
             }
 
-            if ch == '#' {
+            if let Some(line) = self.separator_line() {
                 self.consume_until_newline();
+                self.record(start, RepairKind::SkippedSeparator, line);
+                continue;
+            }
+
+            if let Some(tag) = self.html_tag() {
+                self.pos += tag.len();
+                self.record(start, RepairKind::SkippedHtmlTag, tag);
+                continue;
+            }
+
+            let styles = self.opts.comment_styles;
+            if ch == '#' && styles.contains(CommentStyles::HASH) {
+                self.line_comment(start, "#");
+                continue;
+            }
+
+            if ch == '-' && styles.contains(CommentStyles::DASH) && self.rest().starts_with("--") {
+                self.line_comment(start, "--");
                 continue;
             }
 
             if ch == '/' {
-                self.chars.next(); // consume '/'
-                match self.chars.peek() {
-                    Some('/') => {
-                        self.consume_until_newline();
+                self.bump(); // consume '/'
+                match self.peek() {
+                    Some('/') if styles.contains(CommentStyles::SLASH) => {
+                        self.line_comment(start, "//");
                         continue;
                     }
-                    Some('*') => {
-                        self.chars.next(); // consume '*'
-                        self.consume_block_comment();
+                    Some('*') if styles.contains(CommentStyles::BLOCK) => {
+                        self.bump(); // consume '*'
+                        self.consume_block_comment(start, ("/*", "*/"))?;
+                        self.record(start, RepairKind::SkippedComment, "/*");
                         continue;
                     }
                     _ => {
-                        return;
+                        // 单独的 '/'（如 `3/4`）或未启用的注释语法，退回去交给调用方报错
+                        self.pos = start;
+                        return Ok(());
                     }
                 }
             }
 
+            if ch == '<' && styles.contains(CommentStyles::HTML) && self.rest().starts_with("<!--")
+            {
+                self.pos += "<!--".len();
+                self.consume_block_comment(start, ("<!--", "-->"))?;
+                self.record(start, RepairKind::SkippedComment, "<!--");
+                continue;
+            }
+
             // Markdown-style fenced code blocks: ```json ... ```
             if ch == '`' {
-                self.chars.next(); // 1st
-                if let Some('`') = self.chars.peek() {
-                    self.chars.next(); // 2nd
-                    if let Some('`') = self.chars.peek() {
-                        self.chars.next(); // 3rd
+                self.bump(); // 1st
+                if let Some('`') = self.peek() {
+                    self.bump(); // 2nd
+                    if let Some('`') = self.peek() {
+                        self.bump(); // 3rd
                         self.consume_fence_block();
+                        self.record(start, RepairKind::SkippedFence, "```");
                         continue;
                     }
                 }
-                return;
+                self.pos = start;
+                return Ok(());
             }
 
-            return;
+            return Ok(());
+        }
+    }
+
+    /// 开启 `skip_separators` 且当前行只由 3 个以上的 `-` 或 `=` 组成（两端可有空白）时返回该行；
+    /// `---5` 之类不算
+    fn separator_line(&self) -> Option<&'a str> {
+        if !self.opts.skip_separators {
+            return None;
+        }
+        let before = &self.src[..self.pos];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        if !before[line_start..].chars().all(|c| c == ' ' || c == '\t') {
+            return None;
+        }
+        let rest = self.rest();
+        let line = rest[..rest.find('\n').unwrap_or(rest.len())].trim_end();
+        let first @ ('-' | '=') = line.chars().next()? else {
+            return None;
+        };
+        (line.len() >= 3 && line.chars().all(|c| c == first)).then_some(line)
+    }
+
+    /// 开启 `strip_html` 且游标处是形如 `<name ...>` / `</name>` / `<br/>` 的标签时返回它；
+    /// 只认标签名以字母开头、属性里没有 `<` 的简单标签，不做真正的 HTML 解析
+    fn html_tag(&self) -> Option<&'a str> {
+        if !self.opts.strip_html {
+            return None;
+        }
+        let rest = self.rest();
+        let body = rest.strip_prefix('<')?;
+        let name = body.strip_prefix('/').unwrap_or(body);
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let name_len = name
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | ':' | '_')))
+            .unwrap_or(name.len());
+        let after_name = &name[name_len..];
+        if !after_name.starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+            return None;
+        }
+        let close = after_name.find(['<', '>'])?;
+        if after_name.as_bytes()[close] == b'<' {
+            return None;
+        }
+        let tag_len = rest.len() - after_name.len() + close + 1;
+        Some(&rest[..tag_len])
+    }
+
+    /// 跳过从 `start` 开始、以 `opener` 起头的行注释
+    fn line_comment(&mut self, start: usize, opener: &str) {
+        self.consume_until_newline();
+        self.record(start, RepairKind::SkippedComment, opener);
+        self.capture_comment(start, &self.src[start + opener.len()..self.pos]);
+    }
+
+    fn capture_comment(&mut self, offset: usize, body: &str) {
+        if self.opts.capture_comments {
+            self.comments.push(Comment {
+                offset,
+                text: body.trim().to_string(),
+            });
         }
     }
 
     fn consume_until_newline(&mut self) {
-        while let Some(ch) = self.chars.next() {
+        while let Some(ch) = self.bump() {
             if ch == '\n' {
                 break;
             }
         }
     }
 
-    fn consume_block_comment(&mut self) {
-        let mut last_was_star = false;
-        while let Some(ch) = self.chars.next() {
-            if last_was_star && ch == '/' {
-                return;
-            }
-            last_was_star = ch == '*';
+    /// 起始符已消费，跳到结束符之后；没有闭合时按 `unclosed_comments` 处理，
+    /// `Error` 下退回 `start` 报错
+    fn consume_block_comment(
+        &mut self,
+        start: usize,
+        (opener, closer): (&str, &str),
+    ) -> Result<(), RepairError> {
+        let rest = self.rest();
+        if let Some(end) = rest.find(closer) {
+            self.capture_comment(start, &rest[..end]);
+            self.pos += end + closer.len();
+            return Ok(());
         }
+        let end = match self.opts.unclosed_comments {
+            UnclosedComments::Error => {
+                self.pos = start;
+                return Err(self.error("Unterminated block comment"));
+            }
+            UnclosedComments::Consume => rest.len(),
+            // 停在之后第一个 `}` / `]` 前，让外层容器照常闭合
+            UnclosedComments::Recover => rest.find(['}', ']']).unwrap_or(rest.len()),
+        };
+        self.capture_comment(start, &rest[..end]);
+        self.pos += end;
+        self.record(start, RepairKind::UnclosedComment, opener);
+        Ok(())
     }
 
     fn consume_fence_block(&mut self) {
         // Skip until the next ``` or EOF. We don't try to interpret the language tag.
         let mut backtick_count = 0usize;
-        while let Some(ch) = self.chars.next() {
+        while let Some(ch) = self.bump() {
             if ch == '`' {
                 backtick_count += 1;
                 if backtick_count == 3 {
@@ -100,172 +578,628 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_value(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        self.skip_whitespace_and_comments();
+    /// 解析一个值并建成 `Json`
+    fn parse_value(&mut self) -> Result<Json, RepairError> {
+        let mut builder = JsonBuilder::default();
+        self.parse_value_into(&mut builder)?;
+        Ok(builder.finish())
+    }
+
+    /// 解析一个值，边扫描边把事件交给 `sink`
+    fn parse_value_into<S: ValueSink>(&mut self, sink: &mut S) -> Result<(), RepairError> {
+        self.skip_whitespace_and_comments()?;
 
-        let Some(&ch) = self.chars.peek() else {
-            return Err(PyValueError::new_err(
-                "Unexpected end of input while expecting a value",
-            ));
+        let Some(ch) = self.peek() else {
+            return Err(self.error("Unexpected end of input while expecting a value"));
         };
 
         match ch {
-            '{' => self.parse_object(py),
-            '[' => self.parse_array(py),
-            '"' | '\'' => self.parse_string(py),
+            '{' => self.nested('}', sink, Self::parse_object),
+            '[' => self.nested(']', sink, Self::parse_array),
+            '"' | '\'' => {
+                self.count_item()?;
+                let start = self.pos;
+                let text = self.parse_string()?;
+                // 只有 `auto_unstringify` 重新解析出的容器不是叶子，重放即可
+                self.unstringify(start, text).drive(sink);
+                Ok(())
+            }
+            _ => {
+                self.count_item()?;
+                let leaf = self.parse_scalar(ch)?;
+                sink.scalar(leaf);
+                Ok(())
+            }
+        }
+    }
+
+    /// 解析以 `ch` 开头的字面量或数字
+    fn parse_scalar(&mut self, ch: char) -> Result<Json, RepairError> {
+        let leaf = match ch {
             't' | 'T' => {
-                if self.match_literal("true") {
-                    Ok(true.into_py(py))
+                if self.match_literal("true", true) {
+                    Json::Bool(true)
                 } else {
-                    Err(PyValueError::new_err("Invalid boolean literal"))
+                    return Err(self.error("Invalid boolean literal"));
                 }
             }
             'f' | 'F' => {
-                if self.match_literal("false") {
-                    Ok(false.into_py(py))
+                if self.match_literal("false", true) {
+                    Json::Bool(false)
                 } else {
-                    Err(PyValueError::new_err("Invalid boolean literal"))
+                    return Err(self.error("Invalid boolean literal"));
                 }
             }
             'n' | 'N' => {
                 // Support both "null" and "None"
-                if self.match_literal("null") {
-                    Ok(py.None())
-                } else if self.match_literal("none") {
-                    Ok(py.None())
-                } else if self.match_literal("nan") {
-                    Ok(f64::NAN.into_py(py))
+                if self.match_literal("null", true) || self.match_literal("none", true) {
+                    Json::Null
+                } else if self.match_literal("nan", true) {
+                    Json::Float(f64::NAN)
                 } else {
-                    Err(PyValueError::new_err("Invalid null/None/NaN literal"))
+                    return Err(self.error("Invalid null/None/NaN literal"));
                 }
             }
             'i' | 'I' => {
-                if self.match_literal("infinity") {
-                    Ok(f64::INFINITY.into_py(py))
-                } else if self.match_literal("inf") {
-                    Ok(f64::INFINITY.into_py(py))
+                if self.match_literal("infinity", true) || self.match_literal("inf", true) {
+                    Json::Float(f64::INFINITY)
                 } else {
-                    Err(PyValueError::new_err("Invalid infinity literal"))
+                    return Err(self.error("Invalid infinity literal"));
                 }
             }
-            '-' | '+' | '0'..='9' | '.' => self.parse_number(py),
-            _ => Err(PyValueError::new_err(format!(
-                "Unexpected character {ch:?} while parsing value"
-            ))),
+            'u' | 'U' if self.opts.js_literals => {
+                if self.match_literal("undefined", true) {
+                    Json::Null
+                } else {
+                    return Err(self.error("Invalid undefined literal"));
+                }
+            }
+            '-' | '+' | '0'..='9' | '.' => self.parse_number()?,
+            c if self.normalized_number_char(c).is_some() => self.parse_number()?,
+            _ => return Err(self.error(format!("Unexpected character {ch:?} while parsing value"))),
+        };
+        Ok(leaf)
+    }
+
+    /// 记一个标量值或 key；超过 `max_items` 时报错，避免很宽的输入占满内存
+    fn count_item(&mut self) -> Result<(), RepairError> {
+        self.items += 1;
+        match self.opts.max_items {
+            Some(limit) if self.items > limit => Err(RepairError {
+                kind: ErrorKind::TooLarge,
+                ..self.error(format!(
+                    "Input has more than {limit} values and keys, exceeding max_items={limit}"
+                ))
+            }),
+            _ => Ok(()),
         }
     }
 
-    fn parse_object(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        let dict = PyDict::new(py);
-        self.chars.next(); // skip '{'
+    /// 进入一层以 `closer` 结束的容器；超过 `max_depth` 时报错而不是继续递归
+    fn nested<S: ValueSink>(
+        &mut self,
+        closer: char,
+        sink: &mut S,
+        parse: fn(&mut Self, &mut S) -> Result<(), RepairError>,
+    ) -> Result<(), RepairError> {
+        let limit = self.opts.max_depth.unwrap_or(MAX_DEPTH);
+        if self.closers.len() >= limit {
+            return Err(RepairError {
+                kind: ErrorKind::Depth,
+                ..self.error(format!("Maximum nesting depth {limit} exceeded"))
+            });
+        }
+        self.closers.push(closer);
+        if let Some(members) = &mut self.members {
+            members.push(0);
+        }
+        let result = parse(self, sink);
+        self.closers.pop();
+        if let Some(members) = &mut self.members {
+            members.pop();
+        }
+        result
+    }
 
-        loop {
-            self.skip_whitespace_and_comments();
-            let ch = self.chars.peek().copied();
+    /// 当前容器读完一个成员
+    fn end_member(&mut self) {
+        if let Some(count) = self.members.as_mut().and_then(|members| members.last_mut()) {
+            *count += 1;
+        }
+    }
 
-            if ch.is_none() || ch == Some('}') {
-                if ch == Some('}') {
-                    self.chars.next();
-                }
-                return Ok(dict.into());
-            }
+    /// 游标在成员之后的逗号上：记下续读点
+    fn save_checkpoint(&mut self) {
+        if let Some(members) = &self.members {
+            self.checkpoint = Some(Checkpoint {
+                comma: self.pos,
+                closers: self.closers.clone(),
+                members: members.clone(),
+                items: self.items,
+            });
+        }
+    }
 
-            if ch == Some(',') {
-                self.chars.next();
-                continue;
+    /// 从续读点接着解析：游标在 `checkpoint` 的逗号上，`closers` / `members` 已按它恢复。
+    /// 最内层容器按刚读完一个成员的状态继续，闭合后逐层回到外层容器的同一状态
+    fn resume<S: ValueSink>(&mut self, sink: &mut S) -> Result<(), RepairError> {
+        while let Some(&closer) = self.closers.last() {
+            self.resumed = true;
+            if closer == '}' {
+                self.members_into(false, sink)?;
+            } else {
+                self.parse_array(sink)?;
             }
-
-            // Parse Key
-            // STRICT: Keys MUST be strings (quoted)
-            let ch = self.chars.peek().copied();
-            if ch != Some('"') && ch != Some('\'') {
-                return Err(PyValueError::new_err("Object keys must be strings"));
+            self.closers.pop();
+            if let Some(members) = &mut self.members {
+                members.pop();
             }
+            self.end_member();
+        }
+        Ok(())
+    }
 
-            let key_obj = self.parse_string(py)?;
-            if key_obj.downcast::<PyString>(py).is_err() {
-                return Err(PyValueError::new_err("Object keys must be strings"));
-            }
+    /// `lenient_brackets` 下，游标处是另一种闭括号时结束当前容器（其闭括号为 `own`）。
+    /// 外层有容器正等着这个括号时，视为当前容器漏了闭括号，不消费它，留给外层；
+    /// 否则视为写错了括号，直接消费
+    fn close_mismatched(&mut self, own: char) -> bool {
+        let other = if own == ']' { '}' } else { ']' };
+        if !self.opts.lenient_brackets || self.peek() != Some(other) {
+            return false;
+        }
+        let enclosing = &self.closers[..self.closers.len().saturating_sub(1)];
+        let outer_waits = enclosing.contains(&other);
+        self.record(self.pos, RepairKind::MismatchedBracket, own);
+        if !outer_waits {
+            self.bump();
+        }
+        true
+    }
+
+    fn parse_object<S: ValueSink>(&mut self, sink: &mut S) -> Result<(), RepairError> {
+        self.bump(); // skip '{'
+        self.parse_members(false, sink)
+    }
+
+    /// `sort_keys` 要看到全部成员才能排序：先建成 `Json`，排好后再重放给 `sink`。
+    /// 稳定排序保留重复 key 的先后，物化时仍是后者覆盖前者
+    fn parse_members<S: ValueSink>(
+        &mut self,
+        wrapped: bool,
+        sink: &mut S,
+    ) -> Result<(), RepairError> {
+        if !self.opts.sort_keys {
+            return self.members_into(wrapped, sink);
+        }
+        let mut builder = JsonBuilder::default();
+        self.members_into(wrapped, &mut builder)?;
+        if let Json::Object(mut members) = builder.finish() {
+            members.sort_by(|a, b| a.0.cmp(&b.0));
+            Json::Object(members).drive(sink);
+        }
+        Ok(())
+    }
+
+    /// 解析对象成员直到闭合；`wrapped` 表示顶层省略了花括号的对象体，
+    /// 此时只在 EOF 结束，并允许裸标识符作 key
+    fn members_into<S: ValueSink>(
+        &mut self,
+        wrapped: bool,
+        sink: &mut S,
+    ) -> Result<(), RepairError> {
+        let closer = if wrapped { None } else { Some('}') };
+        let mut trailing_comma = None;
+        // 流式续读时已经读过左括号和前面的成员，直接从成员之后的分隔符开始
+        let mut resumed = std::mem::take(&mut self.resumed);
+        if !resumed {
+            sink.begin_object();
+        }
 
-            self.skip_whitespace_and_comments();
-            match self.chars.peek().copied() {
-                Some(':') => {
-                    self.chars.next();
+        loop {
+            if !resumed {
+                self.skip_whitespace_and_comments()?;
+                let ch = self.peek();
+
+                if ch.is_none() || ch == closer {
+                    if let Some(offset) = trailing_comma {
+                        self.record(offset, RepairKind::ExtraComma, ",");
+                    }
+                    if ch.is_some() {
+                        self.bump();
+                    } else if !wrapped {
+                        self.record(self.pos, RepairKind::ClosedContainer, "}");
+                    }
+                    sink.end_object();
+                    return Ok(());
                 }
-                _ => {
-                    return Err(PyValueError::new_err("Expected ':' after object key"));
+                if !wrapped && self.close_mismatched('}') {
+                    if let Some(offset) = trailing_comma {
+                        self.record(offset, RepairKind::ExtraComma, ",");
+                    }
+                    sink.end_object();
+                    return Ok(());
                 }
-            }
 
-            let value = self.parse_value(py)?;
-            dict.set_item(&key_obj, value)?;
+                if ch == Some(',') {
+                    self.skip_extra_comma()?;
+                    trailing_comma = None;
+                    continue;
+                }
 
-            self.skip_whitespace_and_comments();
-            let ch = self.chars.peek().copied();
+                let key = self.member_key(wrapped)?;
+                self.count_item()?;
+                let has_colon = self.key_separator()?;
+                match self.missing_value(closer) {
+                    Some(value) => {
+                        sink.key(key);
+                        sink.scalar(value);
+                    }
+                    None if has_colon => {
+                        sink.key(key);
+                        self.parse_value_into(sink)?;
+                    }
+                    None => return Err(self.error("Expected ':' after object key")),
+                }
+                self.end_member();
+            }
+            resumed = false;
+
+            self.skip_whitespace_and_comments()?;
+            let ch = self.peek();
             if ch == Some(',') {
-                self.chars.next();
+                trailing_comma = Some(self.pos);
+                self.save_checkpoint();
+                self.bump();
                 continue;
             }
-            if ch == Some('}') {
-                self.chars.next();
-                return Ok(dict.into_py(py));
+            if ch.is_none() || ch == closer {
+                if ch.is_some() {
+                    self.bump();
+                } else if !wrapped {
+                    self.record(self.pos, RepairKind::ClosedContainer, "}");
+                }
+                sink.end_object();
+                return Ok(());
             }
-            if ch.is_none() {
-                return Ok(dict.into_py(py));
+            if !wrapped && self.close_mismatched('}') {
+                sink.end_object();
+                return Ok(());
+            }
+            if self.missing_comma() {
+                continue;
             }
             // If we are here, we expected ',' or '}' but got something else.
-            return Err(PyValueError::new_err("Expected ',' or '}' in object"));
+            return Err(self.error("Expected ',' or '}' in object"));
+        }
+    }
+
+    /// `strip_markdown_emphasis` 下游标处 `*` / `_` 串的长度，未开启时为 0
+    fn emphasis_len(&self) -> usize {
+        if !self.opts.strip_markdown_emphasis {
+            return 0;
         }
+        let rest = self.rest();
+        rest.len() - rest.trim_start_matches(['*', '_']).len()
     }
 
-    fn parse_array(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        let list = PyList::empty(py);
-        self.chars.next(); // skip '['
+    /// 跳过 key 引号前的强调符；后面不是引号时不动，以免吞掉 `_id` 这类裸 key
+    fn skip_emphasis_before_key(&mut self) -> bool {
+        let len = self.emphasis_len();
+        if len == 0 || !self.rest()[len..].starts_with(['"', '\'']) {
+            return false;
+        }
+        self.skip_emphasis(len);
+        true
+    }
 
-        loop {
-            self.skip_whitespace_and_comments();
-            let ch = self.chars.peek().copied();
+    fn skip_emphasis(&mut self, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let run = &self.rest()[..len];
+        self.record(self.pos, RepairKind::StrippedEmphasis, run);
+        self.pos += len;
+    }
 
-            if ch.is_none() || ch == Some(']') {
-                if ch == Some(']') {
-                    self.chars.next();
-                }
-                return Ok(list.into_py(py));
+    /// 容器开头或紧跟另一个逗号的逗号：按 `extra_commas` 跳过或报错
+    fn skip_extra_comma(&mut self) -> Result<(), RepairError> {
+        if self.opts.extra_commas == ExtraCommas::Error {
+            return Err(self.error("Unexpected extra ','"));
+        }
+        self.record(self.pos, RepairKind::ExtraComma, ",");
+        self.bump();
+        Ok(())
+    }
+
+    /// `lenient_commas` 下，上一个成员之后紧跟着另一个值（或 key）的开头时补一个逗号
+    fn missing_comma(&mut self) -> bool {
+        if !self.opts.lenient_commas {
+            return false;
+        }
+        let starts_next = self
+            .peek()
+            .is_some_and(|ch| matches!(ch, '{' | '[' | '"' | '\'' | '-' | '+' | '.' | '0'..='9'))
+            || self.peek_bare_key().is_some();
+        if starts_next {
+            self.record(self.pos, RepairKind::MissingComma, ",");
+        }
+        starts_next
+    }
+
+    /// 非字符串 key：`coerce_keys` 下把数字/布尔/null 转成字符串（同 JS 对象字面量），否则报错并带上该 key
+    fn non_string_key(&mut self) -> Result<String, RepairError> {
+        let start = self.pos;
+        let Some(key) = self.parse_scalar_key() else {
+            let found = self
+                .peek()
+                .map_or("end of input".to_string(), |ch| format!("{ch:?}"));
+            return Err(self.error(format!("Object keys must be strings, got {found}")));
+        };
+        if !self.opts.coerce_keys {
+            let written = &self.src[start..self.pos];
+            self.pos = start;
+            return Err(self.error(format!("Object keys must be strings, got {written}")));
+        }
+        self.record(start, RepairKind::CoercedKey, key.as_str());
+        Ok(key)
+    }
+
+    /// 把标量 key 解析成字符串形式：数字保留原文，布尔/null 用小写规范名；不是标量时不消耗输入
+    fn parse_scalar_key(&mut self) -> Option<String> {
+        let mark = self.mark();
+        let checkpoint = self.checkpoint.take();
+        let start = self.pos;
+        let key = match self.parse_value() {
+            Ok(Json::Bool(b)) => Some(b.to_string()),
+            Ok(Json::Null) => Some("null".to_string()),
+            Ok(Json::Int(_) | Json::BigInt { .. } | Json::Float(_)) => {
+                Some(self.src[start..self.pos].to_string())
             }
-            if ch == Some(',') {
-                self.chars.next();
-                continue;
+            _ => None,
+        };
+        // 解析 `True` 等产生的修复记录不算数，key 只记一条 coerced_key；
+        // 试探着读成容器时其中逗号处的续读点也作废
+        match key {
+            Some(_) => self.discard_since(mark),
+            None => self.rewind(mark),
+        }
+        self.checkpoint = checkpoint;
+        key
+    }
+
+    /// `lenient_missing_values` 下，key 后直接遇到 `,`、右括号或 EOF 时补 null
+    fn missing_value(&mut self, closer: Option<char>) -> Option<Json> {
+        let ch = self.peek();
+        if !self.opts.lenient_missing_values || !(ch.is_none() || ch == Some(',') || ch == closer) {
+            return None;
+        }
+        self.record(self.pos, RepairKind::MissingValue, "null");
+        Some(Json::Null)
+    }
+
+    /// 当前位置的裸标识符 key（`[A-Za-z_$][A-Za-z0-9_$-]*`），不移动游标
+    fn peek_bare_key(&self) -> Option<&'a str> {
+        let rest = self.rest();
+        let first = rest.chars().next()?;
+        if !(first.is_ascii_alphabetic() || first == '_' || first == '$') {
+            return None;
+        }
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '-')))
+            .unwrap_or(rest.len());
+        Some(&rest[..len])
+    }
+
+    /// 顶层是否形如 `key: value, ...`（key 为引号字符串或裸标识符，分隔符见 `key_separator_len`），不移动游标
+    fn looks_like_members(&mut self) -> bool {
+        let mark = self.mark();
+        let has_key = match self.peek() {
+            Some('"' | '\'') => self.parse_string().is_ok(),
+            _ => match self.peek_bare_key() {
+                Some(key) => {
+                    self.pos += key.len();
+                    true
+                }
+                None => false,
+            },
+        };
+        let is_members = has_key
+            && self.skip_whitespace_and_comments().is_ok()
+            && self.key_separator_len().is_some();
+        self.rewind(mark);
+        is_members
+    }
+
+    /// 游标处 key 与值之间分隔符的长度：总是接受 `:`，`lenient_separators` 下另接受 `=>` 与单个 `=`
+    fn key_separator_len(&self) -> Option<usize> {
+        let rest = self.rest();
+        if rest.starts_with(':') {
+            return Some(1);
+        }
+        if !self.opts.lenient_separators {
+            return None;
+        }
+        if rest.starts_with("=>") {
+            Some(2)
+        } else if rest.starts_with('=') && !rest.starts_with("==") {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// 解析对象成员的 key；`wrapped` 下允许裸标识符
+    fn member_key(&mut self, wrapped: bool) -> Result<String, RepairError> {
+        // STRICT: Keys MUST be strings (quoted)
+        let bare_key = if wrapped { self.peek_bare_key() } else { None };
+        if let Some(key) = bare_key {
+            self.pos += key.len();
+            Ok(key.to_string())
+        } else if matches!(self.peek(), Some('"' | '\'')) || self.skip_emphasis_before_key() {
+            let key = self.parse_string()?;
+            self.skip_emphasis(self.emphasis_len());
+            Ok(key)
+        } else {
+            self.non_string_key()
+        }
+    }
+
+    /// 跳过 key 之后的分隔符及其两侧的空白与注释，返回是否有分隔符
+    fn key_separator(&mut self) -> Result<bool, RepairError> {
+        self.skip_whitespace_and_comments()?;
+        let has_colon = self.skip_key_separator();
+        if has_colon {
+            self.skip_whitespace_and_comments()?;
+        }
+        Ok(has_colon)
+    }
+
+    /// 只在紧跟 key 时调用，值位置上的 `=` 不会被当成分隔符
+    fn skip_key_separator(&mut self) -> bool {
+        let Some(len) = self.key_separator_len() else {
+            return false;
+        };
+        let separator = &self.rest()[..len];
+        if separator != ":" {
+            self.record(self.pos, RepairKind::KeySeparator, separator);
+        }
+        self.pos += len;
+        true
+    }
+
+    /// `skip_leading_garbage` 下跳过顶层值前拼接残留的 `}` / `]` / `,`（`}{"a":1}`），每段连续的记一条
+    fn skip_stray_closers(&mut self) -> Result<(), RepairError> {
+        if !self.opts.skip_leading_garbage {
+            return Ok(());
+        }
+        loop {
+            let rest = self.rest();
+            let len = rest.len() - rest.trim_start_matches(['}', ']', ',']).len();
+            if len == 0 {
+                return Ok(());
             }
+            self.record(self.pos, RepairKind::SkippedGarbage, &rest[..len]);
+            self.pos += len;
+            self.skip_whitespace_and_comments()?;
+        }
+    }
+
+    /// 顶层入口：`assume_object` 下把无花括号的对象体包成对象
+    fn parse_root<S: ValueSink>(&mut self, sink: &mut S) -> Result<(), RepairError> {
+        self.skip_whitespace_and_comments()?;
+        self.skip_stray_closers()?;
+        if self.opts.assume_object && self.looks_like_members() {
+            self.record(self.pos, RepairKind::WrappedObject, "{}");
+            self.parse_members(true, sink)?;
+            // 包出来的对象不在 `closers` 里，其中的续读点对不上层级
+            self.checkpoint = None;
+            return Ok(());
+        }
+        self.parse_value_into(sink)
+    }
+
+    fn parse_array<S: ValueSink>(&mut self, sink: &mut S) -> Result<(), RepairError> {
+        let mut trailing_comma = None;
+        // 同 `members_into`，续读时从元素之后的分隔符开始
+        let mut resumed = std::mem::take(&mut self.resumed);
+        if !resumed {
+            sink.begin_array();
+            self.bump(); // skip '['
+        }
+
+        loop {
+            if !resumed {
+                self.skip_whitespace_and_comments()?;
+                let ch = self.peek();
+
+                if ch.is_none() || ch == Some(']') {
+                    if let Some(offset) = trailing_comma {
+                        self.record(offset, RepairKind::ExtraComma, ",");
+                    }
+                    if ch == Some(']') {
+                        self.bump();
+                    } else {
+                        self.record(self.pos, RepairKind::ClosedContainer, "]");
+                    }
+                    sink.end_array();
+                    return Ok(());
+                }
+                if self.close_mismatched(']') {
+                    if let Some(offset) = trailing_comma {
+                        self.record(offset, RepairKind::ExtraComma, ",");
+                    }
+                    sink.end_array();
+                    return Ok(());
+                }
+                if ch == Some(',') {
+                    self.skip_extra_comma()?;
+                    trailing_comma = None;
+                    continue;
+                }
 
-            let value = self.parse_value(py)?;
-            list.append(value)?;
+                self.parse_value_into(sink)?;
+                self.end_member();
+            }
+            resumed = false;
 
-            self.skip_whitespace_and_comments();
-            let ch = self.chars.peek().copied();
+            self.skip_whitespace_and_comments()?;
+            let ch = self.peek();
             if ch == Some(',') {
-                self.chars.next();
+                trailing_comma = Some(self.pos);
+                self.save_checkpoint();
+                self.bump();
                 continue;
             }
             if ch == Some(']') {
-                self.chars.next();
-                return Ok(list.into_py(py));
+                self.bump();
+                sink.end_array();
+                return Ok(());
             }
             if ch.is_none() {
-                return Ok(list.into_py(py));
+                self.record(self.pos, RepairKind::ClosedContainer, "]");
+                sink.end_array();
+                return Ok(());
+            }
+            if self.close_mismatched(']') {
+                sink.end_array();
+                return Ok(());
+            }
+            if self.missing_comma() {
+                continue;
             }
-            return Err(PyValueError::new_err("Expected ',' or ']' in array"));
+            return Err(self.error("Expected ',' or ']' in array"));
         }
     }
 
-    fn parse_string(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        let quote = self.chars.next().ok_or_else(|| {
-            PyValueError::new_err("Unexpected end of input while starting string")
-        })?;
-        let mut out = String::new();
+    /// 快速路径：从游标起不含转义、控制字符的前缀整段复制，游标停在第一个需要逐字符处理的字节上
+    /// （引号、`\`、控制字符或 EOF）。停下的字节都是 ASCII，切片总在字符边界上
+    fn plain_string_prefix(&mut self, quote: char) -> String {
+        let rest = self.rest().as_bytes();
+        let stop = memchr::memchr2(quote as u8, b'\\', rest).unwrap_or(rest.len());
+        let plain = rest[..stop].iter().position(|&b| b < 0x20).unwrap_or(stop);
+        let prefix = self.rest()[..plain].to_string();
+        self.pos += plain;
+        prefix
+    }
 
-        while let Some(ch) = self.chars.next() {
+    fn parse_string(&mut self) -> Result<String, RepairError> {
+        let start = self.pos;
+        let quote = self
+            .bump()
+            .ok_or_else(|| self.error("Unexpected end of input while starting string"))?;
+        if quote == '\'' {
+            self.record(start, RepairKind::SingleQuote, "'");
+        }
+        let mut out = self.plain_string_prefix(quote);
+        if self.peek() == Some(quote) {
+            self.bump();
+            return Ok(out);
+        }
+
+        while let Some(ch) = self.bump() {
             if ch == '\\' {
-                let Some(esc) = self.chars.next() else {
+                let esc_start = self.pos - 1;
+                let Some(esc) = self.bump() else {
                     break;
                 };
                 match esc {
@@ -275,16 +1209,23 @@ impl<'a> Parser<'a> {
                     'b' => out.push('\x08'),
                     'f' => out.push('\x0c'),
                     '"' | '\'' | '\\' | '/' => out.push(esc),
+                    '\n' | '\r' if self.opts.line_continuations => {
+                        if esc == '\r' && self.peek() == Some('\n') {
+                            self.bump();
+                        }
+                        let raw = &self.src[esc_start..self.pos];
+                        self.record(esc_start, RepairKind::LineContinuation, raw);
+                    }
                     'u' => {
                         let mut buffer = ['\0'; 4];
                         let mut count = 0usize;
                         let mut valid_hex = true;
-                        for i in 0..4 {
-                            if let Some(h) = self.chars.next() {
+                        for slot in buffer.iter_mut() {
+                            if let Some(h) = self.bump() {
                                 if !h.is_ascii_hexdigit() {
                                     valid_hex = false;
                                 }
-                                buffer[i] = h;
+                                *slot = h;
                                 count += 1;
                             } else {
                                 valid_hex = false;
@@ -294,101 +1235,981 @@ impl<'a> Parser<'a> {
                         if valid_hex && count == 4 {
                             let s: String = buffer.iter().collect();
                             if let Ok(code) = u32::from_str_radix(&s, 16) {
-                                if let Some(c) = char::from_u32(code) {
+                                if let Some(c) = char::from_u32(self.combine_surrogate(code)) {
                                     out.push(c);
                                     continue;
                                 }
                             }
                         }
-                        out.push_str("\\u");
-                        for i in 0..count {
-                            out.push(buffer[i]);
-                        }
+                        self.bad_unicode_escape(&mut out, esc_start, &buffer[..count])?;
                     }
                     other => {
                         out.push('\\');
                         out.push(other);
+                        let raw = &self.src[esc_start..self.pos];
+                        self.record(esc_start, RepairKind::InvalidEscape, raw);
                     }
                 }
                 continue;
             }
 
             if ch == quote {
-                return Ok(PyString::new(py, &out).into_py(py));
+                return Ok(out);
+            }
+
+            if ch < '\u{20}' {
+                self.push_control_char(&mut out, ch);
+            } else {
+                out.push(ch);
             }
+        }
+
+        self.record(self.pos, RepairKind::ClosedString, quote);
+        Ok(out)
+    }
+
+    /// `auto_unstringify` 下，把整段是 JSON 对象/数组的字符串换成解析结果；
+    /// 需要跳过前后文本才能解析的不算，原样保留为字符串
+    fn unstringify(&mut self, start: usize, text: String) -> Json {
+        let trimmed = text.trim();
+        let bracketed = (trimmed.starts_with('{') && trimmed.ends_with('}'))
+            || (trimmed.starts_with('[') && trimmed.ends_with(']'));
+        if !self.opts.auto_unstringify || !bracketed {
+            return Json::Str(text);
+        }
+        let Ok(inner) = repair_to_value(trimmed, self.opts) else {
+            return Json::Str(text);
+        };
+        let exact = inner.repairs.iter().all(|r| {
+            !matches!(
+                r.kind,
+                RepairKind::SkippedPrefix | RepairKind::IgnoredSuffix | RepairKind::WrappedObject
+            )
+        });
+        if !exact {
+            return Json::Str(text);
+        }
+        let kind = if trimmed.starts_with('{') {
+            "object"
+        } else {
+            "array"
+        };
+        self.record(start, RepairKind::Unstringified, kind);
+        inner.value
+    }
 
-            out.push(ch);
+    /// 高代理项后紧跟 `\uDC00`–`\uDFFF` 时吃掉后者，合成一个码点；否则原样返回（单独的代理项交给调用方）
+    fn combine_surrogate(&mut self, high: u32) -> u32 {
+        if !(0xD800..0xDC00).contains(&high) {
+            return high;
+        }
+        let low = self
+            .rest()
+            .strip_prefix("\\u")
+            .and_then(|rest| rest.get(..4))
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .filter(|low| (0xDC00..0xE000).contains(low));
+        match low {
+            Some(low) => {
+                self.pos += 6;
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            None => high,
+        }
+    }
+
+    /// 按 `bad_unicode_policy` 处理无法解码的 `\u` 转义（含单独的代理项）；`digits` 是 `\u` 之后已读到的字符
+    fn bad_unicode_escape(
+        &mut self,
+        out: &mut String,
+        esc_start: usize,
+        digits: &[char],
+    ) -> Result<(), RepairError> {
+        let raw = &self.src[esc_start..self.pos];
+        match self.opts.bad_unicode_policy {
+            BadUnicodePolicy::Preserve => {
+                out.push_str("\\u");
+                out.extend(digits);
+            }
+            BadUnicodePolicy::Replace => out.push('\u{FFFD}'),
+            BadUnicodePolicy::Error if is_surrogate_escape(digits) => {
+                let message = format!("Unpaired surrogate in unicode escape {raw:?}");
+                self.pos = esc_start;
+                return Err(self.error(message));
+            }
+            BadUnicodePolicy::Error => {
+                let message = format!("Invalid unicode escape {raw:?}");
+                self.pos = esc_start;
+                return Err(self.error(message));
+            }
+        }
+        self.record(esc_start, RepairKind::InvalidEscape, raw);
+        Ok(())
+    }
+
+    /// 按 `control_char_policy` 写入字符串里的原始控制字符
+    fn push_control_char(&mut self, out: &mut String, ch: char) {
+        let offset = self.pos - 1;
+        match self.opts.control_char_policy {
+            ControlCharPolicy::Keep => out.push(ch),
+            ControlCharPolicy::Escape => {
+                let escaped = escape_control_char(ch);
+                out.push_str(&escaped);
+                self.record(offset, RepairKind::ControlChar, escaped);
+            }
+            ControlCharPolicy::Strip => {
+                self.record(offset, RepairKind::ControlChar, "");
+            }
         }
+    }
 
-        Ok(PyString::new(py, &out).into_py(py))
+    /// `raw_numbers` 下仍按常规解析以校验数字，但返回原文
+    fn parse_number(&mut self) -> Result<Json, RepairError> {
+        let start = self.pos;
+        let value = self.parse_number_value()?;
+        if self.opts.raw_numbers {
+            return Ok(Json::Str(self.src[start..self.pos].to_string()));
+        }
+        Ok(value)
     }
 
-    fn parse_number(&mut self, py: Python<'a>) -> PyResult<PyObject> {
-        let mut s = String::new();
-        while let Some(&ch) = self.chars.peek() {
+    fn parse_number_value(&mut self) -> Result<Json, RepairError> {
+        if self.opts.json5_numbers {
+            if let Some(value) = self.parse_radix_integer()? {
+                return Ok(value);
+            }
+        }
+
+        let start = self.pos;
+        let mut normalized = false;
+        while let Some(ch) = self.peek() {
             if ch.is_ascii_digit() || matches!(ch, '-' | '+' | '.' | 'e' | 'E') {
-                s.push(ch);
-                self.chars.next();
+                self.bump();
+            } else if self.normalized_number_char(ch).is_some() {
+                normalized = true;
+                self.bump();
             } else {
                 break;
             }
         }
+        let raw = &self.src[start..self.pos];
+        let s: Cow<str> = if normalized {
+            self.record(start, RepairKind::NormalizedDigits, raw);
+            raw.chars()
+                .map(|c| self.normalized_number_char(c).unwrap_or(c))
+                .collect::<String>()
+                .into()
+        } else {
+            raw.into()
+        };
+        let s = s.as_ref();
+
+        // 标准 JSON 不允许正号；指数里的 `e+3` 不受影响
+        if s.starts_with('+') && !self.opts.json5_numbers {
+            self.pos = start;
+            return Err(self.error(format!(
+                "Leading '+' is not allowed in number {s:?} (enable json5_numbers)"
+            )));
+        }
+        if self.pos == self.src.len() && s.ends_with(DANGLING_NUMBER_CHARS) {
+            return self.truncated_number(start, raw, s);
+        }
+        if has_bare_dot(s) && !self.opts.json5_numbers {
+            return Err(self.error(format!("Invalid number literal {s:?}")));
+        }
+        if self.opts.leading_zero_as_string && has_leading_zero(s) {
+            return Ok(Json::Str(s.to_string()));
+        }
 
         if s.contains('.') || s.contains('e') || s.contains('E') {
             if let Ok(f) = s.parse::<f64>() {
-                return Ok(f.into_py(py));
+                return Ok(self.float_or_whole(f));
             }
         } else if let Ok(i) = s.parse::<i64>() {
-            return Ok(i.into_py(py));
-        } else {
-            // Fallback: delegate big integers to Python's arbitrary-precision int
-            let builtins = py.import("builtins")?;
-            let py_int = builtins.getattr("int")?.call1((s.clone(),))?;
-            return Ok(py_int.into());
+            return Ok(Json::Int(i));
+        } else if is_decimal_integer(s) {
+            return Ok(self.big_integer(start, s.to_string(), 10));
+        }
+
+        Err(self.error(format!("Invalid number literal {s:?}")))
+    }
+
+    /// 按选项把非 ASCII 的数字字符映射成 ASCII：全角数字与符号、Unicode 减号；不认识时返回 None
+    fn normalized_number_char(&self, ch: char) -> Option<char> {
+        if let Some(ascii) = ascii_number_char(ch).filter(|_| self.opts.normalize_unicode_digits) {
+            return Some(ascii);
         }
+        ascii_minus(ch).filter(|_| self.opts.normalize_unicode_minus)
+    }
+
+    /// 在 EOF 处断掉的数字：去掉末尾悬空的 `. e E + -`，按已写出的部分取值；写过小数点的仍是 float
+    fn truncated_number(&mut self, start: usize, raw: &str, s: &str) -> Result<Json, RepairError> {
+        let complete = s.trim_end_matches(DANGLING_NUMBER_CHARS);
+        let salvageable = complete.ends_with(|c: char| c.is_ascii_digit())
+            && (self.opts.json5_numbers || !has_bare_dot(complete));
+        let value = if !salvageable {
+            None
+        } else if s.contains('.') || complete.contains(['e', 'E']) {
+            complete.parse::<f64>().ok().map(|f| self.float_or_whole(f))
+        } else if let Ok(i) = complete.parse::<i64>() {
+            Some(Json::Int(i))
+        } else {
+            Some(self.big_integer(start, complete.to_string(), 10))
+        };
+        let Some(value) = value else {
+            return Err(self.error(format!("Invalid number literal {s:?}")));
+        };
+        self.record(start, RepairKind::TruncatedNumber, raw);
+        Ok(value)
+    }
 
-        Err(PyValueError::new_err(format!(
-            "Invalid number literal {s:?}"
-        )))
+    /// `coerce_whole_floats` 下，整数值的浮点（i64 范围内）转成 int
+    fn float_or_whole(&self, f: f64) -> Json {
+        // i64::MAX as f64 即 2^63，本身已越界，所以上界用开区间
+        let in_range = f >= i64::MIN as f64 && f < i64::MAX as f64;
+        if self.opts.coerce_whole_floats && f.fract() == 0.0 && in_range {
+            Json::Int(f as i64)
+        } else {
+            Json::Float(f)
+        }
     }
 
-    fn match_literal(&mut self, expected: &str) -> bool {
-        let mut cursor = self.chars.clone();
-        for c in expected.chars() {
-            match cursor.next() {
-                Some(got) if got.to_ascii_lowercase() == c => {}
-                _ => return false,
+    /// 解析 `0x`/`0o`/`0b` 前缀整数；前缀不匹配时不消耗任何字符
+    fn parse_radix_integer(&mut self) -> Result<Option<Json>, RepairError> {
+        let start = self.pos;
+        let mut token = String::new();
+        if let Some(sign @ ('-' | '+')) = self.peek() {
+            token.push(sign);
+            self.bump();
+        }
+        if self.bump() != Some('0') {
+            self.pos = start;
+            return Ok(None);
+        }
+        let radix = match self.bump() {
+            Some('x' | 'X') => 16,
+            Some('o' | 'O') => 8,
+            Some('b' | 'B') => 2,
+            _ => {
+                self.pos = start;
+                return Ok(None);
             }
+        };
+
+        let sign_len = token.len();
+        while let Some(ch) = self.peek() {
+            if !ch.is_digit(radix) {
+                break;
+            }
+            token.push(ch);
+            self.bump();
+        }
+        if token.len() == sign_len {
+            return Err(self.error(format!("Missing digits after base-{radix} prefix")));
+        }
+
+        if let Ok(i) = i64::from_str_radix(&token, radix) {
+            return Ok(Some(Json::Int(i)));
+        }
+        Ok(Some(self.big_integer(start, token, radix)))
+    }
+
+    /// 超出 i64 的整数按 `bigint_mode` 表示；`start..pos` 是原始 token
+    fn big_integer(&self, start: usize, digits: String, radix: u32) -> Json {
+        match self.opts.bigint_mode {
+            // 交给 Python int(s, base)
+            BigIntMode::PythonInt => Json::BigInt { digits, radix },
+            BigIntMode::Float => Json::Float(integer_to_f64(&digits, radix)),
+            BigIntMode::String => Json::Str(self.src[start..self.pos].to_string()),
         }
-        // Only now advance the real iterator
-        for _ in 0..expected.len() {
-            self.chars.next();
+    }
+
+    /// 匹配字面量并前进，只返回是否匹配，映射成什么值由调用方决定。
+    ///
+    /// `ignore_case` 为假时要求与 `expected` 逐字节一致（`NaN` 不接受 `nan`）；
+    /// 其后紧跟标识符字符时（`trueValue`、`undefinedX`）不算匹配
+    fn match_literal(&mut self, expected: &str, ignore_case: bool) -> bool {
+        let rest = &self.src.as_bytes()[self.pos..];
+        let Some(head) = rest.get(..expected.len()) else {
+            return false;
+        };
+        let same = if ignore_case {
+            head.eq_ignore_ascii_case(expected.as_bytes())
+        } else {
+            head == expected.as_bytes()
+        };
+        if !same {
+            return false;
         }
+        let at_boundary = rest
+            .get(expected.len())
+            .is_none_or(|&b| !(b.is_ascii_alphanumeric() || b == b'_' || b == b'$'));
+        if !at_boundary {
+            return false;
+        }
+        let written = &self.src[self.pos..self.pos + expected.len()];
+        if !matches!(written, "true" | "false" | "null") {
+            self.record(self.pos, RepairKind::Literal, written);
+        }
+        self.pos += expected.len();
         true
     }
 }
 
-#[pyfunction]
-pub fn repair_json(py: Python<'_>, json_str: &str) -> PyResult<PyObject> {
-    // 1. Try direct parse first (fast path)
-    let mut parser = Parser::new(json_str);
-    if let Ok(res) = parser.parse_value(py) {
-        return Ok(res);
+/// `\u` 后的四位十六进制落在 U+D800–U+DFFF，即没有配对的代理项
+fn is_surrogate_escape(digits: &[char]) -> bool {
+    let hex: String = digits.iter().collect();
+    hex.len() == 4
+        && u32::from_str_radix(&hex, 16).is_ok_and(|code| (0xD800..0xE000).contains(&code))
+}
+
+/// 控制字符的 JSON 转义写法：常用的用短转义，其余用 `\u00XX`
+fn escape_control_char(ch: char) -> String {
+    match ch {
+        '\n' => "\\n".to_string(),
+        '\r' => "\\r".to_string(),
+        '\t' => "\\t".to_string(),
+        '\u{08}' => "\\b".to_string(),
+        '\u{0C}' => "\\f".to_string(),
+        c => format!("\\u{:04x}", c as u32),
     }
+}
+
+/// `char::is_whitespace` 不认、却常被复制进模型输出的不可见字符
+fn is_zero_width(ch: char) -> bool {
+    matches!(ch, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}')
+}
+
+/// `.5` / `5.` / `-.25`：小数点某一侧没有数字（f64 解析本身接受，严格模式需拒绝）
+fn has_bare_dot(token: &str) -> bool {
+    let mantissa = token.split(['e', 'E']).next().unwrap_or_default();
+    let Some(dot) = mantissa.find('.') else {
+        return false;
+    };
+    let bytes = mantissa.as_bytes();
+    let digit_before = dot > 0 && bytes[dot - 1].is_ascii_digit();
+    let digit_after = bytes.get(dot + 1).is_some_and(u8::is_ascii_digit);
+    !(digit_before && digit_after)
+}
+
+/// 带符号的整数数字串转 f64（已保证每位都合法），超大时得到 inf
+fn integer_to_f64(digits: &str, radix: u32) -> f64 {
+    let (negative, body) = match digits.strip_prefix('-') {
+        Some(body) => (true, body),
+        None => (false, digits.trim_start_matches('+')),
+    };
+    let magnitude = body.chars().fold(0.0, |acc, ch| {
+        acc * f64::from(radix) + f64::from(ch.to_digit(radix).unwrap_or(0))
+    });
+    if negative {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// `0042`、`-007.5` 这类 `0` 后紧跟数字的 token
+fn has_leading_zero(token: &str) -> bool {
+    let digits = token.strip_prefix(['-', '+']).unwrap_or(token).as_bytes();
+    digits.len() > 1 && digits[0] == b'0' && digits[1].is_ascii_digit()
+}
+
+/// `[+-]?[0-9]+`：i64 放不下时仍是合法整数
+fn is_decimal_integer(token: &str) -> bool {
+    let digits = token.strip_prefix(['-', '+']).unwrap_or(token);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// 超过 `max_bytes` 的输入在解析前直接拒绝
+fn check_budget(json_str: &str, opts: &RepairOptions) -> Result<(), RepairError> {
+    match opts.max_bytes {
+        Some(limit) if json_str.len() > limit => Err(RepairError {
+            kind: ErrorKind::TooLarge,
+            offset: 0,
+            message: format!(
+                "Input is {} bytes, exceeding max_bytes={limit}",
+                json_str.len()
+            ),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// 从 `start` 处解析一个值交给 `sink`；`start` 为 0 时按顶层解析（含 `assume_object`），否则把前面的文本记为跳过的前缀
+fn parse_at_into<'a, S: ValueSink>(
+    json_str: &'a str,
+    start: usize,
+    opts: &'a RepairOptions,
+    sink: &mut S,
+) -> Result<Parser<'a>, RepairError> {
+    parse_from(Parser::new(json_str, start, opts), sink)
+}
+
+/// 同 `parse_at_into`，解析器由调用方建好
+fn parse_from<'a, S: ValueSink>(
+    mut parser: Parser<'a>,
+    sink: &mut S,
+) -> Result<Parser<'a>, RepairError> {
+    let start = parser.pos;
+    if start == 0 {
+        parser.parse_root(sink)?;
+        return Ok(parser);
+    }
+    parser.parse_value_into(sink)?;
+    parser.record(0, RepairKind::SkippedPrefix, format!("{start} bytes"));
+    Ok(parser)
+}
+
+fn parse_at<'a>(
+    json_str: &'a str,
+    start: usize,
+    opts: &'a RepairOptions,
+) -> Result<(Parser<'a>, Json), RepairError> {
+    let mut builder = JsonBuilder::default();
+    let parser = parse_at_into(json_str, start, opts, &mut builder)?;
+    Ok((parser, builder.finish()))
+}
+
+/// 只从 `start` 处尝试解析，不再向后寻找其他起点；另返回最后一个续读点，供流式缓冲区下次从那里接着解析。
+/// `sort_keys` 会在对象闭合时重排成员，续读点对不上结果里的位置，此时不跟踪
+fn repair_at_tracked(
+    json_str: &str,
+    start: usize,
+    opts: &RepairOptions,
+) -> Result<(Json, Option<Checkpoint>), RepairError> {
+    check_budget(json_str, opts)?;
+    let mut parser = Parser::new(json_str, start, opts);
+    if !opts.sort_keys {
+        parser.members = Some(Vec::new());
+    }
+    let mut builder = JsonBuilder::default();
+    let parser = parse_from(parser, &mut builder)?;
+    Ok((builder.finish(), parser.checkpoint))
+}
+
+/// 从 `checkpoint` 接着解析 `json_str` 剩下的部分，逗号之前的内容不再重扫。
+/// 结果保持检查点处的容器嵌套：除最内层外，每层的第一个成员是装着内层结果的占位，
+/// 其后才是新读到的成员（占位 key 为空串）。同时返回新的续读点
+fn resume_at(
+    json_str: &str,
+    checkpoint: &Checkpoint,
+    opts: &RepairOptions,
+) -> Result<(Json, Option<Checkpoint>), RepairError> {
+    check_budget(json_str, opts)?;
+    let mut parser = Parser::new(json_str, checkpoint.comma, opts);
+    parser.closers = checkpoint.closers.clone();
+    parser.members = Some(checkpoint.members.clone());
+    parser.items = checkpoint.items;
+    let mut builder = JsonBuilder::default();
+    let depth = checkpoint.closers.len();
+    for (level, &closer) in checkpoint.closers.iter().enumerate() {
+        if closer == ']' {
+            builder.begin_array();
+        } else {
+            builder.begin_object();
+            if level + 1 < depth {
+                builder.key(String::new());
+            }
+        }
+    }
+    parser.resume(&mut builder)?;
+    Ok((builder.finish(), parser.checkpoint))
+}
+
+/// 从 `cursor` 所在处解析一个值（不做 `assume_object` 包装，也不向后寻找其他起点），
+/// 成功后把游标移到值之后；`cursor` 必须建在 `json_str` 的字节上
+pub fn repair_value_at(
+    json_str: &str,
+    cursor: &mut Cursor,
+    opts: &RepairOptions,
+) -> Result<Json, RepairError> {
+    check_budget(json_str, opts)?;
+    let mut parser = Parser::new(json_str, cursor.pos, opts);
+    let value = parser.parse_value()?;
+    cursor.restore(parser.pos);
+    Ok(value)
+}
+
+/// 找到第一个可解析的值：先从开头解析，失败再逐个 `{`/`[` 起点尝试，返回停在该值之后的解析器。
+/// 每个起点都用 `new_sink` 新建一个 sink，失败起点上收到过半截事件的 sink 直接丢弃
+fn parse_first_into<'a, S: ValueSink>(
+    json_str: &'a str,
+    opts: &'a RepairOptions,
+    new_sink: &mut impl FnMut() -> S,
+) -> Result<(Parser<'a>, S), RepairError> {
+    check_budget(json_str, opts)?;
+    let mut attempt = |start| {
+        let mut sink = new_sink();
+        parse_at_into(json_str, start, opts, &mut sink).map(|parser| (parser, sink))
+    };
+
+    // 1. Try direct parse first (fast path)
+    let direct_error = match attempt(0) {
+        Ok(parsed) => return Ok(parsed),
+        Err(e) if e.kind.is_limit() => return Err(e),
+        Err(e) => e,
+    };
 
     // 2. If direct parse fails, try to find the first '{' or '['
     // We iterate through the string to find potential start positions
-    let mut chars = json_str.char_indices().peekable();
-
-    while let Some((idx, ch)) = chars.next() {
+    for (idx, ch) in json_str.char_indices() {
         if ch == '{' || ch == '[' {
-            // Try parsing from here
-            let mut sub_parser = Parser::new(&json_str[idx..]);
-            if let Ok(res) = sub_parser.parse_value(py) {
-                return Ok(res);
+            match attempt(idx) {
+                Ok(parsed) => return Ok(parsed),
+                Err(e) if e.kind.is_limit() => return Err(e),
+                Err(_) => {}
             }
         }
     }
 
-    Err(PyValueError::new_err("No valid JSON found"))
+    // 报告从头解析的错误，它通常最能说明输入哪里不对
+    Err(RepairError {
+        message: format!(
+            "No valid JSON found (at offset {}: {})",
+            direct_error.offset, direct_error.message
+        ),
+        ..direct_error
+    })
+}
+
+fn parse_first<'a>(
+    json_str: &'a str,
+    opts: &'a RepairOptions,
+) -> Result<(Parser<'a>, Json), RepairError> {
+    let (parser, builder) = parse_first_into(json_str, opts, &mut JsonBuilder::default)?;
+    Ok((parser, builder.finish()))
+}
+
+/// 同 `repair_to_value`，但值以事件交给 sink，不经过 `Json` 树；返回 `sink.finish()` 与修复记录。
+/// 每个候选起点调用一次 `new_sink`，只有成功的那个会被 `finish`
+pub fn repair_into<S: ValueSink>(
+    json_str: &str,
+    opts: &RepairOptions,
+    mut new_sink: impl FnMut() -> S,
+) -> Result<(S::Output, Vec<Repair>), RepairError> {
+    let (parser, sink) = parse_first_into(json_str, opts, &mut new_sink)?;
+    Ok((sink.finish(), parser.into_records().0))
+}
+
+/// 纯 Rust 修复：返回中间值与修复记录（偏移相对于整个输入），全程不需要 GIL
+pub fn repair_to_value(json_str: &str, opts: &RepairOptions) -> Result<Repaired, RepairError> {
+    let (parser, value) = parse_first(json_str, opts)?;
+    Ok(parser.finish(value))
+}
+
+/// 解析首尾相接的多个值（如 `{"a":1}{"b":2}`），结果为数组；
+/// 值之间可有空白、注释或一个逗号，遇到无法解析的内容就停下并按尾随文本忽略
+pub fn repair_to_values(json_str: &str, opts: &RepairOptions) -> Result<Repaired, RepairError> {
+    let (mut parser, first) = parse_first(json_str, opts)?;
+    let mut values = vec![first];
+    while let Some(value) = parser.parse_next_value() {
+        values.push(value);
+    }
+    Ok(parser.finish(Json::Array(values)))
+}
+
+/// 按出现顺序找出文本中所有可解析的对象/数组（含 Markdown 代码块内的），结果为数组；
+/// 每个值解析完后从它之后继续找，值之间的散文被忽略，找不到时为空数组
+pub fn extract_all_values(json_str: &str, opts: &RepairOptions) -> Result<Repaired, RepairError> {
+    check_budget(json_str, opts)?;
+    let mut values = Vec::new();
+    let mut repairs = Vec::new();
+    let mut comments = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = json_str[pos..].find(['{', '[']) {
+        let start = pos + found;
+        let mut parser = Parser::new(json_str, start, opts);
+        match parser.parse_value() {
+            Ok(value) => {
+                values.push(value);
+                repairs.append(&mut parser.repairs);
+                comments.append(&mut parser.comments);
+                pos = parser.pos;
+            }
+            // 起点是 ASCII 括号，跳过一个字节仍在字符边界上
+            Err(_) => pos = start + 1,
+        }
+    }
+    repairs.sort_by_key(|r| r.offset);
+    Ok(Repaired {
+        value: Json::Array(values),
+        repairs,
+        comments,
+    })
+}
+
+/// 文本中最后一个可解析的对象/数组，常用于模型多次复述答案、以最后一次为准的场景。
+///
+/// 与 `extract_all_values` 一样从前往后找顶层值（嵌套在里面的括号不算候选）；
+/// 优先取最后一个完整闭合的值，全部需要在 EOF 处补全时才退而取最后一个补全的值
+pub fn repair_last_value(json_str: &str, opts: &RepairOptions) -> Result<Repaired, RepairError> {
+    check_budget(json_str, opts)?;
+    let mut last_complete = None;
+    let mut last_truncated = None;
+    let mut pos = 0;
+    while let Some(found) = json_str[pos..].find(['{', '[']) {
+        let start = pos + found;
+        let Ok((parser, value)) = parse_at(json_str, start, opts) else {
+            pos = start + 1;
+            continue;
+        };
+        pos = parser.pos;
+        let repaired = parser.finish(value);
+        if repaired.was_complete() {
+            last_complete = Some(repaired);
+        } else {
+            last_truncated = Some(repaired);
+        }
+    }
+    last_complete.or(last_truncated).ok_or_else(|| RepairError {
+        kind: ErrorKind::Syntax,
+        offset: 0,
+        message: "No JSON object or array found".to_string(),
+    })
+}
+
+/// 用多个线程独立修复每个输入，结果保持输入顺序；全程不需要 GIL
+pub fn repair_to_value_batch(
+    inputs: &[&str],
+    opts: &RepairOptions,
+) -> Vec<Result<Repaired, RepairError>> {
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_len = inputs.len().div_ceil(workers).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = inputs
+            .chunks(chunk_len)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|text| repair_to_value(text, opts))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("repair worker panicked"))
+            .collect()
+    })
+}
+
+/// 大输入在释放 GIL 的情况下执行纯 Rust 的扫描
+fn scan<T: Send>(py: Python<'_>, json_str: &str, f: impl FnOnce() -> T + Send) -> T {
+    scan_sized(py, json_str.len(), f)
+}
+
+/// 同 `scan`，修复失败时转成带行列的 Python 异常
+fn scan_located<T: Send>(
+    py: Python<'_>,
+    json_str: &str,
+    f: impl FnOnce() -> Result<T, RepairError> + Send,
+) -> PyResult<T> {
+    scan(py, json_str, f).map_err(|e| e.into_py_err(json_str))
+}
+
+/// 同 `scan`，输入长度由调用方给出
+fn scan_sized<T: Send>(py: Python<'_>, len: usize, f: impl FnOnce() -> T + Send) -> T {
+    if len >= ALLOW_THREADS_MIN_LEN {
+        py.allow_threads(f)
+    } else {
+        f()
+    }
+}
+
+/// 物化修复结果，带上 `value_hook` 与 `object_pairs_hook`
+fn materialize(py: Python<'_>, value: Json, opts: &RepairOptions) -> PyResult<PyObject> {
+    let hook = opts.value_hook.as_ref().map(|hook| hook.as_ref(py));
+    let pairs_hook = opts.object_pairs_hook.as_ref().map(|hook| hook.as_ref(py));
+    let mut builder = PyBuilder::new(py, hook, pairs_hook, opts.intern_keys);
+    value.drive(&mut builder);
+    builder.finish()
+}
+
+/// 修复并返回 Python 对象以及修复记录；大输入的扫描阶段释放 GIL
+pub fn repair_json_verbose(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+) -> PyResult<(PyObject, Vec<Repair>)> {
+    let repaired = scan_located(py, json_str, || repair_to_value(json_str, opts))?;
+    Ok((materialize(py, repaired.value, opts)?, repaired.repairs))
+}
+
+/// 修复并返回 Python 对象与被跳过的注释，总是开启 `capture_comments`
+pub fn repair_json_with_comments(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+) -> PyResult<(PyObject, Vec<Comment>)> {
+    let opts = RepairOptions {
+        capture_comments: true,
+        ..opts.clone()
+    };
+    let repaired = scan_located(py, json_str, || repair_to_value(json_str, &opts))?;
+    Ok((materialize(py, repaired.value, &opts)?, repaired.comments))
+}
+
+/// 从字节偏移 `offset` 处解析一个值，返回 `(value, end_offset)`，便于调用方手动逐段遍历文档
+pub fn parse_value_at(
+    py: Python<'_>,
+    json_str: &str,
+    offset: usize,
+    opts: &RepairOptions,
+) -> PyResult<(PyObject, usize)> {
+    if offset > json_str.len() {
+        return Err(PyValueError::new_err(format!(
+            "offset {offset} is out of range for input of {} bytes",
+            json_str.len()
+        )));
+    }
+    if !json_str.is_char_boundary(offset) {
+        return Err(PyValueError::new_err(format!(
+            "offset {offset} is not on a UTF-8 character boundary"
+        )));
+    }
+    let mut cursor = Cursor::new(json_str.as_bytes());
+    cursor.advance(offset);
+    let value = scan_located(py, json_str, || {
+        repair_value_at(json_str, &mut cursor, opts)
+    })?;
+    Ok((materialize(py, value, opts)?, cursor.pos))
+}
+
+/// 修复并返回 `(value, was_complete)`，`was_complete` 为 false 表示输入在 EOF 处被截断
+pub fn repair_json_status(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+) -> PyResult<(PyObject, bool)> {
+    let repaired = scan_located(py, json_str, || repair_to_value(json_str, opts))?;
+    let complete = repaired.was_complete();
+    Ok((materialize(py, repaired.value, opts)?, complete))
+}
+
+pub fn repair_json(py: Python<'_>, json_str: &str, opts: &RepairOptions) -> PyResult<PyObject> {
+    repair_json_verbose(py, json_str, opts).map(|(value, _)| value)
+}
+
+/// 同 `repair_json`，但无法修复（`ValueError`）时返回 None；其他异常（如 `value_hook` 抛出的）照常传出
+pub fn repair_json_or_none(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+) -> PyResult<PyObject> {
+    none_on_value_error(py, repair_json(py, json_str, opts))
+}
+
+fn none_on_value_error(py: Python<'_>, result: PyResult<PyObject>) -> PyResult<PyObject> {
+    match result {
+        Err(err) if err.is_instance_of::<PyValueError>(py) => Ok(py.None()),
+        result => result,
+    }
+}
+
+/// 逐个独立修复，失败的元素为 None，不会因为一个输入中断整批
+pub fn repair_json_batch(
+    py: Python<'_>,
+    inputs: &[&str],
+    opts: &RepairOptions,
+) -> PyResult<Vec<PyObject>> {
+    inputs
+        .iter()
+        .map(|text| repair_json_or_none(py, text, opts))
+        .collect()
+}
+
+/// 释放 GIL 后多线程扫描，再按输入顺序物化；失败的元素为 None
+pub fn repair_json_batch_parallel(
+    py: Python<'_>,
+    inputs: &[&str],
+    opts: &RepairOptions,
+) -> PyResult<Vec<PyObject>> {
+    let results = py.allow_threads(|| repair_to_value_batch(inputs, opts));
+    results
+        .into_iter()
+        .zip(inputs)
+        .map(|(result, text)| {
+            let value = result
+                .map_err(|e| e.into_py_err(text))
+                .and_then(|repaired| materialize(py, repaired.value, opts));
+            none_on_value_error(py, value)
+        })
+        .collect()
+}
+
+/// 只判断能否修复，不构建 Python 对象
+pub fn can_repair(py: Python<'_>, json_str: &str, opts: &RepairOptions) -> bool {
+    scan(py, json_str, || repair_to_value(json_str, opts).is_ok())
+}
+
+/// `{valid, truncated, depth, value_count}` 形式的修复概况，不构建值本身
+pub fn repair_report(py: Python<'_>, json_str: &str, opts: &RepairOptions) -> PyResult<PyObject> {
+    let report = scan(py, json_str, || RepairReport::scan(json_str, opts));
+    let dict = PyDict::new(py);
+    dict.set_item("valid", report.valid)?;
+    dict.set_item("truncated", report.truncated)?;
+    dict.set_item("depth", report.depth)?;
+    dict.set_item("value_count", report.value_count)?;
+    Ok(dict.into())
+}
+
+/// 批量修复的汇总统计，返回 dict；`repairs` 按类别名排序
+pub fn repair_json_batch_stats(
+    py: Python<'_>,
+    inputs: &[&str],
+    opts: &RepairOptions,
+) -> PyResult<PyObject> {
+    let stats = py.allow_threads(|| BatchStats::collect(inputs, opts));
+    let mut kinds: Vec<_> = stats.kinds.iter().map(|(k, n)| (k.as_str(), *n)).collect();
+    kinds.sort_unstable();
+    let repairs = PyDict::new(py);
+    for (kind, count) in kinds {
+        repairs.set_item(kind, count)?;
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("total", stats.total)?;
+    dict.set_item("succeeded", stats.succeeded)?;
+    dict.set_item("failed", stats.failed)?;
+    dict.set_item("truncated", stats.truncated)?;
+    dict.set_item("repairs", repairs)?;
+    Ok(dict.into())
+}
+
+/// 流式缓冲区当前的部分结果；尚无可解析内容时为 None
+pub fn repair_partial(
+    py: Python<'_>,
+    buffer: &mut StreamBuffer,
+    opts: &RepairOptions,
+) -> PyResult<PyObject> {
+    match scan_sized(py, buffer.text().len(), || buffer.partial(opts).cloned()) {
+        Some(value) => materialize(py, value, opts),
+        None => Ok(py.None()),
+    }
+}
+
+/// 解析首尾相接的多个值，返回 Python list
+pub fn repair_json_concatenated(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+) -> PyResult<PyObject> {
+    let repaired = scan_located(py, json_str, || repair_to_values(json_str, opts))?;
+    materialize(py, repaired.value, opts)
+}
+
+/// 提取文本中所有的 JSON 对象/数组，返回 Python list
+pub fn extract_all_json(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+) -> PyResult<PyObject> {
+    let repaired = scan_located(py, json_str, || extract_all_values(json_str, opts))?;
+    materialize(py, repaired.value, opts)
+}
+
+/// 修复文本中最后一个 JSON 对象/数组，返回 Python 对象
+pub fn repair_last_json(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+) -> PyResult<PyObject> {
+    let repaired = scan_located(py, json_str, || repair_last_value(json_str, opts))?;
+    materialize(py, repaired.value, opts)
+}
+
+/// 一次修复同时得到 Python 对象与紧凑的 JSON 文本，两者来自同一棵中间树；
+/// 文本反映 `value_hook` / `object_pairs_hook` 之前的修复结果
+pub fn repair_and_normalize(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+) -> PyResult<(PyObject, String)> {
+    let (value, text) = scan_located(py, json_str, || {
+        repair_to_value(json_str, opts).map(|r| {
+            let text = r.value.to_minified_string(opts.escape_forward_slashes);
+            (r.value, text)
+        })
+    })?;
+    Ok((materialize(py, value, opts)?, text))
+}
+
+/// 修复后构造 `cls` 的实例：有 `model_validate`（Pydantic v2）时交给它，否则要求顶层是对象并调用 `cls(**value)`；
+/// 构造函数抛出的异常原样上抛
+pub fn parse_into(
+    py: Python<'_>,
+    json_str: &str,
+    cls: &PyAny,
+    opts: &RepairOptions,
+) -> PyResult<PyObject> {
+    let value = repair_json(py, json_str, opts)?;
+    let value = value.as_ref(py);
+    if let Ok(validate) = cls.getattr("model_validate") {
+        return Ok(validate.call1((value,))?.into());
+    }
+    let fields = value.downcast::<PyDict>().map_err(|_| {
+        let name = cls
+            .getattr("__name__")
+            .map_or_else(|_| "the target class".to_string(), |n| n.to_string());
+        PyTypeError::new_err(format!(
+            "parse_into needs a JSON object to build {name}, got {}",
+            value.get_type().name().unwrap_or("value")
+        ))
+    })?;
+    Ok(cls.call((), Some(fields))?.into())
+}
+
+/// 输出是 JSON 文本的接口不接受 hook：hook 返回的任意 Python 对象无从序列化
+fn reject_hooks(opts: &RepairOptions, function: &str) -> PyResult<()> {
+    let hook = if opts.value_hook.is_some() {
+        Some("value_hook")
+    } else if opts.object_pairs_hook.is_some() {
+        Some("object_pairs_hook")
+    } else {
+        None
+    };
+    match hook {
+        Some(hook) => Err(PyTypeError::new_err(format!(
+            "{function} does not accept {hook}"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// 修复后把 JSON 文本分块写给 `writer.write(str)`，不在内存里拼出整串；`indent` 语义同 `json.dumps`
+pub fn repair_to_writer(
+    py: Python<'_>,
+    json_str: &str,
+    writer: &PyAny,
+    opts: &RepairOptions,
+    indent: Option<usize>,
+) -> PyResult<()> {
+    reject_hooks(opts, "repair_to_writer")?;
+    let write = writer
+        .getattr("write")
+        .ok()
+        .filter(|write| write.is_callable())
+        .ok_or_else(|| PyTypeError::new_err("writer must have a callable write method"))?;
+    let repaired = scan_located(py, json_str, || repair_to_value(json_str, opts))?;
+    repaired
+        .value
+        .write_json_chunks(indent, opts.escape_forward_slashes, |chunk| {
+            write.call1((chunk,)).map(drop)
+        })
+}
+
+/// 修复后重新序列化为 JSON 文本，全程不需要 GIL；`indent` 语义同 `json.dumps`
+pub fn repair_to_json_str(
+    py: Python<'_>,
+    json_str: &str,
+    opts: &RepairOptions,
+    indent: Option<usize>,
+) -> PyResult<String> {
+    reject_hooks(opts, "repair_to_json_str")?;
+    let text = scan_located(py, json_str, || {
+        repair_to_value(json_str, opts)
+            .map(|r| r.value.to_json_string(indent, opts.escape_forward_slashes))
+    })?;
+    Ok(text)
 }
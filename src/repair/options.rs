@@ -0,0 +1,341 @@
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::ops::BitOr;
+
+/// 注释语法集合（位标志）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentStyles(u8);
+
+impl CommentStyles {
+    /// `# ...` 行注释（Python/YAML）
+    pub const HASH: Self = Self(1);
+    /// `// ...` 行注释（C/JS）
+    pub const SLASH: Self = Self(1 << 1);
+    /// `/* ... */` 块注释
+    pub const BLOCK: Self = Self(1 << 2);
+    /// `-- ...` 行注释（SQL）
+    pub const DASH: Self = Self(1 << 3);
+    /// `<!-- ... -->` 块注释（HTML/Markdown）
+    pub const HTML: Self = Self(1 << 4);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// 从 Python 的名字集合（`{"hash", "slash", "block", "dash", "html"}`）构造
+    fn from_names(names: &PyAny) -> PyResult<Self> {
+        if names.extract::<&str>().is_ok() {
+            return Err(PyTypeError::new_err(
+                "comment_styles must be a collection of names, not a string",
+            ));
+        }
+        let mut styles = Self::empty();
+        for name in names.iter()? {
+            styles = styles
+                | match name?.extract::<&str>()? {
+                    "hash" => Self::HASH,
+                    "slash" => Self::SLASH,
+                    "block" => Self::BLOCK,
+                    "dash" => Self::DASH,
+                    "html" => Self::HTML,
+                    other => {
+                        return Err(PyValueError::new_err(format!(
+                            "Unknown comment style {other:?}"
+                        )))
+                    }
+                };
+        }
+        Ok(styles)
+    }
+}
+
+/// 默认沿用历来支持的 `#`、`//`、`/* */`
+impl Default for CommentStyles {
+    fn default() -> Self {
+        Self::HASH | Self::SLASH | Self::BLOCK
+    }
+}
+
+impl BitOr for CommentStyles {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// 超出 i64 的整数如何表示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BigIntMode {
+    /// Python 任意精度 int
+    #[default]
+    PythonInt,
+    /// 有损转成 f64，过大时为 inf
+    Float,
+    /// 保留原始数字文本
+    String,
+}
+
+impl BigIntMode {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "python_int" => Ok(Self::PythonInt),
+            "float" => Ok(Self::Float),
+            "string" => Ok(Self::String),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown bigint_mode {other:?}"
+            ))),
+        }
+    }
+}
+
+/// 没有闭合的 `/*` 块注释如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnclosedComments {
+    /// 报错，不吞掉注释之后的内容
+    Error,
+    /// 注释在之后第一个 `}` / `]` 前结束
+    Recover,
+    /// 注释一直延续到输入结束
+    #[default]
+    Consume,
+}
+
+impl UnclosedComments {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "error" => Ok(Self::Error),
+            "recover" => Ok(Self::Recover),
+            "consume" => Ok(Self::Consume),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown unclosed_comments {other:?}"
+            ))),
+        }
+    }
+}
+
+/// 容器开头或连续出现的多余逗号（`[,1]`、`{"a": 1,,}`）如何处理；单个尾逗号总是接受
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtraCommas {
+    /// 跳过并报告为 `extra_comma`
+    #[default]
+    Ignore,
+    /// 报错，指向多余的那个逗号
+    Error,
+}
+
+impl ExtraCommas {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "ignore" => Ok(Self::Ignore),
+            "error" => Ok(Self::Error),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown extra_commas {other:?}"
+            ))),
+        }
+    }
+}
+
+/// 无法解码的 `\u` 转义（非十六进制、不足 4 位、单独的代理项）如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BadUnicodePolicy {
+    /// 原样保留 `\u` 及其后读到的字符
+    #[default]
+    Preserve,
+    /// 换成 U+FFFD
+    Replace,
+    /// 报错
+    Error,
+}
+
+impl BadUnicodePolicy {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "preserve" => Ok(Self::Preserve),
+            "replace" => Ok(Self::Replace),
+            "error" => Ok(Self::Error),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown bad_unicode_policy {other:?}"
+            ))),
+        }
+    }
+}
+
+/// 字符串里原始控制字符（U+0000–U+001F）的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ControlCharPolicy {
+    /// 原样保留
+    #[default]
+    Keep,
+    /// 换成转义文本（`\n`、`\t`、`\u001b` 等），结果字符串里是反斜杠加字母
+    Escape,
+    /// 删除
+    Strip,
+}
+
+impl ControlCharPolicy {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "keep" => Ok(Self::Keep),
+            "escape" => Ok(Self::Escape),
+            "strip" => Ok(Self::Strip),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown control_char_policy {other:?}"
+            ))),
+        }
+    }
+}
+
+fn callable(value: &PyAny, name: &str) -> PyResult<PyObject> {
+    if !value.is_callable() {
+        return Err(PyTypeError::new_err(format!(
+            "{name} must be callable, got {}",
+            value.get_type().name()?
+        )));
+    }
+    Ok(value.into())
+}
+
+/// 修复行为开关；除注释外，默认值即严格 JSON 行为
+#[derive(Debug, Clone, Default)]
+pub struct RepairOptions {
+    /// 接受 JSON5/JS 风格的数字：`0x`/`0o`/`0b` 前缀整数，以及 `.5`/`5.` 这类省略一侧数字的小数
+    pub json5_numbers: bool,
+    /// 顶层形如 `key: value, ...` 却没有花括号时，包成对象解析（顶层 key 可为裸标识符）
+    pub assume_object: bool,
+    /// 允许跳过的注释语法；为空时任何注释符都按普通字符处理
+    pub comment_styles: CommentStyles,
+    /// 跳过整行的 `---` / `===` 分隔线（YAML front matter 风格）
+    pub skip_separators: bool,
+    /// 把零宽字符（U+200B–U+200D、U+2060）与 BOM（U+FEFF）当作空白跳过
+    pub skip_zero_width: bool,
+    /// 跳过值周围的 HTML/XML 标签（`<pre>`、`</code>`、`<output lang="json">`）
+    pub strip_html: bool,
+    /// 顶层值之前悬空的 `}` / `]` / `,`（拼接多段回复的残留）直接跳过，而不是报错
+    pub skip_leading_garbage: bool,
+    /// key 之后的 `=>` 与 `=` 也当作 key/值分隔符（`{"a" => 1}`）
+    pub lenient_separators: bool,
+    /// 重新序列化成 JSON 文本时把字符串里的 `/` 写成 `\/`（嵌进 `<script>` 标签时避免 `</script>`）；不影响解析
+    pub escape_forward_slashes: bool,
+    /// 对象 key 两侧的 Markdown 强调符 `*` / `_`（`**"name"**: "x"`）在读 key 前后跳过
+    pub strip_markdown_emphasis: bool,
+    /// 保留被跳过的注释正文与偏移（`Repaired::comments`），供调用方当作注解使用；
+    /// Python 侧不单独暴露，由 `repair_json_with_comments` 开启
+    pub capture_comments: bool,
+    /// 对象 key 没有值（后面紧跟 `,`、`}` 或 EOF）时补 null，而不是报错
+    pub lenient_missing_values: bool,
+    /// `0042` 这类带前导零的数字保留为原始字符串（多为补零的编号）
+    pub leading_zero_as_string: bool,
+    /// 带小数点或指数、但值是整数（且在 i64 内）的数字（`1.0`、`2e3`）返回 int
+    pub coerce_whole_floats: bool,
+    /// 每个数字都按原文（如 `0.300`、`0x1F`）返回字符串，便于审计模型写出的原始数值
+    pub raw_numbers: bool,
+    /// 数字里的全角数字 `０`–`９` 与全角 `＋` `－` `．` 按 ASCII 解析
+    pub normalize_unicode_digits: bool,
+    /// 数字里的 Unicode 减号 `−`（U+2212）与全角 `－` 按 ASCII `-` 解析
+    pub normalize_unicode_minus: bool,
+    /// 数字、布尔、null 作 key 时转成字符串（`1` → `"1"`、`true` → `"true"`），否则报错
+    pub coerce_keys: bool,
+    /// 超出 i64 的整数的表示方式
+    pub bigint_mode: BigIntMode,
+    /// 数组元素、对象成员之间缺逗号时（`[1 2]`、`{"a": 1 "b": 2}`）补上，而不是报错
+    pub lenient_commas: bool,
+    /// 用错的闭括号（`[1, 2}`、`{"a": [1}`）按关闭当前容器处理，而不是报错
+    pub lenient_brackets: bool,
+    /// 接受 JS 的 `undefined` 字面量，按 null 处理
+    pub js_literals: bool,
+    /// 多余逗号的处理方式
+    pub extra_commas: ExtraCommas,
+    /// 内容本身是完整 JSON 对象/数组的字符串值（`"{\"a\": 1}"`）按 JSON 重新解析，可逐层嵌套
+    pub auto_unstringify: bool,
+    /// 对象 key 按码点排序（同 `json.dumps(sort_keys=True)`），只用于需要确定性输出的场景
+    pub sort_keys: bool,
+    /// 未闭合块注释的处理方式
+    pub unclosed_comments: UnclosedComments,
+    /// 字符串里原始控制字符的处理方式
+    pub control_char_policy: ControlCharPolicy,
+    /// 无法解码的 `\u` 转义的处理方式
+    pub bad_unicode_policy: BadUnicodePolicy,
+    /// 字符串里 `\` 紧跟换行（`\n` 或 `\r\n`）视为续行，两者一起删除
+    pub line_continuations: bool,
+    /// 输入的字节数上限（UTF-8 字节，不是字符），超出时解析前即报错
+    pub max_bytes: Option<usize>,
+    /// 容器嵌套层数上限，未指定时为 512；调得很大时过深的输入仍可能耗尽栈
+    pub max_depth: Option<usize>,
+    /// 一次解析中标量值和 key 的总数上限（容器本身不计），防御很宽但不深的输入
+    pub max_items: Option<usize>,
+    /// 物化时同一个 key 只创建一个 Python 字符串，适合大量同构记录的数组
+    pub intern_keys: bool,
+    /// 物化 Python 对象时对每个叶子调用的 `hook(value, tag)`，返回值代替原值
+    pub value_hook: Option<PyObject>,
+    /// 同 `json.loads(object_pairs_hook=...)`：每个对象以 `(key, value)` 元组的 list 传入，
+    /// 保留原始顺序与重复 key，返回值代替 dict
+    pub object_pairs_hook: Option<PyObject>,
+}
+
+impl RepairOptions {
+    /// 从 Python 关键字参数构造，未知参数名直接报 TypeError
+    pub fn from_kwargs(kwargs: Option<&PyDict>) -> PyResult<Self> {
+        let mut opts = Self::default();
+        let Some(kwargs) = kwargs else {
+            return Ok(opts);
+        };
+
+        for (key, value) in kwargs {
+            let key: &str = key.extract()?;
+            match key {
+                "json5_numbers" => opts.json5_numbers = value.extract()?,
+                "assume_object" => opts.assume_object = value.extract()?,
+                "comment_styles" => opts.comment_styles = CommentStyles::from_names(value)?,
+                "skip_separators" => opts.skip_separators = value.extract()?,
+                "skip_zero_width" => opts.skip_zero_width = value.extract()?,
+                "strip_html" => opts.strip_html = value.extract()?,
+                "strip_markdown_emphasis" => opts.strip_markdown_emphasis = value.extract()?,
+                "skip_leading_garbage" => opts.skip_leading_garbage = value.extract()?,
+                "escape_forward_slashes" => opts.escape_forward_slashes = value.extract()?,
+                "lenient_separators" => opts.lenient_separators = value.extract()?,
+                "lenient_missing_values" => opts.lenient_missing_values = value.extract()?,
+                "leading_zero_as_string" => opts.leading_zero_as_string = value.extract()?,
+                "coerce_whole_floats" => opts.coerce_whole_floats = value.extract()?,
+                "raw_numbers" => opts.raw_numbers = value.extract()?,
+                "normalize_unicode_digits" => opts.normalize_unicode_digits = value.extract()?,
+                "normalize_unicode_minus" => opts.normalize_unicode_minus = value.extract()?,
+                "coerce_keys" => opts.coerce_keys = value.extract()?,
+                "bigint_mode" => opts.bigint_mode = BigIntMode::from_name(value.extract()?)?,
+                "lenient_commas" => opts.lenient_commas = value.extract()?,
+                "lenient_brackets" => opts.lenient_brackets = value.extract()?,
+                "js_literals" => opts.js_literals = value.extract()?,
+                "extra_commas" => opts.extra_commas = ExtraCommas::from_name(value.extract()?)?,
+                "auto_unstringify" => opts.auto_unstringify = value.extract()?,
+                "sort_keys" => opts.sort_keys = value.extract()?,
+                "unclosed_comments" => {
+                    opts.unclosed_comments = UnclosedComments::from_name(value.extract()?)?
+                }
+                "control_char_policy" => {
+                    opts.control_char_policy = ControlCharPolicy::from_name(value.extract()?)?
+                }
+                "bad_unicode_policy" => {
+                    opts.bad_unicode_policy = BadUnicodePolicy::from_name(value.extract()?)?
+                }
+                "line_continuations" => opts.line_continuations = value.extract()?,
+                "max_bytes" => opts.max_bytes = value.extract()?,
+                "max_depth" => opts.max_depth = value.extract()?,
+                "max_items" => opts.max_items = value.extract()?,
+                "intern_keys" => opts.intern_keys = value.extract()?,
+                "value_hook" => opts.value_hook = Some(callable(value, key)?),
+                "object_pairs_hook" => opts.object_pairs_hook = Some(callable(value, key)?),
+                other => {
+                    return Err(PyTypeError::new_err(format!(
+                        "Unexpected keyword argument {other:?}"
+                    )))
+                }
+            }
+        }
+        Ok(opts)
+    }
+}
@@ -0,0 +1,313 @@
+use super::Json;
+
+/// 十进制大数的每个 limb 存 9 位
+const LIMB_BASE: u64 = 1_000_000_000;
+
+/// 分块输出时每块的目标字节数
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// 输出排版：紧凑、`json.dumps` 默认的带空格分隔，或按宽度缩进
+#[derive(Clone, Copy)]
+enum Layout {
+    Minified,
+    Spaced,
+    Indented(usize),
+}
+
+impl Layout {
+    fn key_separator(self) -> &'static str {
+        match self {
+            Layout::Minified => ":",
+            _ => ": ",
+        }
+    }
+}
+
+/// 排版与字符串转义方式；`escape_slashes` 时 `/` 写成 `\/`，便于嵌进 `<script>` 标签
+#[derive(Clone, Copy)]
+struct Style {
+    layout: Layout,
+    escape_slashes: bool,
+}
+
+/// 序列化输出的去处：整串拼进 `String`，或攒成块交给回调
+trait Sink {
+    fn push(&mut self, ch: char);
+    fn push_str(&mut self, s: &str);
+}
+
+impl Sink for String {
+    fn push(&mut self, ch: char) {
+        String::push(self, ch);
+    }
+
+    fn push_str(&mut self, s: &str) {
+        String::push_str(self, s);
+    }
+}
+
+/// 攒满 `CHUNK_LEN` 字节就交给 `emit`；`emit` 出错后丢弃其余输出，错误留给调用方
+struct Chunked<F, E> {
+    buf: String,
+    emit: F,
+    error: Option<E>,
+}
+
+impl<F: FnMut(&str) -> Result<(), E>, E> Chunked<F, E> {
+    fn flush(&mut self) {
+        if self.error.is_none() && !self.buf.is_empty() {
+            self.error = (self.emit)(&self.buf).err();
+        }
+        self.buf.clear();
+    }
+}
+
+impl<F: FnMut(&str) -> Result<(), E>, E> Sink for Chunked<F, E> {
+    fn push(&mut self, ch: char) {
+        self.buf.push(ch);
+        if self.buf.len() >= CHUNK_LEN {
+            self.flush();
+        }
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.buf.push_str(s);
+        if self.buf.len() >= CHUNK_LEN {
+            self.flush();
+        }
+    }
+}
+
+impl Json {
+    /// 序列化为 JSON 文本，格式与 Python `json.dumps(value, ensure_ascii=False, indent=indent)` 一致：
+    /// 不缩进时用 `", "` / `": "` 分隔；缩进时每层 `indent` 个空格、成员间换行，空容器保持 `{}` / `[]`。
+    /// `escape_slashes` 时字符串里的 `/` 写成 `\/`
+    pub fn to_json_string(&self, indent: Option<usize>, escape_slashes: bool) -> String {
+        self.render(Style {
+            layout: indent.map_or(Layout::Spaced, Layout::Indented),
+            escape_slashes,
+        })
+    }
+
+    /// 紧凑的 JSON 文本，同 `json.dumps(value, ensure_ascii=False, separators=(",", ":"))`
+    pub fn to_minified_string(&self, escape_slashes: bool) -> String {
+        self.render(Style {
+            layout: Layout::Minified,
+            escape_slashes,
+        })
+    }
+
+    /// 与 `to_json_string` 相同的文本，按约 64 KiB 一块依次交给 `emit`，不在内存里拼出整串；
+    /// `emit` 第一次出错后停止输出并返回该错误
+    pub fn write_json_chunks<E>(
+        &self,
+        indent: Option<usize>,
+        escape_slashes: bool,
+        emit: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let mut out = Chunked {
+            buf: String::with_capacity(CHUNK_LEN),
+            emit,
+            error: None,
+        };
+        let style = Style {
+            layout: indent.map_or(Layout::Spaced, Layout::Indented),
+            escape_slashes,
+        };
+        self.write_json(&mut out, style, 0);
+        out.flush();
+        out.error.map_or(Ok(()), Err)
+    }
+
+    fn render(&self, style: Style) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out, style, 0);
+        out
+    }
+
+    fn write_json<S: Sink>(&self, out: &mut S, style: Style, level: usize) {
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Json::Int(i) => out.push_str(&i.to_string()),
+            Json::BigInt { digits, radix } => out.push_str(&to_decimal(digits, *radix)),
+            Json::Float(f) => out.push_str(&format_float(*f)),
+            Json::Str(s) => write_string(out, s, style.escape_slashes),
+            Json::Array(items) => {
+                write_container(out, ('[', ']'), items, style.layout, level, |out, item| {
+                    item.write_json(out, style, level + 1)
+                })
+            }
+            Json::Object(members) => write_container(
+                out,
+                ('{', '}'),
+                members,
+                style.layout,
+                level,
+                |out, (key, value)| {
+                    write_string(out, key, style.escape_slashes);
+                    out.push_str(style.layout.key_separator());
+                    value.write_json(out, style, level + 1);
+                },
+            ),
+        }
+    }
+}
+
+/// 写出数组/对象的括号、分隔与缩进，元素本身交给 `write_item`
+fn write_container<S: Sink, T>(
+    out: &mut S,
+    (open, close): (char, char),
+    items: &[T],
+    layout: Layout,
+    level: usize,
+    mut write_item: impl FnMut(&mut S, &T),
+) {
+    out.push(open);
+    if items.is_empty() {
+        out.push(close);
+        return;
+    }
+    for (i, item) in items.iter().enumerate() {
+        match layout {
+            Layout::Indented(width) => {
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                push_indent(out, width, level + 1);
+            }
+            _ if i == 0 => {}
+            Layout::Spaced => out.push_str(", "),
+            Layout::Minified => out.push(','),
+        }
+        write_item(out, item);
+    }
+    if let Layout::Indented(width) = layout {
+        out.push('\n');
+        push_indent(out, width, level);
+    }
+    out.push(close);
+}
+
+fn push_indent<S: Sink>(out: &mut S, width: usize, level: usize) {
+    for _ in 0..width * level {
+        out.push(' ');
+    }
+}
+
+/// 与 Python `json.dumps` 相同的转义：引号、反斜杠与控制字符，非 ASCII 原样输出；
+/// `escape_slashes` 时另把 `/` 写成 `\/`
+fn write_string<S: Sink>(out: &mut S, s: &str, escape_slashes: bool) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '/' if escape_slashes => out.push_str("\\/"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// 与 Python `repr(float)` 逐字相同：能还原出同一个 f64 的最短写法里离真值最近的那个，整值保留 `.0`，
+/// 指数带符号且至少两位（`1e+16`、`1e-05`）；非有限值沿用 Python 的 `NaN` / `Infinity` 写法
+fn format_float(f: f64) -> String {
+    if f.is_nan() {
+        return "NaN".to_string();
+    }
+    if f.is_infinite() {
+        return if f > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    // `{:e}` 给出最短还原位数，但同样位数下不一定选离真值最近的（`2.3` 与 `2.2` 都能还原时可能取 `2.3`）；
+    // 按这个位数再正确舍入一次，仍能还原就用它
+    let shortest = format!("{f:e}");
+    let mantissa = shortest
+        .split_once('e')
+        .map_or(shortest.as_str(), |(m, _)| m);
+    let precision = mantissa.chars().filter(char::is_ascii_digit).count() - 1;
+    let nearest = format!("{f:.precision$e}");
+    if nearest.parse::<f64>() == Ok(f) {
+        python_repr(&nearest)
+    } else {
+        python_repr(&shortest)
+    }
+}
+
+/// `-1.5e-7` 形式的科学计数法改成 Python 的写法：小数点位置落在 (-4, 16] 内时写成定点，
+/// 否则保留科学计数法，指数补足两位（`1.5e-07`）
+fn python_repr(scientific: &str) -> String {
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((scientific, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    // 小数点落在第几位数字之后
+    let point = exponent + 1;
+    if point <= -4 || point > 16 {
+        let (first, rest) = digits.split_at(1);
+        let fraction = if rest.is_empty() {
+            String::new()
+        } else {
+            format!(".{rest}")
+        };
+        let exponent_sign = if exponent < 0 { '-' } else { '+' };
+        return format!(
+            "{sign}{first}{fraction}e{exponent_sign}{:02}",
+            exponent.unsigned_abs()
+        );
+    }
+    if point <= 0 {
+        return format!(
+            "{sign}0.{}{digits}",
+            "0".repeat(point.unsigned_abs() as usize)
+        );
+    }
+    let point = point as usize;
+    if point >= digits.len() {
+        format!("{sign}{digits}{}.0", "0".repeat(point - digits.len()))
+    } else {
+        format!("{sign}{}.{}", &digits[..point], &digits[point..])
+    }
+}
+
+/// 带符号的任意进制数字串转十进制文本
+fn to_decimal(digits: &str, radix: u32) -> String {
+    let (sign, body) = match digits.strip_prefix('-') {
+        Some(body) => ("-", body),
+        None => ("", digits.trim_start_matches('+')),
+    };
+    if radix == 10 {
+        return format!("{sign}{body}");
+    }
+
+    // 小端 limb，每个 limb 是 0..LIMB_BASE
+    let mut limbs: Vec<u64> = vec![0];
+    for ch in body.chars() {
+        let mut carry = u64::from(ch.to_digit(radix).unwrap_or(0));
+        for limb in &mut limbs {
+            let v = *limb * u64::from(radix) + carry;
+            *limb = v % LIMB_BASE;
+            carry = v / LIMB_BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+
+    let mut out = sign.to_string();
+    let mut rev = limbs.iter().rev();
+    if let Some(top) = rev.next() {
+        out.push_str(&top.to_string());
+    }
+    for limb in rev {
+        out.push_str(&format!("{limb:09}"));
+    }
+    out
+}
@@ -0,0 +1,293 @@
+//! SAX 风格的值接收端：解析器边扫描边发出 `begin_*` / `key` / `scalar` / `end_*` 事件，
+//! 由 sink 决定建成什么。`JsonBuilder` 建出 `Json` 树，`PyBuilder` 直接建 Python 对象；
+//! 自定义 sink 可以只统计或转写，不必在内存里留下整棵树。
+//!
+//! 事件总是成对且良构：`key` 只出现在 `begin_object` 与 `end_object` 之间，且后面紧跟一个值。
+//! 解析失败时已发出的事件不会撤回，调用方应丢弃这个 sink（`repair_into` 每个候选起点都新建一个）。
+
+use super::value::Json;
+use ahash::AHashMap;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString};
+
+pub trait ValueSink {
+    type Output;
+
+    fn begin_object(&mut self);
+    /// 对象成员的 key，之后紧跟它的值
+    fn key(&mut self, key: String);
+    fn end_object(&mut self);
+    fn begin_array(&mut self);
+    fn end_array(&mut self);
+    /// 叶子值，不会是 `Json::Array` / `Json::Object`
+    fn scalar(&mut self, value: Json);
+    /// 一个完整的顶层值之后调用
+    fn finish(self) -> Self::Output;
+}
+
+impl Json {
+    /// 把已建好的树按事件重放给 `sink`
+    pub fn drive<S: ValueSink>(self, sink: &mut S) {
+        match self {
+            Json::Array(items) => {
+                sink.begin_array();
+                for item in items {
+                    item.drive(sink);
+                }
+                sink.end_array();
+            }
+            Json::Object(members) => {
+                sink.begin_object();
+                for (key, value) in members {
+                    sink.key(key);
+                    value.drive(sink);
+                }
+                sink.end_object();
+            }
+            leaf => sink.scalar(leaf),
+        }
+    }
+}
+
+enum JsonFrame {
+    Array(Vec<Json>),
+    /// 已收齐的成员与等待值的 key
+    Object(Vec<(String, Json)>, Option<String>),
+}
+
+/// 建出 `Json` 树
+#[derive(Default)]
+pub struct JsonBuilder {
+    stack: Vec<JsonFrame>,
+    root: Option<Json>,
+}
+
+impl JsonBuilder {
+    fn push(&mut self, value: Json) {
+        match self.stack.last_mut() {
+            None => self.root = Some(value),
+            Some(JsonFrame::Array(items)) => items.push(value),
+            Some(JsonFrame::Object(members, key)) => {
+                members.push((key.take().unwrap_or_default(), value))
+            }
+        }
+    }
+}
+
+impl ValueSink for JsonBuilder {
+    type Output = Json;
+
+    fn begin_object(&mut self) {
+        self.stack.push(JsonFrame::Object(Vec::new(), None));
+    }
+
+    fn key(&mut self, key: String) {
+        if let Some(JsonFrame::Object(_, pending)) = self.stack.last_mut() {
+            *pending = Some(key);
+        }
+    }
+
+    fn end_object(&mut self) {
+        if let Some(JsonFrame::Object(members, _)) = self.stack.pop() {
+            self.push(Json::Object(members));
+        }
+    }
+
+    fn begin_array(&mut self) {
+        self.stack.push(JsonFrame::Array(Vec::new()));
+    }
+
+    fn end_array(&mut self) {
+        if let Some(JsonFrame::Array(items)) = self.stack.pop() {
+            self.push(Json::Array(items));
+        }
+    }
+
+    fn scalar(&mut self, value: Json) {
+        self.push(value);
+    }
+
+    /// 事件良构时一定有根值；没有收到任何值时为 null
+    fn finish(self) -> Json {
+        self.root.unwrap_or(Json::Null)
+    }
+}
+
+enum PyFrame<'py> {
+    Array(Vec<PyObject>),
+    Dict(&'py PyDict, Option<PyObject>),
+    /// `object_pairs_hook` 下对象不建 dict，先收集 `(key, value)`
+    Pairs(Vec<PyObject>, Option<PyObject>),
+}
+
+/// 直接建 Python 对象；`hook` 存在时每个叶子值以 `hook(value, tag)` 的返回值代替，
+/// tag 为 `"null"`、`"bool"`、`"int"`、`"float"`、`"str"` 之一；
+/// `pairs_hook` 存在时对象不建 dict，而是把 `(key, value)` 列表交给它。
+/// hook 抛出的第一个异常由 `finish` 返回，之后的事件被忽略
+pub struct PyBuilder<'py> {
+    py: Python<'py>,
+    hook: Option<&'py PyAny>,
+    pairs_hook: Option<&'py PyAny>,
+    /// 开启 key 驻留时，同一个 key 只创建一个 `PyString`
+    keys: Option<AHashMap<String, Py<PyString>>>,
+    stack: Vec<PyFrame<'py>>,
+    root: Option<PyObject>,
+    error: Option<PyErr>,
+}
+
+impl<'py> PyBuilder<'py> {
+    pub fn new(
+        py: Python<'py>,
+        hook: Option<&'py PyAny>,
+        pairs_hook: Option<&'py PyAny>,
+        intern_keys: bool,
+    ) -> Self {
+        PyBuilder {
+            py,
+            hook,
+            pairs_hook,
+            keys: intern_keys.then(AHashMap::new),
+            stack: Vec::new(),
+            root: None,
+            error: None,
+        }
+    }
+
+    fn push(&mut self, value: PyObject) {
+        let py = self.py;
+        let result = match self.stack.last_mut() {
+            None => {
+                self.root = Some(value);
+                Ok(())
+            }
+            Some(PyFrame::Array(items)) => {
+                items.push(value);
+                Ok(())
+            }
+            // abi3 下没有预分配容量的 dict 构造（`_PyDict_NewPresized` 不在受限 API 中），
+            // 且重复 key 需要后者覆盖前者，只能逐个 set_item
+            Some(PyFrame::Dict(dict, key)) => dict.set_item(key.take(), value),
+            Some(PyFrame::Pairs(pairs, key)) => {
+                pairs.push((key.take(), value).into_py(py));
+                Ok(())
+            }
+        };
+        self.fail_on(result);
+    }
+
+    fn fail_on<T>(&mut self, result: PyResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.error.get_or_insert(err);
+                None
+            }
+        }
+    }
+
+    fn leaf(&self, value: Json) -> PyResult<PyObject> {
+        let py = self.py;
+        let (leaf, tag) = match value {
+            Json::Null => (py.None(), "null"),
+            Json::Bool(b) => (b.into_py(py), "bool"),
+            Json::Int(i) => (i.into_py(py), "int"),
+            Json::BigInt { digits, radix } => {
+                let builtins = py.import("builtins")?;
+                (
+                    builtins.getattr("int")?.call1((digits, radix))?.into(),
+                    "int",
+                )
+            }
+            Json::Float(f) => (f.into_py(py), "float"),
+            Json::Str(s) => (s.into_py(py), "str"),
+            Json::Array(_) | Json::Object(_) => unreachable!("containers are replayed by scalar"),
+        };
+        match self.hook {
+            Some(hook) => Ok(hook.call1((leaf, tag))?.into()),
+            None => Ok(leaf),
+        }
+    }
+
+    fn key_object(&mut self, key: String) -> PyObject {
+        let py = self.py;
+        let Some(keys) = self.keys.as_mut() else {
+            return key.into_py(py);
+        };
+        keys.entry(key)
+            .or_insert_with_key(|key| PyString::new(py, key).into())
+            .clone_ref(py)
+            .into_py(py)
+    }
+}
+
+impl<'py> ValueSink for PyBuilder<'py> {
+    type Output = PyResult<PyObject>;
+
+    fn begin_object(&mut self) {
+        let frame = match self.pairs_hook {
+            Some(_) => PyFrame::Pairs(Vec::new(), None),
+            None => PyFrame::Dict(PyDict::new(self.py), None),
+        };
+        self.stack.push(frame);
+    }
+
+    fn key(&mut self, key: String) {
+        if self.error.is_some() {
+            return;
+        }
+        let key = self.key_object(key);
+        if let Some(PyFrame::Dict(_, pending) | PyFrame::Pairs(_, pending)) = self.stack.last_mut()
+        {
+            *pending = Some(key);
+        }
+    }
+
+    fn end_object(&mut self) {
+        let py = self.py;
+        let object = match self.stack.pop() {
+            Some(PyFrame::Dict(dict, _)) => dict.into(),
+            Some(PyFrame::Pairs(pairs, _)) => {
+                let called = self.pairs_hook.map_or(Ok(py.None()), |hook| {
+                    hook.call1((PyList::new(py, pairs),)).map(Into::into)
+                });
+                match self.fail_on(called) {
+                    Some(object) => object,
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+        self.push(object);
+    }
+
+    fn begin_array(&mut self) {
+        self.stack.push(PyFrame::Array(Vec::new()));
+    }
+
+    fn end_array(&mut self) {
+        // 先收集再一次性建出定长 list，避免逐个 append 反复扩容
+        if let Some(PyFrame::Array(items)) = self.stack.pop() {
+            self.push(PyList::new(self.py, items).into());
+        }
+    }
+
+    fn scalar(&mut self, value: Json) {
+        if self.error.is_some() {
+            return;
+        }
+        // 容器误当叶子传进来时按事件重放，而不是报错
+        if let Json::Array(_) | Json::Object(_) = value {
+            return value.drive(self);
+        }
+        if let Some(leaf) = self.fail_on(self.leaf(value)) {
+            self.push(leaf);
+        }
+    }
+
+    fn finish(self) -> PyResult<PyObject> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.root.unwrap_or_else(|| self.py.None())),
+        }
+    }
+}
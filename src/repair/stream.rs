@@ -0,0 +1,141 @@
+use super::{repair_at_tracked, resume_at, Checkpoint, Json, RepairError, RepairOptions};
+
+/// 部分解析失败时，最多再退回几个结构边界重试；截断的 token 通常就在最后一个边界之后
+const MAX_PARTIAL_RETRIES: usize = 8;
+
+/// 流式输入的缓冲区：按块累积文本，随时给出“能解析多少算多少”的结果
+///
+/// 容器里的成员一旦读到其后的逗号就定型，之后追加的文本不会再改变它。
+/// 每次成功解析都记下最后一个这样的逗号（及其外层各容器的状态），下次只从那里续读，
+/// 把新读到的成员接到上一次的结果上，因此每块的解析量只与新增文本和最后一个未定型成员有关。
+/// 截断重试只退到上一次成功的截断点为止，退不到时沿用上一次的结果，不会在中途又变回 None。
+/// `sort_keys` 下对象要读完才能排序，每次仍从头解析。
+/// 同一个缓冲区应始终用同一组选项求部分结果
+#[derive(Debug, Default)]
+pub struct StreamBuffer {
+    text: String,
+    /// 上一次成功解析的前缀长度；文本只会追加，这段前缀的结果不会变
+    good_len: usize,
+    /// 上一次成功解析的结果
+    value: Option<Json>,
+    /// 上一次成功解析留下的续读点
+    checkpoint: Option<Checkpoint>,
+}
+
+impl StreamBuffer {
+    pub fn feed(&mut self, chunk: &str) {
+        self.text.push_str(chunk);
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// 取出全部文本并清空缓冲区，供下一条消息复用
+    pub fn take(&mut self) -> String {
+        self.good_len = 0;
+        self.value = None;
+        self.checkpoint = None;
+        std::mem::take(&mut self.text)
+    }
+
+    /// 当前文本的尽力解析结果；还没有任何可解析内容时返回 None
+    pub fn partial(&mut self, opts: &RepairOptions) -> Option<&Json> {
+        match (self.checkpoint.take(), self.value.as_mut()) {
+            (Some(checkpoint), Some(value)) => {
+                let floor = self.good_len.max(checkpoint.comma + 1);
+                let attempt = |prefix: &str| resume_at(prefix, &checkpoint, opts);
+                match parse_truncated(&self.text, floor, attempt) {
+                    Some((len, (tail, next))) => {
+                        splice(value, &checkpoint.members, tail);
+                        self.good_len = len;
+                        self.checkpoint = next.or(Some(checkpoint));
+                    }
+                    None => self.checkpoint = Some(checkpoint),
+                }
+            }
+            _ => {
+                if let Some((len, (value, checkpoint))) =
+                    parse_fresh(&self.text, self.good_len, opts)
+                {
+                    self.good_len = len;
+                    self.value = Some(value);
+                    self.checkpoint = checkpoint;
+                }
+            }
+        }
+        self.value.as_ref()
+    }
+}
+
+type Tracked = (Json, Option<Checkpoint>);
+
+/// 还没有续读点时从头解析：先从开头解析，不行就锚定第一个 `{` / `[` 截断重试。
+/// 锚定起点是为了总拿到最外层容器，而不是被回退扫描救回来的某个内层值
+fn parse_fresh(text: &str, floor: usize, opts: &RepairOptions) -> Option<(usize, Tracked)> {
+    if let Ok(parsed) = repair_at_tracked(text, 0, opts) {
+        return Some((text.len(), parsed));
+    }
+    let anchor = text.find(['{', '['])?;
+    let attempt = |prefix: &str| repair_at_tracked(prefix, anchor, opts);
+    parse_truncated(text, floor.max(anchor), attempt)
+}
+
+/// 依次退回到最近的 `,` / `[` / `{` 处截断重试，丢掉块边界上尚未完整的 token（如 `tru`、`-`、`"key": `）。
+/// 截断点不早于 `floor`；返回成功解析的前缀长度与结果
+fn parse_truncated(
+    text: &str,
+    floor: usize,
+    attempt: impl Fn(&str) -> Result<Tracked, RepairError>,
+) -> Option<(usize, Tracked)> {
+    let mut prefix = text;
+    for _ in 0..=MAX_PARTIAL_RETRIES {
+        if let Ok(parsed) = attempt(prefix) {
+            return Some((prefix.len(), parsed));
+        }
+        let cut = prefix.rfind([',', '[', '{']).filter(|&cut| cut >= floor)?;
+        // 逗号本身丢掉，括号保留下来由 EOF 自动闭合
+        prefix = match prefix.as_bytes()[cut] {
+            b',' => &text[..cut],
+            _ if cut + 1 < prefix.len() => &text[..=cut],
+            // 已经截到一个单独的括号仍然失败，不会再有更好的结果
+            _ => return None,
+        };
+    }
+    None
+}
+
+/// 把续读结果 `tail` 接到 `value` 上：`members` 是续读点处各层已读完的成员数（见 `resume_at`），
+/// 每层保留这些成员，除最内层外再往下接占位成员里的内层结果，最后追加新读到的成员
+fn splice(value: &mut Json, members: &[usize], tail: Json) {
+    match (value, tail) {
+        (Json::Array(items), Json::Array(more)) => {
+            splice_members(items, members, more, |item| item)
+        }
+        (Json::Object(entries), Json::Object(more)) => {
+            splice_members(entries, members, more, |(_, value)| value)
+        }
+        _ => unreachable!("a resumed container keeps its kind"),
+    }
+}
+
+fn splice_members<T>(
+    members: &mut Vec<T>,
+    counts: &[usize],
+    more: Vec<T>,
+    value_of: fn(&mut T) -> &mut Json,
+) {
+    let mut more = more.into_iter();
+    match counts {
+        [count] => members.truncate(*count),
+        [open, inner @ ..] => {
+            members.truncate(open + 1);
+            if let (Some(member), Some(mut placeholder)) = (members.last_mut(), more.next()) {
+                let tail = std::mem::replace(value_of(&mut placeholder), Json::Null);
+                splice(value_of(member), inner, tail);
+            }
+        }
+        [] => {}
+    }
+    members.extend(more);
+}
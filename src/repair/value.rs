@@ -0,0 +1,49 @@
+use super::sink::{PyBuilder, ValueSink};
+use pyo3::prelude::*;
+
+/// 修复解析的中间结果：纯 Rust 数据，构建过程不需要 GIL
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// 超出 i64 的整数：带符号的数字串及其进制，物化时交给 Python `int(s, base)`
+    BigInt {
+        digits: String,
+        radix: u32,
+    },
+    Float(f64),
+    Str(String),
+    Array(Vec<Json>),
+    /// 保留原始顺序与重复 key，物化成 dict 时后者覆盖前者
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// 不带任何 hook 的物化
+    pub fn into_py_object(self, py: Python<'_>) -> PyResult<PyObject> {
+        let mut builder = PyBuilder::new(py, None, None, false);
+        self.drive(&mut builder);
+        builder.finish()
+    }
+
+    /// 容器嵌套层数：标量为 0，`[1]` 为 1
+    pub fn depth(&self) -> usize {
+        match self {
+            Json::Array(items) => 1 + items.iter().map(Json::depth).max().unwrap_or(0),
+            Json::Object(members) => 1 + members.iter().map(|(_, v)| v.depth()).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// 树中值的总数：容器与叶子都算，key 不算
+    pub fn value_count(&self) -> usize {
+        match self {
+            Json::Array(items) => 1 + items.iter().map(Json::value_count).sum::<usize>(),
+            Json::Object(members) => {
+                1 + members.iter().map(|(_, v)| v.value_count()).sum::<usize>()
+            }
+            _ => 1,
+        }
+    }
+}
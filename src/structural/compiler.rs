@@ -1,107 +1,444 @@
-use super::schema::{FieldLookup, SchemaNode, SMALL_MAP_THRESHOLD};
+use super::merge::merge_all_of;
+use super::pattern::Pattern;
+use super::schema::{
+    ArrayBounds, FieldFlags, FieldLookup, KeyRule, NumberBounds, PropertyBounds, RefSlot,
+    SchemaNode, StringFormat, SMALL_MAP_THRESHOLD,
+};
 use ahash::{AHashMap, AHashSet};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString};
 use smallvec::SmallVec;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+
+/// 读取可选的 Schema 关键字
+fn optional<'py, T: FromPyObject<'py>>(schema: &'py PyDict, key: &str) -> PyResult<Option<T>> {
+    schema.get_item(key)?.map(|v| v.extract()).transpose()
+}
+
+fn nullable(schema: &PyDict) -> PyResult<bool> {
+    optional::<&PyAny>(schema, "nullable")?.map_or(Ok(false), |flag| {
+        flag.extract()
+            .map_err(|_| PyValueError::new_err("'nullable' must be a boolean"))
+    })
+}
+
+fn compile_array_bounds(schema: &PyDict) -> PyResult<ArrayBounds> {
+    Ok(ArrayBounds {
+        min_items: optional(schema, "minItems")?,
+        max_items: optional(schema, "maxItems")?,
+        unique_items: optional(schema, "uniqueItems")?.unwrap_or(false),
+    })
+}
+
+/// 显式的 `format` 优先（未知格式也不再推断）；没有时看所有 `examples` 是否同属一种已知格式
+fn compile_string(schema: &PyDict) -> PyResult<SchemaNode> {
+    if let Some(name) = optional::<&str>(schema, "format")? {
+        return Ok(match StringFormat::from_name(name) {
+            Some(format) => SchemaNode::FormattedString {
+                format,
+                inferred: false,
+            },
+            None => SchemaNode::PrimitiveString,
+        });
+    }
+    let examples = optional::<&PyAny>(schema, "examples")?
+        .map(|examples| {
+            examples
+                .downcast::<PyList>()
+                .map_err(|_| PyValueError::new_err("'examples' must be a list"))
+        })
+        .transpose()?;
+    // 有非字符串的例子时不推断
+    let examples: Option<Vec<String>> = examples.and_then(|list| list.extract().ok());
+    Ok(match examples.as_deref().and_then(StringFormat::infer) {
+        Some(format) => SchemaNode::FormattedString {
+            format,
+            inferred: true,
+        },
+        None => SchemaNode::PrimitiveString,
+    })
+}
+
+fn compile_number_bounds(schema: &PyDict) -> PyResult<NumberBounds> {
+    let multiple_of = optional::<&PyAny>(schema, "multipleOf")?
+        .map(|value| match value.extract::<f64>() {
+            Ok(divisor)
+                if divisor > 0.0 && divisor.is_finite() && !value.is_instance_of::<PyBool>() =>
+            {
+                Ok(divisor)
+            }
+            _ => Err(PyValueError::new_err(
+                "'multipleOf' must be a positive number",
+            )),
+        })
+        .transpose()?;
+    Ok(NumberBounds { multiple_of })
+}
+
+fn compile_property_bounds(schema: &PyDict) -> PyResult<PropertyBounds> {
+    Ok(PropertyBounds {
+        min_properties: optional(schema, "minProperties")?,
+        max_properties: optional(schema, "maxProperties")?,
+    })
+}
+
+/// 字段 Schema 上的 `readOnly` / `writeOnly` / `deprecated`；非 dict 的字段 Schema 没有标记
+fn compile_field_flags(schema: &PyAny) -> PyResult<FieldFlags> {
+    let Ok(schema) = schema.downcast::<PyDict>() else {
+        return Ok(FieldFlags::default());
+    };
+    let flag = |key| Ok::<_, PyErr>(optional::<bool>(schema, key)?.unwrap_or(false));
+    Ok(FieldFlags {
+        read_only: flag("readOnly")?,
+        write_only: flag("writeOnly")?,
+        deprecated: flag("deprecated")?,
+    })
+}
+
+/// `propertyNames` 子 Schema 中对字符串的约束；`const` 视为只有一个值的 `enum`
+fn compile_key_rule(schema: &PyAny) -> PyResult<KeyRule> {
+    let schema = schema
+        .downcast::<PyDict>()
+        .map_err(|_| PyValueError::new_err("'propertyNames' must be a schema object"))?;
+    let pattern = optional::<&str>(schema, "pattern")?
+        .map(|source| {
+            Pattern::new(source)
+                .map_err(|e| PyValueError::new_err(format!("Invalid pattern {source:?}: {e}")))
+        })
+        .transpose()?;
+    let mut allowed = optional::<Vec<String>>(schema, "enum")?;
+    if let Some(value) = optional::<String>(schema, "const")? {
+        allowed = Some(match allowed {
+            Some(values) => values.into_iter().filter(|v| *v == value).collect(),
+            None => vec![value],
+        });
+    }
+    Ok(KeyRule {
+        pattern,
+        allowed,
+        min_length: optional(schema, "minLength")?,
+        max_length: optional(schema, "maxLength")?,
+    })
+}
+
+/// 按 JSON Pointer（`#/$defs/Address`）在文档内定位子 Schema；`#` 即文档根
+pub(crate) fn resolve_pointer<'py>(document: &'py PyAny, pointer: &str) -> PyResult<&'py PyAny> {
+    let path = pointer.strip_prefix('#').ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "Only local $ref pointers are supported: {pointer:?}"
+        ))
+    })?;
+    let unresolvable = || PyValueError::new_err(format!("Unresolvable $ref {pointer:?}"));
+
+    let mut node = document;
+    for token in path.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        node = if let Ok(dict) = node.downcast::<PyDict>() {
+            dict.get_item(token.as_str())?.ok_or_else(unresolvable)?
+        } else if let Ok(list) = node.downcast::<PyList>() {
+            let index = token.parse::<usize>().map_err(|_| unresolvable())?;
+            list.get_item(index).map_err(|_| unresolvable())?
+        } else {
+            return Err(unresolvable());
+        };
+    }
+    Ok(node)
+}
+
+/// 按 const 值的 Python 类型选出解析它的叶子
+fn const_leaf(value: &PyAny) -> PyResult<SchemaNode> {
+    // bool 是 int 的子类，必须先判断
+    if value.is_instance_of::<PyBool>() {
+        Ok(SchemaNode::PrimitiveBool)
+    } else if value.is_instance_of::<PyLong>() {
+        Ok(SchemaNode::PrimitiveInteger(NumberBounds::default()))
+    } else if value.is_instance_of::<PyFloat>() {
+        Ok(SchemaNode::PrimitiveNumber(NumberBounds::default()))
+    } else if value.is_instance_of::<PyString>() {
+        Ok(SchemaNode::PrimitiveString)
+    } else if value.is_none() {
+        Ok(SchemaNode::PrimitiveNull)
+    } else {
+        Err(PyValueError::new_err(format!(
+            "Unsupported const value {}: only str, int, float, bool and None are allowed",
+            value.repr()?
+        )))
+    }
+}
+
+/// 类型列表中各类型的尝试顺序：越严格越靠前，integer 先于 number，string 最后
+fn type_priority(name: &str) -> u8 {
+    match name {
+        "null" => 0,
+        "boolean" => 1,
+        "integer" => 2,
+        "number" => 3,
+        "object" => 4,
+        "array" => 5,
+        "string" => 7,
+        _ => 6,
+    }
+}
+
+/// 一次编译的上下文：记录 `$ref` 指针到槽位的映射
+///
+/// `$ref` 编译成指向槽位的弱引用，槽位在整棵树编译完后才填充，
+/// 因此递归定义不会在编译期无限展开；槽位的强引用由根上的 `SchemaNode::Document` 持有。
+struct Compiler<'py> {
+    document: &'py PyAny,
+    slots: AHashMap<String, Arc<RefSlot>>,
+    pending: Vec<(String, Arc<RefSlot>)>,
+}
 
 pub fn compile(schema_obj: &PyAny) -> PyResult<SchemaNode> {
-    if let Ok(schema_dict) = schema_obj.downcast::<PyDict>() {
-        let type_val = schema_dict.get_item("type")?;
-
-        if let Some(t) = type_val {
-            let type_str = t.extract::<String>()?;
-            match type_str.as_str() {
-                "string" => Ok(SchemaNode::PrimitiveString),
-                "integer" | "number" => Ok(SchemaNode::PrimitiveNumber),
-                "boolean" => Ok(SchemaNode::PrimitiveBool),
-                "array" => {
-                    let items = schema_dict.get_item("items")?.ok_or_else(|| {
-                        PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                            "Array schema missing 'items'",
-                        )
-                    })?;
-                    let inner_node = compile(items)?;
-                    Ok(SchemaNode::Array(Arc::new(inner_node)))
-                }
-                "object" => {
-                    let properties = schema_dict.get_item("properties")?;
-                    let required_list = schema_dict.get_item("required")?;
-
-                    let mut fields_vec = SmallVec::new();
-                    let mut fields_map = AHashMap::new();
-                    let mut patterns = Vec::new();
-                    let mut required_set = AHashSet::new();
-
-                    if let Some(props) = properties {
-                        if let Ok(props_dict) = props.downcast::<PyDict>() {
-                            for (k, v) in props_dict {
-                                let key_str = k.extract::<String>()?;
-                                let key_bytes = key_str.as_bytes().to_vec();
-                                let node = Arc::new(compile(v)?);
-
-                                // 构建 Aho-Corasick 模式
-                                // 1. 双引号: "key"
-                                let mut dq = Vec::with_capacity(key_bytes.len() + 2);
-                                dq.push(b'"');
-                                dq.extend_from_slice(&key_bytes);
-                                dq.push(b'"');
-                                patterns.push(dq);
-
-                                // 2. 单引号: 'key'
-                                let mut sq = Vec::with_capacity(key_bytes.len() + 2);
-                                sq.push(b'\'');
-                                sq.extend_from_slice(&key_bytes);
-                                sq.push(b'\'');
-                                patterns.push(sq);
-
-                                if props_dict.len() < SMALL_MAP_THRESHOLD {
-                                    fields_vec.push((key_bytes.clone(), node.clone()));
-                                } else {
-                                    fields_map.insert(key_bytes.clone(), node.clone());
-                                }
-                            }
-                        }
-                    }
+    let mut compiler = Compiler {
+        document: schema_obj,
+        slots: AHashMap::new(),
+        pending: Vec::new(),
+    };
+    let root = compiler.compile_node(schema_obj)?;
+    compiler.link_refs()?;
+
+    if compiler.slots.is_empty() {
+        return Ok(root);
+    }
+    Ok(SchemaNode::Document {
+        root: Arc::new(root),
+        slots: compiler.slots.into_values().collect(),
+    })
+}
+
+impl<'py> Compiler<'py> {
+    /// 逐个编译被引用的定义；编译过程中新发现的 `$ref` 继续排队
+    fn link_refs(&mut self) -> PyResult<()> {
+        while let Some((pointer, slot)) = self.pending.pop() {
+            let target = resolve_pointer(self.document, &pointer)?;
+            let node = self.compile_node(target)?;
+            // 每个指针只入队一次，槽位不会被重复填充
+            let _ = slot.set(node);
+        }
+        Ok(())
+    }
+
+    /// 同一指针共用一个槽位
+    fn compile_ref(&mut self, pointer: &str) -> SchemaNode {
+        let slot = self.slots.entry(pointer.to_string()).or_insert_with(|| {
+            let slot = Arc::new(OnceLock::new());
+            self.pending.push((pointer.to_string(), slot.clone()));
+            slot
+        });
+        SchemaNode::Ref(Arc::downgrade(slot))
+    }
 
-                    if let Some(req) = required_list {
-                        if let Ok(req_list) = req.downcast::<PyList>() {
-                            for item in req_list {
-                                let req_str = item.extract::<String>()?;
-                                required_set.insert(req_str.as_bytes().to_vec());
-                            }
-                        }
+    /// 编译 anyOf / oneOf 的分支列表
+    fn compile_alternatives(&mut self, list: &PyAny, keyword: &str) -> PyResult<Vec<SchemaNode>> {
+        let list = list
+            .downcast::<PyList>()
+            .map_err(|_| PyValueError::new_err(format!("'{keyword}' must be a list")))?;
+        list.iter().map(|item| self.compile_node(item)).collect()
+    }
+
+    fn compile_node(&mut self, schema_obj: &PyAny) -> PyResult<SchemaNode> {
+        let Ok(schema_dict) = schema_obj.downcast::<PyDict>() else {
+            // Not a dict, maybe a string (primitive type shorthand)?
+            // For now, just return Any
+            return Ok(SchemaNode::Any);
+        };
+
+        if schema_dict.contains("allOf")? {
+            let merged = merge_all_of(self.document, schema_dict)?;
+            return self.compile_node(merged);
+        }
+        if nullable(schema_dict)? {
+            // OpenAPI 的 `nullable: true`：等价于在原类型之外再接受 null，null 先试
+            let inner = schema_dict.copy()?;
+            inner.del_item("nullable")?;
+            return Ok(SchemaNode::AnyOf(vec![
+                SchemaNode::PrimitiveNull,
+                self.compile_node(inner)?,
+            ]));
+        }
+        if let Some(pointer) = optional::<&str>(schema_dict, "$ref")? {
+            return Ok(self.compile_ref(pointer));
+        }
+        if let Some(value) = schema_dict.get_item("const")? {
+            return Ok(SchemaNode::Const {
+                value: value.into(),
+                inner: Arc::new(const_leaf(value)?),
+            });
+        }
+        if let Some(values) = schema_dict.get_item("enum")? {
+            let values = values
+                .extract::<Vec<String>>()
+                .map_err(|_| PyValueError::new_err("'enum' must be a list of strings"))?;
+            return Ok(SchemaNode::Enum(values));
+        }
+        if let Some(options) = schema_dict.get_item("anyOf")? {
+            return Ok(SchemaNode::AnyOf(
+                self.compile_alternatives(options, "anyOf")?,
+            ));
+        }
+        if let Some(options) = schema_dict.get_item("oneOf")? {
+            return Ok(SchemaNode::OneOf(
+                self.compile_alternatives(options, "oneOf")?,
+            ));
+        }
+
+        let Some(t) = schema_dict.get_item("type")? else {
+            // No type specified, assume Any
+            return Ok(SchemaNode::Any);
+        };
+        if let Ok(types) = t.downcast::<PyList>() {
+            return self.compile_type_list(schema_dict, types);
+        }
+        self.compile_type(schema_dict, &t.extract::<String>()?)
+    }
+
+    /// `"type": ["string", "null"]`：按类型逐个编译，组成 anyOf
+    fn compile_type_list(&mut self, schema_dict: &PyDict, types: &PyList) -> PyResult<SchemaNode> {
+        let mut names = types
+            .extract::<Vec<String>>()
+            .map_err(|_| PyValueError::new_err("'type' must be a string or a list of strings"))?;
+        if names.is_empty() {
+            return Err(PyValueError::new_err("'type' list must not be empty"));
+        }
+        // 字符串叶子是宽松的，会接住 null/true 等字面量，必须最后尝试
+        names.sort_by_key(|name| type_priority(name));
+        names.dedup();
+        if names.len() == 1 {
+            return self.compile_type(schema_dict, &names[0]);
+        }
+        let alternatives = names
+            .iter()
+            .map(|name| self.compile_type(schema_dict, name))
+            .collect::<PyResult<_>>()?;
+        Ok(SchemaNode::AnyOf(alternatives))
+    }
+
+    fn compile_type(&mut self, schema_dict: &PyDict, type_str: &str) -> PyResult<SchemaNode> {
+        match type_str {
+            "string" => compile_string(schema_dict),
+            "number" => Ok(SchemaNode::PrimitiveNumber(compile_number_bounds(
+                schema_dict,
+            )?)),
+            "integer" => Ok(SchemaNode::PrimitiveInteger(compile_number_bounds(
+                schema_dict,
+            )?)),
+            "boolean" => Ok(SchemaNode::PrimitiveBool),
+            "null" => Ok(SchemaNode::PrimitiveNull),
+            "array" => {
+                let items = schema_dict
+                    .get_item("items")?
+                    .ok_or_else(|| PyValueError::new_err("Array schema missing 'items'"))?;
+                let inner_node = self.compile_node(items)?;
+                Ok(SchemaNode::Array {
+                    items: Arc::new(inner_node),
+                    bounds: compile_array_bounds(schema_dict)?,
+                })
+            }
+            "object" => self.compile_object(schema_dict),
+            _ => Ok(SchemaNode::Any),
+        }
+    }
+
+    fn compile_object(&mut self, schema_dict: &PyDict) -> PyResult<SchemaNode> {
+        let properties = schema_dict.get_item("properties")?;
+        let names = schema_dict
+            .get_item("propertyNames")?
+            .map(compile_key_rule)
+            .transpose()?;
+        if let (None, Some(names)) = (properties, &names) {
+            return self.compile_map(schema_dict, names.clone());
+        }
+        let required_list = schema_dict.get_item("required")?;
+
+        let mut fields_vec = SmallVec::new();
+        let mut fields_map = AHashMap::new();
+        let mut patterns = Vec::new();
+        let mut required_set = AHashSet::new();
+        let mut flags = AHashMap::new();
+
+        if let Some(props) = properties {
+            if let Ok(props_dict) = props.downcast::<PyDict>() {
+                for (k, v) in props_dict {
+                    let key_str = k.extract::<String>()?;
+                    // 只会输出声明过的字段，因此 propertyNames 在编译期检查即可
+                    if names.as_ref().is_some_and(|names| !names.accepts(&key_str)) {
+                        return Err(PyValueError::new_err(format!(
+                            "Property {key_str:?} does not match propertyNames"
+                        )));
+                    }
+                    let key_bytes = key_str.as_bytes().to_vec();
+                    let node = Arc::new(self.compile_node(v)?);
+                    let field_flags = compile_field_flags(v)?;
+                    if field_flags != FieldFlags::default() {
+                        flags.insert(key_bytes.clone(), field_flags);
                     }
 
-                    let fields = if fields_map.is_empty() && !fields_vec.is_empty() {
-                        FieldLookup::Small(fields_vec)
+                    // 构建 Aho-Corasick 模式
+                    // 1. 双引号: "key"
+                    let mut dq = Vec::with_capacity(key_bytes.len() + 2);
+                    dq.push(b'"');
+                    dq.extend_from_slice(&key_bytes);
+                    dq.push(b'"');
+                    patterns.push(dq);
+
+                    // 2. 单引号: 'key'
+                    let mut sq = Vec::with_capacity(key_bytes.len() + 2);
+                    sq.push(b'\'');
+                    sq.extend_from_slice(&key_bytes);
+                    sq.push(b'\'');
+                    patterns.push(sq);
+
+                    if props_dict.len() < SMALL_MAP_THRESHOLD {
+                        fields_vec.push((key_bytes.clone(), node.clone()));
                     } else {
-                        FieldLookup::Large(fields_map)
-                    };
-
-                    // 构建 AC 自动机
-                    let ac = aho_corasick::AhoCorasick::new(&patterns).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                            "Failed to build Aho-Corasick automaton: {}",
-                            e
-                        ))
-                    })?;
-
-                    Ok(SchemaNode::Object {
-                        fields,
-                        required: required_set,
-                        ac: Arc::new(ac),
-                    })
+                        fields_map.insert(key_bytes.clone(), node.clone());
+                    }
                 }
-                _ => Ok(SchemaNode::Any),
             }
-        } else {
-            // No type specified, assume Any
-            Ok(SchemaNode::Any)
         }
-    } else {
-        // Not a dict, maybe a string (primitive type shorthand)?
-        // For now, just return Any
-        Ok(SchemaNode::Any)
+
+        if let Some(req) = required_list {
+            if let Ok(req_list) = req.downcast::<PyList>() {
+                for item in req_list {
+                    let req_str = item.extract::<String>()?;
+                    required_set.insert(req_str.as_bytes().to_vec());
+                }
+            }
+        }
+
+        let fields = if fields_map.is_empty() && !fields_vec.is_empty() {
+            FieldLookup::Small(fields_vec)
+        } else {
+            FieldLookup::Large(fields_map)
+        };
+
+        // 构建 AC 自动机
+        let ac = aho_corasick::AhoCorasick::new(&patterns).map_err(|e| {
+            PyValueError::new_err(format!("Failed to build Aho-Corasick automaton: {}", e))
+        })?;
+
+        Ok(SchemaNode::Object {
+            fields,
+            required: required_set,
+            ac: Arc::new(ac),
+            bounds: compile_property_bounds(schema_dict)?,
+            flags,
+        })
+    }
+
+    /// 动态对象：值按 `additionalProperties`（缺省为任意值）解析
+    fn compile_map(&mut self, schema_dict: &PyDict, names: KeyRule) -> PyResult<SchemaNode> {
+        let values = match schema_dict.get_item("additionalProperties")? {
+            Some(values) if values.is_instance_of::<PyDict>() => self.compile_node(values)?,
+            _ => SchemaNode::Any,
+        };
+        Ok(SchemaNode::Map {
+            names,
+            values: Arc::new(values),
+            bounds: compile_property_bounds(schema_dict)?,
+        })
     }
 }
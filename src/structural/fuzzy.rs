@@ -0,0 +1,80 @@
+//! 近似匹配：enum 的规范化比较与编辑距离吸附，以及对象 key 的规范化
+
+use super::options::KeyNormalization;
+
+/// 在 `values` 中为 `raw` 找规范写法。
+///
+/// 精确匹配优先；`fuzzy` 时再比较去空白、忽略大小写后的形式，
+/// 最后在 `max_distance` 以内取编辑距离最小的候选（距离相同取先声明的）。
+pub fn match_enum<'a>(
+    values: &'a [String],
+    raw: &str,
+    fuzzy: bool,
+    max_distance: usize,
+) -> Option<&'a str> {
+    if let Some(exact) = values.iter().find(|v| v.as_str() == raw) {
+        return Some(exact);
+    }
+    if !fuzzy {
+        return None;
+    }
+
+    let wanted = normalize(raw);
+    let mut best: Option<(usize, &str)> = None;
+    for value in values {
+        let distance = levenshtein(&wanted, &normalize(value));
+        if distance <= max_distance && best.is_none_or(|(d, _)| distance < d) {
+            best = Some((distance, value));
+        }
+    }
+    best.map(|(_, value)| value)
+}
+
+fn normalize(s: &str) -> Vec<char> {
+    s.trim().chars().flat_map(char::to_lowercase).collect()
+}
+
+/// 按字符计的编辑距离，单行滚动数组
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// 按 `mode` 规范化对象 key；Schema 字段名与输入 key 用同一规则比较
+pub fn normalize_key(key: &str, mode: KeyNormalization) -> String {
+    match mode {
+        KeyNormalization::Exact => key.to_string(),
+        KeyNormalization::Trim => key.trim().to_string(),
+        KeyNormalization::Lower => key.trim().to_lowercase(),
+        KeyNormalization::Snake => snake_case(key.trim()),
+    }
+}
+
+/// 空白与 `-` 变 `_`，小写/数字后的大写字母前补 `_`，全部转小写
+fn snake_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len() + 4);
+    let mut prev: Option<char> = None;
+    for ch in key.chars() {
+        if ch.is_whitespace() || ch == '-' || ch == '_' {
+            if !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+        } else {
+            if ch.is_uppercase() && prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()) {
+                out.push('_');
+            }
+            out.extend(ch.to_lowercase());
+        }
+        prev = Some(ch);
+    }
+    out
+}
@@ -0,0 +1,195 @@
+//! `allOf` 在编译期合并成一个等效的 Schema dict，解析器只会看到合并后的单个节点
+//!
+//! `properties` 取并集（同名字段再包一层 `allOf` 递归合并），`required` 拼接去重，
+//! 下界取最大、上界取最小，`type` 取交集；交集为空时编译报错。
+
+use super::compiler::resolve_pointer;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+/// 合并时取较大值的下界关键字
+const LOWER_BOUNDS: [&str; 5] = [
+    "minimum",
+    "exclusiveMinimum",
+    "minLength",
+    "minItems",
+    "minProperties",
+];
+
+/// 合并时取较小值的上界关键字
+const UPPER_BOUNDS: [&str; 5] = [
+    "maximum",
+    "exclusiveMaximum",
+    "maxLength",
+    "maxItems",
+    "maxProperties",
+];
+
+/// 子 Schema 都要满足的关键字：两边都有时包成 `allOf`，编译时再递归合并
+const NESTED: [&str; 3] = ["items", "additionalProperties", "propertyNames"];
+
+/// 把带 `allOf` 的 Schema（连同它的兄弟关键字）展开成一个不含 `allOf` 的 dict
+pub fn merge_all_of<'py>(document: &'py PyAny, schema: &'py PyDict) -> PyResult<&'py PyDict> {
+    let merged = PyDict::new(schema.py());
+    absorb(document, merged, schema, &mut Vec::new())?;
+    Ok(merged)
+}
+
+/// 将一个成员并入 `merged`；`$ref` 与嵌套的 `allOf` 就地展开，`visiting` 用来发现循环引用
+fn absorb<'py>(
+    document: &'py PyAny,
+    merged: &'py PyDict,
+    member: &'py PyAny,
+    visiting: &mut Vec<String>,
+) -> PyResult<()> {
+    let member = member
+        .downcast::<PyDict>()
+        .map_err(|_| PyValueError::new_err("'allOf' members must be schema objects"))?;
+    if let Some(pointer) = member.get_item("$ref")? {
+        let pointer = pointer.extract::<String>()?;
+        if visiting.contains(&pointer) {
+            return Err(PyValueError::new_err(format!(
+                "Recursive $ref {pointer:?} inside allOf cannot be merged"
+            )));
+        }
+        let target = resolve_pointer(document, &pointer)?;
+        visiting.push(pointer);
+        absorb(document, merged, target, visiting)?;
+        visiting.pop();
+    }
+    if let Some(members) = member.get_item("allOf")? {
+        let members = members
+            .downcast::<PyList>()
+            .map_err(|_| PyValueError::new_err("'allOf' must be a list"))?;
+        for sub in members {
+            absorb(document, merged, sub, visiting)?;
+        }
+    }
+    for (key, value) in member {
+        let key = key.extract::<&str>()?;
+        if key != "$ref" && key != "allOf" {
+            merge_keyword(merged, key, value)?;
+        }
+    }
+    Ok(())
+}
+
+fn merge_keyword(merged: &PyDict, key: &str, value: &PyAny) -> PyResult<()> {
+    let Some(existing) = merged.get_item(key)? else {
+        return merged.set_item(key, value);
+    };
+    let combined = match key {
+        "type" => merge_types(existing, value)?,
+        "properties" => merge_properties(existing, value)?,
+        "required" => concat_unique(existing, value)?,
+        "enum" => intersect_enum(existing, value)?,
+        "const" if !existing.eq(value)? => {
+            return Err(PyValueError::new_err(format!(
+                "Conflicting const values in allOf: {} and {}",
+                existing.repr()?,
+                value.repr()?
+            )))
+        }
+        "uniqueItems" => (existing.is_true()? || value.is_true()?).into_py(merged.py()),
+        _ if LOWER_BOUNDS.contains(&key) => pick(existing, value, value.gt(existing)?),
+        _ if UPPER_BOUNDS.contains(&key) => pick(existing, value, value.lt(existing)?),
+        _ if NESTED.contains(&key) && existing.is_instance_of::<PyDict>() => {
+            both_of(merged.py(), existing, value)?
+        }
+        // 其余关键字先出现的生效
+        _ => return Ok(()),
+    };
+    merged.set_item(key, combined)
+}
+
+fn pick(existing: &PyAny, value: &PyAny, take_new: bool) -> PyObject {
+    if take_new { value } else { existing }.into()
+}
+
+/// `{"allOf": [a, b]}`，留给编译器递归合并
+fn both_of(py: Python<'_>, a: &PyAny, b: &PyAny) -> PyResult<PyObject> {
+    let wrapped = PyDict::new(py);
+    wrapped.set_item("allOf", PyList::new(py, [a, b]))?;
+    Ok(wrapped.into())
+}
+
+/// `type` 取交集；`integer` 是 `number` 的子集
+fn merge_types(existing: &PyAny, value: &PyAny) -> PyResult<PyObject> {
+    let left = type_names(existing)?;
+    let right = type_names(value)?;
+    let mut common = Vec::new();
+    for a in &left {
+        for b in &right {
+            let name = match (a.as_str(), b.as_str()) {
+                (a, b) if a == b => a,
+                ("number", "integer") | ("integer", "number") => "integer",
+                _ => continue,
+            };
+            if !common.contains(&name) {
+                common.push(name);
+            }
+        }
+    }
+    let py = existing.py();
+    match common.as_slice() {
+        [] => Err(PyValueError::new_err(format!(
+            "Conflicting types in allOf: {left:?} and {right:?}"
+        ))),
+        [name] => Ok(name.into_py(py)),
+        names => Ok(PyList::new(py, names).into()),
+    }
+}
+
+fn type_names(value: &PyAny) -> PyResult<Vec<String>> {
+    if let Ok(name) = value.extract::<String>() {
+        return Ok(vec![name]);
+    }
+    value
+        .extract()
+        .map_err(|_| PyValueError::new_err("'type' must be a string or a list of strings"))
+}
+
+/// 字段取并集，同名字段的 Schema 合并为 `allOf`
+fn merge_properties(existing: &PyAny, value: &PyAny) -> PyResult<PyObject> {
+    let (Ok(existing), Ok(value)) = (existing.downcast::<PyDict>(), value.downcast::<PyDict>())
+    else {
+        return Err(PyValueError::new_err("'properties' must be an object"));
+    };
+    let py = existing.py();
+    let combined = existing.copy()?;
+    for (name, schema) in value {
+        let merged = match combined.get_item(name)? {
+            Some(previous) => both_of(py, previous, schema)?,
+            None => schema.into(),
+        };
+        combined.set_item(name, merged)?;
+    }
+    Ok(combined.into())
+}
+
+fn concat_unique(existing: &PyAny, value: &PyAny) -> PyResult<PyObject> {
+    let mut names = existing.extract::<Vec<String>>()?;
+    for name in value.extract::<Vec<String>>()? {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    Ok(names.into_py(existing.py()))
+}
+
+/// 两边都允许的枚举值，保持前者的顺序
+fn intersect_enum(existing: &PyAny, value: &PyAny) -> PyResult<PyObject> {
+    let allowed = value.extract::<Vec<String>>()?;
+    let common: Vec<String> = existing
+        .extract::<Vec<String>>()?
+        .into_iter()
+        .filter(|v| allowed.contains(v))
+        .collect();
+    if common.is_empty() {
+        return Err(PyValueError::new_err(
+            "Conflicting enum values in allOf: no value satisfies every member",
+        ));
+    }
+    Ok(common.into_py(existing.py()))
+}
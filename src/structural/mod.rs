@@ -1,3 +1,8 @@
-pub mod schema;
-pub mod parser;
 pub mod compiler;
+mod fuzzy;
+mod merge;
+pub mod options;
+pub mod parser;
+pub mod pattern;
+pub mod schema;
+pub mod validate;
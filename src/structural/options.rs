@@ -0,0 +1,159 @@
+use super::schema::FieldFlags;
+use ahash::AHashMap;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// 在 Schema 的 properties 中查找 key 前如何规范化
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyNormalization {
+    /// 逐字节精确匹配
+    #[default]
+    Exact,
+    /// 去掉首尾空白
+    Trim,
+    /// 去掉首尾空白并转小写
+    Lower,
+    /// 去掉首尾空白后转 snake_case：`First Name`、`firstName` 都变成 `first_name`
+    Snake,
+}
+
+impl KeyNormalization {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "exact" => Ok(Self::Exact),
+            "trim" => Ok(Self::Trim),
+            "lower" => Ok(Self::Lower),
+            "snake" => Ok(Self::Snake),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown key_normalization {other:?}"
+            ))),
+        }
+    }
+}
+
+/// 按提取方向丢弃字段：`Read` 丢弃 `writeOnly`，`Write` 丢弃 `readOnly`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropMode {
+    Read,
+    Write,
+}
+
+impl DropMode {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "read" => Ok(Self::Read),
+            "write" => Ok(Self::Write),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown drop_mode {other:?}"
+            ))),
+        }
+    }
+}
+
+/// 结构化提取的行为开关，默认值即严格匹配
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// enum 匹配前先去掉首尾空白并忽略大小写
+    pub enum_fuzzy: bool,
+    /// `enum_fuzzy` 下仍无精确匹配时，吸附到编辑距离不超过该值的最近候选
+    pub enum_max_distance: usize,
+    /// 数字里的全角数字 `０`–`９` 与全角 `＋` `－` `．` 按 ASCII 解析
+    pub normalize_unicode_digits: bool,
+    /// 查找字段前对 key 的规范化方式
+    pub key_normalization: KeyNormalization,
+    /// 校验带 `format` 的字符串，并把 `date-time`/`date`/`time` 转成 `datetime` 对象
+    pub coerce_formats: bool,
+    /// boolean 叶子额外接受的真值写法（如 `yes`、`on`、`1`），存为去空白后的小写
+    pub truthy_words: Vec<String>,
+    /// boolean 叶子额外接受的假值写法（如 `no`、`off`、`0`），存为去空白后的小写
+    pub falsy_words: Vec<String>,
+    /// array 节点遇到的不是 `[` 时，把这一个值按 `items` 解析后包成单元素列表
+    pub coerce_scalar_to_array: bool,
+    /// number / integer 叶子按千分位读数：`1,234.56`、`1 234,56`；分组不规整时逗号结束数字，
+    /// 因此 `[1,2,3]` 不会被读成 `123`
+    pub parse_grouped_numbers: bool,
+    /// 字段名 → 兜底值：该字段的值解析或校验失败时跳过原值，改用兜底值并记一条 `field_fallback`
+    pub field_fallbacks: AHashMap<String, PyObject>,
+    /// 丢弃与提取方向冲突的字段（连同其必填要求），值不解析也不校验
+    pub drop_mode: Option<DropMode>,
+    /// 同时丢弃 `deprecated` 字段
+    pub drop_deprecated: bool,
+}
+
+/// 从 Python 的字符串集合读出同义词，统一为去空白后的小写
+fn bool_words(words: &PyAny, name: &str) -> PyResult<Vec<String>> {
+    if words.extract::<&str>().is_ok() {
+        return Err(PyTypeError::new_err(format!(
+            "{name} must be a collection of strings, not a string"
+        )));
+    }
+    words
+        .iter()?
+        .map(|word| Ok(word?.extract::<&str>()?.trim().to_lowercase()))
+        .collect()
+}
+
+fn fallbacks(mapping: &PyAny) -> PyResult<AHashMap<String, PyObject>> {
+    let mapping = mapping
+        .downcast::<PyDict>()
+        .map_err(|_| PyTypeError::new_err("field_fallbacks must be a dict"))?;
+    mapping
+        .iter()
+        .map(|(name, value)| Ok((name.extract()?, value.into())))
+        .collect()
+}
+
+impl ExtractOptions {
+    /// 带这些标记的字段是否应从结果中丢弃
+    pub fn drops(&self, flags: Option<&FieldFlags>) -> bool {
+        let Some(flags) = flags else {
+            return false;
+        };
+        let conflicts = match self.drop_mode {
+            Some(DropMode::Read) => flags.write_only,
+            Some(DropMode::Write) => flags.read_only,
+            None => false,
+        };
+        conflicts || (self.drop_deprecated && flags.deprecated)
+    }
+
+    /// 从 Python 关键字参数构造，未知参数名直接报 TypeError
+    pub fn from_kwargs(kwargs: Option<&PyDict>) -> PyResult<Self> {
+        let mut opts = Self::default();
+        let Some(kwargs) = kwargs else {
+            return Ok(opts);
+        };
+
+        for (key, value) in kwargs {
+            let key: &str = key.extract()?;
+            match key {
+                "enum_fuzzy" => opts.enum_fuzzy = value.extract()?,
+                "enum_max_distance" => opts.enum_max_distance = value.extract()?,
+                "normalize_unicode_digits" => opts.normalize_unicode_digits = value.extract()?,
+                "coerce_formats" => opts.coerce_formats = value.extract()?,
+                "truthy_words" => opts.truthy_words = bool_words(value, key)?,
+                "falsy_words" => opts.falsy_words = bool_words(value, key)?,
+                "coerce_scalar_to_array" => opts.coerce_scalar_to_array = value.extract()?,
+                "parse_grouped_numbers" => opts.parse_grouped_numbers = value.extract()?,
+                "field_fallbacks" => opts.field_fallbacks = fallbacks(value)?,
+                "drop_mode" => {
+                    opts.drop_mode = value
+                        .extract::<Option<&str>>()?
+                        .map(DropMode::from_name)
+                        .transpose()?
+                }
+                "drop_deprecated" => opts.drop_deprecated = value.extract()?,
+                "key_normalization" => {
+                    opts.key_normalization = KeyNormalization::from_name(value.extract()?)?
+                }
+                other => {
+                    return Err(PyTypeError::new_err(format!(
+                        "Unexpected keyword argument {other:?}"
+                    )))
+                }
+            }
+        }
+        Ok(opts)
+    }
+}
@@ -1,8 +1,19 @@
-use super::schema::{FieldLookup, SchemaNode};
+use super::fuzzy;
+use super::options::{ExtractOptions, KeyNormalization};
+use super::schema::{
+    ArrayBounds, FieldFlags, FieldLookup, KeyRule, NumberBounds, PropertyBounds, SchemaNode,
+    StringFormat,
+};
+use crate::repair::{Repair, RepairKind};
 use crate::utils::cursor::Cursor;
+use crate::utils::unicode::ascii_number_char;
+use ahash::{AHashMap, AHashSet};
 use pyo3::prelude::*;
-use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyString};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString};
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::fmt;
+use std::sync::{Arc, OnceLock};
 
 #[derive(Debug)]
 pub enum ParseError {
@@ -10,19 +21,105 @@ pub enum ParseError {
     MissingField(String),
     InvalidUtf8,
     UnexpectedEof,
+    /// 值与 Schema 声明的类型不符，携带期望的类型名
+    TypeMismatch(&'static str),
+    /// anyOf / oneOf 的所有分支都无法解析
+    NoAlternativeMatched,
+    /// oneOf 有多个分支同时匹配
+    AmbiguousAlternatives,
+    /// integer 叶子拿到了带非零小数部分（或非有限）的数字
+    NotAnInteger(f64),
+    /// 不是 multipleOf 的整数倍：(除数, 实际值)
+    NotMultipleOf(f64, f64),
+    /// `$ref` 的目标已随编译结果释放
+    DanglingRef,
+    /// 与 const 不相等，携带 const 的 repr
+    ConstMismatch(String),
+    /// 字符串不在 enum 中
+    NotInEnum(String),
+    /// 数组元素少于 minItems：(下限, 实际个数)
+    TooFewItems(usize, usize),
+    /// 数组元素多于 maxItems：(上限, 实际个数)
+    TooManyItems(usize, usize),
+    /// 对象字段少于 minProperties：(下限, 实际个数)
+    TooFewProperties(usize, usize),
+    /// 对象字段多于 maxProperties：(上限, 实际个数)
+    TooManyProperties(usize, usize),
+    /// uniqueItems 下出现重复：(先出现的下标, 重复的下标)
+    DuplicateItems(usize, usize),
+    /// 字符串不符合 `format`：(格式名, 原值)
+    InvalidFormat(&'static str, String),
+    /// 动态对象的 key 不满足 propertyNames
+    InvalidPropertyName(String),
+    /// 发生在对象字段或数组元素内部的错误，路径从外到内
+    InPath(Vec<PathSegment>, Box<ParseError>),
+}
+
+/// 错误路径中的一段：对象 key 或数组下标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl ParseError {
+    /// 错误穿出一层容器时，在路径最前面补上这一层
+    pub(crate) fn within(self, segment: PathSegment) -> ParseError {
+        match self {
+            ParseError::InPath(mut path, error) => {
+                path.insert(0, segment);
+                ParseError::InPath(path, error)
+            }
+            error => ParseError::InPath(vec![segment], Box::new(error)),
+        }
+    }
+
+    /// 拆出路径与最内层的错误
+    pub fn into_parts(self) -> (Vec<PathSegment>, ParseError) {
+        match self {
+            ParseError::InPath(path, error) => (path, *error),
+            error => (Vec::new(), error),
+        }
+    }
+
+    /// 最内层的错误，决定 Python 异常类型
+    pub fn cause(&self) -> &ParseError {
+        match self {
+            ParseError::InPath(_, error) => error,
+            error => error,
+        }
+    }
+}
+
+/// 以 `data` 为根渲染路径，如 `data.items[3]`；非标识符 key 用 `["..."]`
+pub fn format_path(path: &[PathSegment]) -> String {
+    let mut out = String::from("data");
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) if is_identifier(key) => {
+                out.push('.');
+                out.push_str(key);
+            }
+            PathSegment::Key(key) => out.push_str(&format!("[{key:?}]")),
+            PathSegment::Index(index) => out.push_str(&format!("[{index}]")),
+        }
+    }
+    out
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
 }
 
 impl From<ParseError> for PyErr {
     fn from(err: ParseError) -> PyErr {
-        match err {
+        match err.cause() {
             ParseError::RecursionLimit => {
-                pyo3::exceptions::PyRecursionError::new_err("Recursion limit reached")
+                pyo3::exceptions::PyRecursionError::new_err(err.to_string())
             }
-            ParseError::MissingField(f) => {
-                pyo3::exceptions::PyValueError::new_err(format!("Missing field: {}", f))
-            }
-            ParseError::InvalidUtf8 => pyo3::exceptions::PyValueError::new_err("Invalid UTF-8"),
-            ParseError::UnexpectedEof => pyo3::exceptions::PyValueError::new_err("Unexpected EOF"),
+            _ => pyo3::exceptions::PyValueError::new_err(err.to_string()),
         }
     }
 }
@@ -34,16 +131,141 @@ impl fmt::Display for ParseError {
             ParseError::MissingField(field) => write!(f, "Missing field: {}", field),
             ParseError::InvalidUtf8 => write!(f, "Invalid UTF-8"),
             ParseError::UnexpectedEof => write!(f, "Unexpected EOF"),
+            ParseError::TypeMismatch(expected) => write!(f, "Expected {}", expected),
+            ParseError::NoAlternativeMatched => write!(f, "No schema alternative matched"),
+            ParseError::AmbiguousAlternatives => write!(f, "Multiple oneOf alternatives matched"),
+            ParseError::NotAnInteger(value) => write!(f, "Expected integer, got {}", value),
+            ParseError::NotMultipleOf(divisor, value) => {
+                write!(f, "Expected a multiple of {}, found {}", divisor, value)
+            }
+            ParseError::DanglingRef => write!(f, "Schema $ref target is no longer available"),
+            ParseError::ConstMismatch(expected) => write!(f, "Expected const value {}", expected),
+            ParseError::NotInEnum(value) => {
+                write!(f, "Value {:?} is not one of the enum values", value)
+            }
+            ParseError::TooFewItems(min, found) => {
+                write!(f, "Expected at least {} array items, found {}", min, found)
+            }
+            ParseError::TooManyItems(max, found) => {
+                write!(f, "Expected at most {} array items, found {}", max, found)
+            }
+            ParseError::TooFewProperties(min, found) => {
+                write!(f, "Expected at least {} properties, found {}", min, found)
+            }
+            ParseError::TooManyProperties(max, found) => {
+                write!(f, "Expected at most {} properties, found {}", max, found)
+            }
+            ParseError::DuplicateItems(first, dup) => {
+                write!(
+                    f,
+                    "Array items must be unique: item {} duplicates item {}",
+                    dup, first
+                )
+            }
+            ParseError::InvalidFormat(format, value) => {
+                write!(f, "Value {:?} is not a valid {}", value, format)
+            }
+            ParseError::InvalidPropertyName(key) => {
+                write!(f, "Property name {:?} does not match propertyNames", key)
+            }
+            ParseError::InPath(path, error) => {
+                write!(f, "validation failed at {}: {}", format_path(path), error)
+            }
         }
     }
 }
 
-const MAX_DEPTH: usize = 128;
+/// 带输入位置的解析错误，定位到解析停下的地方
+#[derive(Debug)]
+pub struct LocatedError {
+    pub error: ParseError,
+    /// 出错值在结果中的位置，空表示根
+    pub path: Vec<PathSegment>,
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LocatedError {
+    fn new(error: ParseError, cursor: &Cursor) -> Self {
+        let (offset, line, column) = cursor.position();
+        let (path, error) = error.into_parts();
+        LocatedError {
+            error,
+            path,
+            offset,
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for LocatedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "validation failed at {}: ", format_path(&self.path))?;
+        }
+        write!(
+            f,
+            "{} at line {}, column {} (offset {})",
+            self.error, self.line, self.column, self.offset
+        )
+    }
+}
+
+impl From<LocatedError> for PyErr {
+    fn from(err: LocatedError) -> PyErr {
+        let message = err.to_string();
+        match err.error {
+            ParseError::RecursionLimit => pyo3::exceptions::PyRecursionError::new_err(message),
+            _ => pyo3::exceptions::PyValueError::new_err(message),
+        }
+    }
+}
+
+thread_local! {
+    /// 本线程最近一次 `parse_root` 中用上 `field_fallbacks` 的字段；解析是同步的，免得把日志穿过每一层
+    static FALLBACKS: RefCell<Vec<Repair>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(crate) const MAX_DEPTH: usize = 128;
 const MAX_STRING_LEN: usize = 1024 * 1024; // 1MB
 
+/// 从游标当前位置按 Schema 解析一个值，失败时附带出错位置
+pub fn parse_root<'py>(
+    cursor: &mut Cursor,
+    schema: &SchemaNode,
+    opts: &ExtractOptions,
+    py: Python<'py>,
+) -> Result<PyObject, LocatedError> {
+    FALLBACKS.with(|log| log.borrow_mut().clear());
+    parse_node(cursor, schema, opts, py, 0).map_err(|e| LocatedError::new(e, cursor))
+}
+
+/// 同 `parse_root`，另外返回用上兜底值的字段（`RepairKind::FieldFallback`，offset 为原值起点）
+pub fn parse_root_verbose<'py>(
+    cursor: &mut Cursor,
+    schema: &SchemaNode,
+    opts: &ExtractOptions,
+    py: Python<'py>,
+) -> Result<(PyObject, Vec<Repair>), LocatedError> {
+    let value = parse_root(cursor, schema, opts, py)?;
+    Ok((value, FALLBACKS.with(|log| log.take())))
+}
+
+/// 当前已记录的兜底条数，配合 `discard_fallbacks_since` 撤销被回滚的推测解析里记下的兜底
+fn fallbacks_mark() -> usize {
+    FALLBACKS.with(|log| log.borrow().len())
+}
+
+fn discard_fallbacks_since(mark: usize) {
+    FALLBACKS.with(|log| log.borrow_mut().truncate(mark));
+}
+
 pub fn parse_node<'py>(
     cursor: &mut Cursor,
     schema: &SchemaNode,
+    opts: &ExtractOptions,
     py: Python<'py>,
     depth: usize,
 ) -> Result<PyObject, ParseError> {
@@ -54,28 +276,330 @@ pub fn parse_node<'py>(
 
     match schema {
         SchemaNode::PrimitiveString => parse_string_speculative(cursor, py),
-        SchemaNode::PrimitiveNumber => parse_number_robust(cursor, py),
-        SchemaNode::PrimitiveBool => parse_bool_speculative(cursor, py),
+        SchemaNode::FormattedString { format, inferred } => {
+            parse_formatted(cursor, *format, *inferred || opts.coerce_formats, py)
+        }
+        SchemaNode::PrimitiveNumber(bounds) => parse_number_robust(cursor, bounds, opts, py),
+        SchemaNode::PrimitiveInteger(bounds) => parse_integer(cursor, bounds, opts, py),
+        SchemaNode::PrimitiveBool => parse_bool(cursor, opts, py),
+        SchemaNode::PrimitiveNull => parse_null_speculative(cursor, py),
         SchemaNode::Object {
             fields,
             required,
             ac,
-        } => parse_object(cursor, fields, required, ac, py, depth),
-        SchemaNode::Array(inner) => parse_array(cursor, inner, py, depth),
-        _ => Ok(py.None().into()), // Placeholder for Any or unimplemented types
+            bounds,
+            flags,
+        } => {
+            let (dict, found) = parse_object(cursor, fields, ac, flags, opts, py, depth)?;
+            check_required(required, &found, flags, opts)?;
+            check_property_bounds(dict, bounds)?;
+            Ok(dict.into())
+        }
+        SchemaNode::Array { items, bounds } => {
+            let list = parse_array(cursor, items, opts, py, depth)?;
+            check_array_bounds(list, bounds)?;
+            Ok(list.into())
+        }
+        SchemaNode::Ref(slot) => {
+            let target = slot.upgrade().ok_or(ParseError::DanglingRef)?;
+            let node = target.get().ok_or(ParseError::DanglingRef)?;
+            parse_node(cursor, node, opts, py, depth + 1)
+        }
+        SchemaNode::Document { root, .. } => parse_node(cursor, root, opts, py, depth),
+        SchemaNode::Const { value, inner } => parse_const(cursor, value, inner, opts, py, depth),
+        SchemaNode::Enum(values) => parse_enum(cursor, values, opts, py),
+        SchemaNode::AnyOf(alternatives) => parse_any_of(cursor, alternatives, opts, py, depth),
+        SchemaNode::OneOf(alternatives) => parse_one_of(cursor, alternatives, opts, py, depth),
+        SchemaNode::Map {
+            names,
+            values,
+            bounds,
+        } => {
+            let dict = parse_map(cursor, names, values, opts, py, depth)?;
+            check_property_bounds(dict, bounds)?;
+            Ok(dict.into())
+        }
+        SchemaNode::Any => parse_any(cursor, opts, py, depth),
+    }
+}
+
+/// 无 Schema 约束的值：按开头的字符选择解析方式，整数返回 int，其余数字返回 float
+fn parse_any<'py>(
+    cursor: &mut Cursor,
+    opts: &ExtractOptions,
+    py: Python<'py>,
+    depth: usize,
+) -> Result<PyObject, ParseError> {
+    static ANY_MAP: OnceLock<SchemaNode> = OnceLock::new();
+    let any_map = ANY_MAP.get_or_init(|| SchemaNode::Map {
+        names: KeyRule::default(),
+        values: Arc::new(SchemaNode::Any),
+        bounds: PropertyBounds::default(),
+    });
+    match cursor.remaining().first() {
+        Some(b'{') => parse_node(cursor, any_map, opts, py, depth + 1),
+        Some(b'[') => Ok(parse_array(cursor, &SchemaNode::Any, opts, py, depth)?.into()),
+        Some(b'"' | b'\'') => parse_string_speculative(cursor, py),
+        Some(b't' | b'f' | b'T' | b'F') => parse_bool_speculative(cursor, py),
+        Some(b'n' | b'N') => parse_null_speculative(cursor, py),
+        Some(b'-' | b'+' | b'.' | b'0'..=b'9') => {
+            let start = cursor.checkpoint();
+            let token = scan_number(cursor, opts)?;
+            if let Ok(i) = token.parse::<i64>() {
+                return Ok(i.into_py(py));
+            }
+            cursor.restore(start);
+            parse_number_robust(cursor, &NumberBounds::default(), opts, py)
+        }
+        _ => Err(ParseError::TypeMismatch("value")),
+    }
+}
+
+/// 按 const 的类型解析，再与 const 比较
+fn parse_const<'py>(
+    cursor: &mut Cursor,
+    value: &PyObject,
+    inner: &SchemaNode,
+    opts: &ExtractOptions,
+    py: Python<'py>,
+    depth: usize,
+) -> Result<PyObject, ParseError> {
+    let parsed = parse_node(cursor, inner, opts, py, depth)?;
+    let expected = value.as_ref(py);
+    if parsed.as_ref(py).eq(expected).unwrap_or(false) {
+        return Ok(parsed);
+    }
+    let repr = expected
+        .repr()
+        .map_or_else(|_| "<const>".to_string(), |r| r.to_string());
+    Err(ParseError::ConstMismatch(repr))
+}
+
+/// 带 `format` 的字符串：`coerce` 时校验，日期时间类转成 `datetime` 模块的对象
+fn parse_formatted<'py>(
+    cursor: &mut Cursor,
+    format: StringFormat,
+    coerce: bool,
+    py: Python<'py>,
+) -> Result<PyObject, ParseError> {
+    let parsed = parse_string_speculative(cursor, py)?;
+    if !coerce {
+        return Ok(parsed);
+    }
+    let raw: String = parsed
+        .extract(py)
+        .map_err(|_| ParseError::TypeMismatch("string"))?;
+    coerce_format(raw, parsed, format, py)
+}
+
+/// 按 `format` 校验已读出的字符串 `raw`（`parsed` 是它的 Python 对象），日期时间类转成 `datetime` 对象
+pub(crate) fn coerce_format(
+    raw: String,
+    parsed: PyObject,
+    format: StringFormat,
+    py: Python,
+) -> Result<PyObject, ParseError> {
+    let invalid = || ParseError::InvalidFormat(format.name(), raw.clone());
+    let class = match format {
+        StringFormat::Email | StringFormat::Uuid => {
+            return if format.matches(&raw) {
+                Ok(parsed)
+            } else {
+                Err(invalid())
+            };
+        }
+        StringFormat::DateTime => "datetime",
+        StringFormat::Date => "date",
+        StringFormat::Time => "time",
+    };
+    // Python 3.11 之前的 fromisoformat 不认 `Z` 后缀
+    let iso = match raw.strip_suffix(['Z', 'z']) {
+        Some(rest) if format != StringFormat::Date => format!("{rest}+00:00"),
+        _ => raw.clone(),
+    };
+    py.import("datetime")
+        .and_then(|module| module.getattr(class))
+        .and_then(|class| class.call_method1("fromisoformat", (iso,)))
+        .map(Into::into)
+        .map_err(|_| invalid())
+}
+
+/// 按字符串解析后映射到 enum 的规范写法
+fn parse_enum<'py>(
+    cursor: &mut Cursor,
+    values: &[String],
+    opts: &ExtractOptions,
+    py: Python<'py>,
+) -> Result<PyObject, ParseError> {
+    let raw: String = parse_string_speculative(cursor, py)?
+        .extract(py)
+        .map_err(|_| ParseError::TypeMismatch("string"))?;
+    match fuzzy::match_enum(values, &raw, opts.enum_fuzzy, opts.enum_max_distance) {
+        Some(value) => Ok(PyString::new(py, value).into()),
+        None => Err(ParseError::NotInEnum(raw)),
+    }
+}
+
+/// 在当前位置尝试一个分支；失败时游标回到起点
+fn try_alternative<'py>(
+    cursor: &mut Cursor,
+    schema: &SchemaNode,
+    opts: &ExtractOptions,
+    py: Python<'py>,
+    depth: usize,
+) -> Option<PyObject> {
+    let start = cursor.checkpoint();
+    let logged = fallbacks_mark();
+    // 容器解析器对缺失的括号是宽容的，作为分支时必须看到真正的开括号，否则会吞掉任何输入
+    if container_opener(schema).is_some_and(|o| !cursor.matches(o)) {
+        return None;
+    }
+    match parse_node(cursor, schema, opts, py, depth + 1) {
+        Ok(val) => Some(val),
+        Err(_) => {
+            cursor.restore(start);
+            discard_fallbacks_since(logged);
+            None
+        }
     }
 }
 
+/// 对象/数组 Schema 要求的开括号；`$ref` 只跟一层，避免自引用的定义在这里无限递归
+fn container_opener(schema: &SchemaNode) -> Option<&'static [u8]> {
+    let direct = |node: &SchemaNode| match node {
+        SchemaNode::Object { .. } | SchemaNode::Map { .. } => Some(&b"{"[..]),
+        SchemaNode::Array { .. } => Some(&b"["[..]),
+        _ => None,
+    };
+    match schema {
+        SchemaNode::Ref(slot) => slot.upgrade().and_then(|t| t.get().and_then(direct)),
+        _ => direct(schema),
+    }
+}
+
+/// anyOf：按声明顺序接受第一个能解析的分支
+fn parse_any_of<'py>(
+    cursor: &mut Cursor,
+    alternatives: &[SchemaNode],
+    opts: &ExtractOptions,
+    py: Python<'py>,
+    depth: usize,
+) -> Result<PyObject, ParseError> {
+    alternatives
+        .iter()
+        .find_map(|alt| try_alternative(cursor, alt, opts, py, depth))
+        .ok_or(ParseError::NoAlternativeMatched)
+}
+
+/// oneOf：逐个分支试解析，必须恰好一个成功，游标停在该分支的结束处
+fn parse_one_of<'py>(
+    cursor: &mut Cursor,
+    alternatives: &[SchemaNode],
+    opts: &ExtractOptions,
+    py: Python<'py>,
+    depth: usize,
+) -> Result<PyObject, ParseError> {
+    let start = cursor.checkpoint();
+    let logged = fallbacks_mark();
+    let mut matched = None;
+    for alt in alternatives {
+        cursor.restore(start);
+        if let Some(val) = try_alternative(cursor, alt, opts, py, depth) {
+            if matched.is_some() {
+                discard_fallbacks_since(logged);
+                return Err(ParseError::AmbiguousAlternatives);
+            }
+            matched = Some((val, cursor.checkpoint()));
+        }
+    }
+    let (val, end) = matched.ok_or(ParseError::NoAlternativeMatched)?;
+    cursor.restore(end);
+    Ok(val)
+}
+
+/// 跳过空白后若是 ':'，返回 ':' 之后的偏移
+fn colon_after(input: &[u8], idx: usize) -> Option<usize> {
+    let ws = input[idx..]
+        .iter()
+        .take_while(|b| b.is_ascii_whitespace())
+        .count();
+    (input.get(idx + ws) == Some(&b':')).then_some(idx + ws + 1)
+}
+
+/// 用 Aho-Corasick 搜索带引号的字段名，返回 (Value 起始偏移, 字段名, 子 Schema)
+fn find_exact_key<'s>(
+    input: &'s [u8],
+    fields: &'s FieldLookup,
+    ac: &aho_corasick::AhoCorasick,
+) -> Option<(usize, &'s [u8], &'s std::sync::Arc<SchemaNode>)> {
+    ac.find_iter(input).find_map(|mat| {
+        let value_start = colon_after(input, mat.end())?;
+        // mat.start() .. mat.end() 是带引号的 Key，去掉引号后查表
+        let key_content = &input[mat.start() + 1..mat.end() - 1];
+        let node = fields.get(key_content)?;
+        Some((value_start, key_content, node))
+    })
+}
+
+/// 逐个扫描带引号的 key，规范化后与同样规范化的字段名比较
+fn find_normalized_key<'s>(
+    input: &[u8],
+    fields: &'s FieldLookup,
+    mode: KeyNormalization,
+) -> Option<(usize, &'s [u8], &'s std::sync::Arc<SchemaNode>)> {
+    let mut idx = 0;
+    while let Some(open) = memchr::memchr2(b'"', b'\'', &input[idx..]) {
+        let quote = input[idx + open];
+        let content_start = idx + open + 1;
+        let close = closing_quote(input, content_start, quote)?;
+        idx = close + 1;
+
+        let Some(value_start) = colon_after(input, idx) else {
+            continue;
+        };
+        let raw = String::from_utf8_lossy(&input[content_start..close]);
+        if let Some((key, node)) = normalized_field(fields, &raw, mode) {
+            return Some((value_start, key, node));
+        }
+    }
+    None
+}
+
+/// 规范化后与 `raw` 相同的字段，返回 Schema 中的字段名与子 Schema
+pub(crate) fn normalized_field<'s>(
+    fields: &'s FieldLookup,
+    raw: &str,
+    mode: KeyNormalization,
+) -> Option<(&'s [u8], &'s Arc<SchemaNode>)> {
+    let wanted = fuzzy::normalize_key(raw, mode);
+    fields.find(|k| fuzzy::normalize_key(&String::from_utf8_lossy(k), mode) == wanted)
+}
+
+/// 从 `from` 开始找与 `quote` 配对的闭合引号，跳过反斜杠转义
+fn closing_quote(input: &[u8], from: usize, quote: u8) -> Option<usize> {
+    let mut i = from;
+    while i < input.len() {
+        match input[i] {
+            b'\\' => i += 2,
+            b if b == quote => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// 读出已声明的字段，返回 dict 与找到的字段名；被 `drop_mode` 丢弃的字段跳过原值、不计入
 fn parse_object<'py>(
     cursor: &mut Cursor,
     fields: &FieldLookup,
-    required: &ahash::AHashSet<Vec<u8>>,
     ac: &aho_corasick::AhoCorasick,
+    flags: &AHashMap<Vec<u8>, FieldFlags>,
+    opts: &ExtractOptions,
     py: Python<'py>,
     depth: usize,
-) -> Result<PyObject, ParseError> {
+) -> Result<(&'py PyDict, AHashSet<Vec<u8>>), ParseError> {
     let dict = PyDict::new(py);
-    let mut found_keys = ahash::AHashSet::new(); // 记录找到的 keys
+    let mut found_keys = AHashSet::new(); // 记录找到的 keys
 
     // 容错：如果没找到 '{'，我们假设已经在里面了（上下文推断），
     // 但标准情况是必须有 '{'
@@ -91,64 +615,24 @@ fn parse_object<'py>(
             break;
         }
 
-        // === 核心推测逻辑 (Aho-Corasick) ===
-        // 使用 AC 自动机在剩余文本中搜索所有可能的 Key
+        // === 核心推测逻辑 ===
+        // 在剩余文本中找下一个后面紧跟 ':' 的已知 Key
         let input = cursor.remaining();
-        let mut found_match = false;
-
-        // 限制搜索范围，避免在超大文本中搜索太久
-        // 假设 Key 不会离当前位置太远（例如 1KB 内）
-        // 注意：AC 搜索是流式的，但为了简单起见，我们先在 slice 上搜
-        // 实际上 AC 很快，搜整个 remaining 通常也没问题，除非 remaining 巨大
-        // 为了安全起见，我们可以限制搜索窗口，但如果 JSON 结构非常松散，限制窗口可能导致找不到
-        // 鉴于 AC 的高性能，我们先尝试搜索整个 remaining (或者一个较大的窗口)
-
-        // 迭代查找所有匹配项
-        // println!("DEBUG: Searching in input: {:?}", String::from_utf8_lossy(input));
-        for mat in ac.find_iter(input) {
-            let _pattern_id = mat.pattern();
-            let end = mat.end();
-            // println!("DEBUG: Found match at {:?}-{:?}", mat.start(), mat.end());
-
-            // mat.start() 是相对于 input 的偏移
-            // 检查匹配项后面是否紧跟着 ':' (允许中间有空格)
-            let after_match_idx = end;
-            let after_match = &input[after_match_idx..];
-
-            let mut colon_idx = 0;
-            while colon_idx < after_match.len() && after_match[colon_idx].is_ascii_whitespace() {
-                colon_idx += 1;
-            }
+        let found = match opts.key_normalization {
+            KeyNormalization::Exact => find_exact_key(input, fields, ac),
+            mode => find_normalized_key(input, fields, mode),
+        };
+        let found_match = found.is_some();
 
-            if colon_idx < after_match.len() && after_match[colon_idx] == b':' {
-                // 找到了合法的 Key: Value 结构！
-                // 1. 移动游标到 Value 开始处
-                let value_start_offset = after_match_idx + colon_idx + 1;
-                cursor.advance(value_start_offset);
-
-                // 2. 获取 Key 内容
-                // pattern_id 对应 compiler.rs 中构建的 patterns
-                // patterns 顺序: "key1", 'key1', "key2", 'key2', ...
-                // 偶数是双引号，奇数是单引号
-                // 实际上我们不需要反查 pattern table，直接从 input 提取即可
-                // mat.start() .. mat.end() 是引号包含的 Key
-                let key_quote_content = &input[mat.start()..mat.end()];
-                // 去掉引号
-                let key_content = &key_quote_content[1..key_quote_content.len() - 1];
-
-                // 3. 解析 Value
-                if let Some(sub_schema) = fields.get(key_content) {
-                    let val = parse_node(cursor, sub_schema, py, depth + 1)?;
-
-                    // 安全的 UTF-8 转换
-                    let key_str = String::from_utf8_lossy(key_content);
-                    dict.set_item(key_str, val)
-                        .map_err(|_| ParseError::InvalidUtf8)?;
-                    found_keys.insert(key_content.to_vec());
-
-                    found_match = true;
-                    break; // 处理完一个 Key 后，跳出搜索循环，继续外层循环寻找下一个 Key
-                }
+        if let Some((value_start_offset, key_content, sub_schema)) = found {
+            // 移动游标到 Value 开始处并解析
+            cursor.advance(value_start_offset);
+            cursor.skip_whitespace();
+            if opts.drops(flags.get(key_content)) {
+                skip_raw_value(cursor);
+            } else {
+                parse_field(cursor, key_content, sub_schema, opts, py, depth, dict)?;
+                found_keys.insert(key_content.to_vec());
             }
         }
 
@@ -163,24 +647,186 @@ fn parse_object<'py>(
         }
     }
 
-    // === 审计阶段 ===
-    for req in required {
-        if !found_keys.contains(req) {
-            return Err(ParseError::MissingField(
-                String::from_utf8_lossy(req).to_string(),
-            ));
+    Ok((dict, found_keys))
+}
+
+/// 解析一个字段的值写入 `dict`；失败时按 `field_fallbacks` 兜底
+fn parse_field(
+    cursor: &mut Cursor,
+    key_content: &[u8],
+    sub_schema: &SchemaNode,
+    opts: &ExtractOptions,
+    py: Python,
+    depth: usize,
+    dict: &PyDict,
+) -> Result<(), ParseError> {
+    // 结果里的 key 总是 Schema 中声明的写法
+    let key_str = String::from_utf8_lossy(key_content);
+    let value_start = cursor.checkpoint();
+    let logged = fallbacks_mark();
+    let val = match parse_node(cursor, sub_schema, opts, py, depth + 1) {
+        Ok(val) => val,
+        Err(e) => fall_back(cursor, (value_start, logged), &key_str, e, opts, py)?,
+    };
+    dict.set_item(key_str, val)
+        .map_err(|_| ParseError::InvalidUtf8)?;
+    Ok(())
+}
+
+/// 审计必填字段；被 `drop_mode` 丢弃的字段不再要求出现
+pub(crate) fn check_required(
+    required: &AHashSet<Vec<u8>>,
+    found: &AHashSet<Vec<u8>>,
+    flags: &AHashMap<Vec<u8>, FieldFlags>,
+    opts: &ExtractOptions,
+) -> Result<(), ParseError> {
+    let missing = required
+        .iter()
+        .find(|req| !found.contains(*req) && !opts.drops(flags.get(*req)));
+    match missing {
+        Some(req) => Err(ParseError::MissingField(
+            String::from_utf8_lossy(req).to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// `field_fallbacks` 里有该字段时跳过坏值、换成兜底值；嵌套超限不兜底，照常报错。
+/// 坏值内部已记下的兜底随坏值一起丢掉，`logged` 是解析坏值前的 `fallbacks_mark`
+fn fall_back(
+    cursor: &mut Cursor,
+    (value_start, logged): (usize, usize),
+    key: &str,
+    error: ParseError,
+    opts: &ExtractOptions,
+    py: Python,
+) -> Result<PyObject, ParseError> {
+    let Some(fallback) = fallback_value(key, &error, opts) else {
+        return Err(error.within(PathSegment::Key(key.to_string())));
+    };
+    cursor.restore(value_start);
+    discard_fallbacks_since(logged);
+    skip_raw_value(cursor);
+    let repair = Repair {
+        offset: value_start,
+        kind: RepairKind::FieldFallback,
+        detail: format!("{key}: {error}"),
+    };
+    FALLBACKS.with(|log| log.borrow_mut().push(repair));
+    Ok(fallback.clone_ref(py))
+}
+
+/// 字段 `key` 因 `error` 失败时可用的兜底值；嵌套超限不兜底
+pub(crate) fn fallback_value<'o>(
+    key: &str,
+    error: &ParseError,
+    opts: &'o ExtractOptions,
+) -> Option<&'o PyObject> {
+    if matches!(error.cause(), ParseError::RecursionLimit) {
+        return None;
+    }
+    opts.field_fallbacks.get(key)
+}
+
+/// 不做校验地跳过一个值：停在同层的 `,` `}` `]` 前，字符串与嵌套括号整体跳过
+fn skip_raw_value(cursor: &mut Cursor) {
+    let input = cursor.remaining();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            quote @ (b'"' | b'\'') => {
+                i = closing_quote(input, i + 1, quote).map_or(input.len(), |close| close + 1);
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b',' | b'}' | b']' if depth == 0 => break,
+            b'}' | b']' => depth -= 1,
+            _ => {}
         }
+        i += 1;
     }
+    cursor.advance(i);
+}
+
+/// 动态对象：逐个读取 `key: value`，key 不满足 propertyNames 时报错（路径指向该 key）
+fn parse_map<'py>(
+    cursor: &mut Cursor,
+    names: &KeyRule,
+    values: &SchemaNode,
+    opts: &ExtractOptions,
+    py: Python<'py>,
+    depth: usize,
+) -> Result<&'py PyDict, ParseError> {
+    if !cursor.matches(b"{") {
+        return Err(ParseError::TypeMismatch("object"));
+    }
+    cursor.advance(1);
+    let dict = PyDict::new(py);
+
+    loop {
+        cursor.skip_whitespace();
+        if cursor.matches(b"}") || cursor.remaining().is_empty() {
+            cursor.advance(1);
+            break;
+        }
+        if cursor.matches(b",") {
+            cursor.advance(1);
+            continue;
+        }
 
-    Ok(dict.into())
+        let key = parse_map_key(cursor, py)?;
+        let in_key = |e: ParseError| e.within(PathSegment::Key(key.clone()));
+        if !names.accepts(&key) {
+            return Err(in_key(ParseError::InvalidPropertyName(key.clone())));
+        }
+        cursor.skip_whitespace();
+        if !cursor.matches(b":") {
+            return Err(in_key(ParseError::TypeMismatch("':' after object key")));
+        }
+        cursor.advance(1);
+        cursor.skip_whitespace();
+        let value = parse_node(cursor, values, opts, py, depth + 1).map_err(in_key)?;
+        dict.set_item(&key, value)
+            .map_err(|_| ParseError::InvalidUtf8)?;
+    }
+
+    Ok(dict)
+}
+
+/// 动态对象的 key：引号字符串，或读到 `:`、空白、`,`、`}` 为止的裸 key
+fn parse_map_key(cursor: &mut Cursor, py: Python) -> Result<String, ParseError> {
+    if cursor.matches(b"\"") || cursor.matches(b"'") {
+        return parse_string_speculative(cursor, py)?
+            .extract(py)
+            .map_err(|_| ParseError::TypeMismatch("string key"));
+    }
+    let input = cursor.remaining();
+    let len = input
+        .iter()
+        .position(|&b| matches!(b, b':' | b',' | b'}') || b.is_ascii_whitespace())
+        .unwrap_or(input.len());
+    if len == 0 {
+        return Err(ParseError::TypeMismatch("object key"));
+    }
+    let key = std::str::from_utf8(&input[..len]).map_err(|_| ParseError::InvalidUtf8)?;
+    cursor.advance(len);
+    Ok(key.to_string())
 }
 
 fn parse_array<'py>(
     cursor: &mut Cursor,
     inner: &SchemaNode,
+    opts: &ExtractOptions,
     py: Python<'py>,
     depth: usize,
-) -> Result<PyObject, ParseError> {
+) -> Result<&'py PyList, ParseError> {
+    if opts.coerce_scalar_to_array && !cursor.matches(b"[") && !cursor.remaining().is_empty() {
+        let val = parse_node(cursor, inner, opts, py, depth + 1)
+            .map_err(|e| e.within(PathSegment::Index(0)))?;
+        return Ok(PyList::new(py, [val]));
+    }
+
     let list = PyList::empty(py);
 
     if cursor.matches(b"[") {
@@ -195,7 +841,8 @@ fn parse_array<'py>(
         }
 
         let start_pos = cursor.pos;
-        let val = parse_node(cursor, inner, py, depth + 1)?;
+        let val = parse_node(cursor, inner, opts, py, depth + 1)
+            .map_err(|e| e.within(PathSegment::Index(list.len())))?;
         list.append(val).map_err(|_| ParseError::InvalidUtf8)?;
 
         if cursor.pos == start_pos {
@@ -213,42 +860,333 @@ fn parse_array<'py>(
         }
     }
 
-    Ok(list.into())
+    Ok(list)
+}
+
+/// 校验 minProperties / maxProperties；按物化后的 dict 计数，重复 key 只算一次
+pub(crate) fn check_property_bounds(
+    dict: &PyDict,
+    bounds: &PropertyBounds,
+) -> Result<(), ParseError> {
+    let len = dict.len();
+    if let Some(min) = bounds.min_properties.filter(|&min| len < min) {
+        return Err(ParseError::TooFewProperties(min, len));
+    }
+    if let Some(max) = bounds.max_properties.filter(|&max| len > max) {
+        return Err(ParseError::TooManyProperties(max, len));
+    }
+    Ok(())
+}
+
+/// 校验 minItems / maxItems / uniqueItems；元素相等按 Python `==` 判断
+pub(crate) fn check_array_bounds(list: &PyList, bounds: &ArrayBounds) -> Result<(), ParseError> {
+    let len = list.len();
+    if let Some(min) = bounds.min_items.filter(|&min| len < min) {
+        return Err(ParseError::TooFewItems(min, len));
+    }
+    if let Some(max) = bounds.max_items.filter(|&max| len > max) {
+        return Err(ParseError::TooManyItems(max, len));
+    }
+    if bounds.unique_items {
+        for (i, a) in list.iter().enumerate() {
+            for (j, b) in list.iter().enumerate().skip(i + 1) {
+                if a.eq(b).unwrap_or(false) {
+                    return Err(ParseError::DuplicateItems(i, j));
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
 /// 鲁棒的数字解析
-fn parse_number_robust<'py>(cursor: &mut Cursor, py: Python<'py>) -> Result<PyObject, ParseError> {
-    let _start = cursor.pos;
+pub(crate) fn parse_number_robust<'py>(
+    cursor: &mut Cursor,
+    bounds: &NumberBounds,
+    opts: &ExtractOptions,
+    py: Python<'py>,
+) -> Result<PyObject, ParseError> {
+    let token = read_number(cursor, opts)?;
+    let float_val = token
+        .parse::<f64>()
+        .map_err(|_| ParseError::TypeMismatch("number"))?;
+    check_multiple_of(float_val, bounds)?;
+    Ok(PyFloat::new(py, float_val).into())
+}
+
+/// integer 叶子：`3.0`、`3.00` 这类小数部分为零的值收敛为 int，有真正小数部分的报错。
+/// 超出 i64 的值（`12345678901234567890`、`1e20`）按 token 精确展开成 Python int
+pub(crate) fn parse_integer<'py>(
+    cursor: &mut Cursor,
+    bounds: &NumberBounds,
+    opts: &ExtractOptions,
+    py: Python<'py>,
+) -> Result<PyObject, ParseError> {
+    let token = read_number(cursor, opts)?;
+    if let Ok(i) = token.parse::<i64>() {
+        check_integer_multiple_of(i, bounds)?;
+        return Ok(i.into_py(py));
+    }
+    let f = token
+        .parse::<f64>()
+        .map_err(|_| ParseError::TypeMismatch("integer"))?;
+    if f.fract() != 0.0 || fits_i64(f) {
+        return float_to_integer(py, f, bounds);
+    }
+    // f64 在这个量级已丢掉小数位，是否为整数要看 token 本身
+    let digits = integral_digits(&token).ok_or(ParseError::NotAnInteger(f))?;
+    big_integer(py.get_type::<PyLong>().call1((digits,)), bounds)
+}
+
+/// 小数部分为零的浮点数转成整数并校验 multipleOf；超出 i64 时按 f64 的精确值转成 Python int
+pub(crate) fn float_to_integer(
+    py: Python<'_>,
+    f: f64,
+    bounds: &NumberBounds,
+) -> Result<PyObject, ParseError> {
+    // 非有限值的 fract() 是 NaN，同样落在这里
+    if f.fract() != 0.0 {
+        return Err(ParseError::NotAnInteger(f));
+    }
+    if fits_i64(f) {
+        check_integer_multiple_of(f as i64, bounds)?;
+        return Ok((f as i64).into_py(py));
+    }
+    big_integer(PyFloat::new(py, f).call_method0("__int__"), bounds)
+}
+
+/// i64 的边界是 2^63，f64 能精确表示
+fn fits_i64(f: f64) -> bool {
+    (-(2f64.powi(63))..2f64.powi(63)).contains(&f)
+}
+
+/// 超出 i64 的 Python int 校验 multipleOf 后原样返回
+fn big_integer(value: PyResult<&PyAny>, bounds: &NumberBounds) -> Result<PyObject, ParseError> {
+    let value = value.map_err(|_| ParseError::TypeMismatch("integer"))?;
+    check_big_multiple_of(value, bounds)?;
+    Ok(value.into())
+}
+
+/// 把 `-1.5e20` 这类 token 按十进制精确展开成整数位；有非零小数位时为 None
+fn integral_digits(token: &str) -> Option<String> {
+    let (mantissa, exponent) = match token.find(['e', 'E']) {
+        Some(i) => (&token[..i], token[i + 1..].parse::<i64>().ok()?),
+        None => (token, 0),
+    };
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{int_part}{frac_part}");
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // 小数点在 digits 里的位置；调用方只传 |值| >= 2^63 的 token，不会为负
+    let point = usize::try_from(int_part.len() as i64 + exponent).ok()?;
+    if point >= digits.len() {
+        return Some(format!(
+            "{sign}{digits}{}",
+            "0".repeat(point - digits.len())
+        ));
+    }
+    let (whole, fraction) = digits.split_at(point);
+    fraction
+        .bytes()
+        .all(|b| b == b'0')
+        .then(|| format!("{sign}{whole}"))
+}
+
+/// 商与最近整数的差在相对误差内即视为整除，容忍 `0.3 / 0.1` 这类浮点误差
+pub(crate) fn check_multiple_of(value: f64, bounds: &NumberBounds) -> Result<(), ParseError> {
+    let Some(divisor) = bounds.multiple_of else {
+        return Ok(());
+    };
+    let quotient = value / divisor;
+    if (quotient - quotient.round()).abs() > 1e-9 * quotient.abs().max(1.0) {
+        return Err(ParseError::NotMultipleOf(divisor, value));
+    }
+    Ok(())
+}
+
+/// 整数除数直接取模，避免大整数转 f64 丢精度
+pub(crate) fn check_integer_multiple_of(
+    value: i64,
+    bounds: &NumberBounds,
+) -> Result<(), ParseError> {
+    match bounds.multiple_of {
+        Some(divisor) if divisor.fract() == 0.0 && divisor < 2f64.powi(63) => {
+            if value % divisor as i64 != 0 {
+                return Err(ParseError::NotMultipleOf(divisor, value as f64));
+            }
+            Ok(())
+        }
+        _ => check_multiple_of(value as f64, bounds),
+    }
+}
+
+/// 超出 i64 的 Python int：整数除数交给 Python `%`，其余按 f64 近似判断
+pub(crate) fn check_big_multiple_of(
+    value: &PyAny,
+    bounds: &NumberBounds,
+) -> Result<(), ParseError> {
+    let approx = value.extract::<f64>().unwrap_or(f64::INFINITY);
+    match bounds.multiple_of {
+        Some(divisor) if divisor.fract() == 0.0 && divisor < 2f64.powi(63) => {
+            let remainder = value
+                .call_method1("__mod__", (divisor as i64,))
+                .and_then(|r| r.extract::<i64>())
+                .map_err(|_| ParseError::TypeMismatch("integer"))?;
+            if remainder != 0 {
+                return Err(ParseError::NotMultipleOf(divisor, approx));
+            }
+            Ok(())
+        }
+        _ => check_multiple_of(approx, bounds),
+    }
+}
+
+/// 开启 `parse_grouped_numbers` 时先按千分位语法读，不符合再按普通数字读（此时逗号结束数字）
+fn read_number<'a>(
+    cursor: &mut Cursor<'a>,
+    opts: &ExtractOptions,
+) -> Result<Cow<'a, str>, ParseError> {
+    if opts.parse_grouped_numbers {
+        if let Some((len, token)) = scan_grouped_number(cursor.remaining()) {
+            cursor.advance(len);
+            return Ok(Cow::Owned(token));
+        }
+    }
+    scan_number(cursor, opts)
+}
+
+/// 千分位数字：`1-3 位 (分隔符 3 位)*`，分隔符为 `,` 或空格且全程一致；
+/// 小数点为 `.`，空格分组时也可以是 `,`（`1 234,56`）。返回消耗的字节数与规范化后的 token。
+/// 没有分组、分组不规整（`1,2345`）或带指数时为 None，交给 `scan_number`
+fn scan_grouped_number(input: &[u8]) -> Option<(usize, String)> {
+    let digits_at = |at: usize| {
+        input[at.min(input.len())..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let mut token = String::new();
+    let mut pos = 0;
+    if let Some(&sign @ (b'-' | b'+')) = input.first() {
+        token.push(sign as char);
+        pos = 1;
+    }
+    let lead = digits_at(pos);
+    if !(1..=3).contains(&lead) {
+        return None;
+    }
+    token.push_str(std::str::from_utf8(&input[pos..pos + lead]).ok()?);
+    pos += lead;
+    let separator = *input.get(pos).filter(|b| matches!(b, b',' | b' '))?;
+    let mut groups = 0;
+    while input.get(pos) == Some(&separator) && digits_at(pos + 1) == 3 {
+        token.push_str(std::str::from_utf8(&input[pos + 1..pos + 4]).ok()?);
+        pos += 4;
+        groups += 1;
+    }
+    // 一组都没有，或分组后还紧跟数字（`1,2345`）
+    if groups == 0 || input.get(pos).is_some_and(u8::is_ascii_digit) {
+        return None;
+    }
+    let decimal = match input.get(pos) {
+        Some(b'.') => true,
+        Some(b',') => separator == b' ',
+        _ => false,
+    };
+    let fraction = if decimal { digits_at(pos + 1) } else { 0 };
+    if fraction > 0 {
+        token.push('.');
+        token.push_str(std::str::from_utf8(&input[pos + 1..pos + 1 + fraction]).ok()?);
+        pos += 1 + fraction;
+    }
+    // 带指数的写法不在千分位语法内
+    if matches!(input.get(pos), Some(b'e' | b'E')) {
+        return None;
+    }
+    Some((pos, token))
+}
+
+/// 读取数字 token（去掉千分位逗号）；不含任何数字时不移动游标
+fn scan_number<'a>(
+    cursor: &mut Cursor<'a>,
+    opts: &ExtractOptions,
+) -> Result<Cow<'a, str>, ParseError> {
     let input = cursor.remaining();
+    // 容忍规整的 `,ddd` 千分位（`1,000`）；其他逗号结束数字，留给外层当分隔符（`[1,2]`）
+    let comma_grouped =
+        scan_grouped_number(input).filter(|(len, _)| !input[..*len].contains(&b' '));
+    if let Some((len, token)) = comma_grouped {
+        cursor.advance(len);
+        return Ok(Cow::Owned(token));
+    }
     let mut end = 0;
+    let mut fullwidth = false;
+    let mut has_digit = false;
 
     // 贪婪匹配所有可能组成数字的字符
-    // 容忍 '1,000' 中的逗号
     while end < input.len() {
-        match input[end] {
-            b'0'..=b'9' | b'.' | b'-' | b'+' | b'e' | b'E' | b',' => end += 1,
-            _ => break,
+        let b = input[end];
+        if b.is_ascii_digit() {
+            has_digit = true;
+            end += 1;
+        } else if matches!(b, b'.' | b'-' | b'+' | b'e' | b'E')
+            || (b == b',' && fullwidth_group_at(&input[end + 1..], opts))
+        {
+            end += 1;
+        } else if let Some(c) = fullwidth_number_at(&input[end..], opts) {
+            has_digit |= c.is_ascii_digit();
+            fullwidth = true;
+            end += 3;
+        } else {
+            break;
         }
     }
 
-    cursor.advance(end);
     let raw_bytes = &input[..end];
+    // 不含任何数字的片段不是数字，交给调用方决定（例如 anyOf 的下一个分支）
+    if !has_digit {
+        return Err(ParseError::TypeMismatch("number"));
+    }
+    cursor.advance(end);
 
-    // 优化：先检查是否存在逗号。memchr 极快。
-    let has_comma = memchr::memchr(b',', raw_bytes).is_some();
+    // 上面的循环只接受 ASCII 的 [0-9.-+eE]、全角数字之间的逗号与完整的全角数字字符
+    let s = std::str::from_utf8(raw_bytes).map_err(|_| ParseError::InvalidUtf8)?;
+    if fullwidth {
+        return Ok(Cow::Owned(
+            s.chars()
+                .filter(|&c| c != ',')
+                .map(|c| ascii_number_char(c).unwrap_or(c))
+                .collect(),
+        ));
+    }
+    // 快乐路径：完全零拷贝
+    Ok(Cow::Borrowed(s))
+}
 
-    let float_val = if !has_comma {
-        // 快乐路径：完全零拷贝
-        // 安全性：我们在上面的循环里只允许了 [0-9.-+eE]
-        let s = unsafe { std::str::from_utf8_unchecked(raw_bytes) };
-        s.parse::<f64>().unwrap_or(0.0)
-    } else {
-        // 悲伤路径：只有遇到逗号才分配内存
-        let s = String::from_utf8_lossy(raw_bytes);
-        s.replace(',', "").parse::<f64>().unwrap_or(0.0)
+/// `bytes` 开头恰好是三个全角数字、其后不再是数字：全角数字的千分位分组（`１,２３４`）。
+/// ASCII 数字的分组由 `scan_grouped_number` 处理
+fn fullwidth_group_at(bytes: &[u8], opts: &ExtractOptions) -> bool {
+    let digit_at = |at: usize| {
+        bytes
+            .get(at..)
+            .and_then(|rest| fullwidth_number_at(rest, opts))
+            .is_some_and(|c| c.is_ascii_digit())
     };
+    (0..3).all(|i| digit_at(i * 3)) && !digit_at(9) && !bytes.get(9).is_some_and(u8::is_ascii_digit)
+}
 
-    Ok(PyFloat::new(py, float_val).into())
+/// 开启 `normalize_unicode_digits` 且 `bytes` 开头是一个全角数字字符（3 字节 UTF-8）时，返回对应的 ASCII 字符
+fn fullwidth_number_at(bytes: &[u8], opts: &ExtractOptions) -> Option<char> {
+    if !opts.normalize_unicode_digits || bytes.first() != Some(&0xEF) {
+        return None;
+    }
+    let ch = std::str::from_utf8(bytes.get(..3)?).ok()?.chars().next()?;
+    ascii_number_char(ch)
 }
 
 /// 推测性字符串解析
@@ -329,7 +1267,7 @@ fn parse_string_speculative<'py>(
         }
 
         // Hit EOF without closing quote -> Error
-        return Err(ParseError::UnexpectedEof);
+        Err(ParseError::UnexpectedEof)
     } else {
         // Unquoted string mode: ROBUST / HEURISTIC
         // Consume until a separator is found
@@ -361,35 +1299,93 @@ fn parse_string_speculative<'py>(
 
         // Special handling for null -> None
         if s == "null" {
-            return Ok(py.None().into());
+            return Ok(py.None());
         }
 
         Ok(PyString::new(py, &s).into())
     }
 }
 
-fn parse_bool_speculative<'py>(
+/// boolean 叶子：先认字面量，再认 `truthy_words` / `falsy_words` 里的同义词（忽略大小写，可带引号）
+fn parse_bool<'py>(
     cursor: &mut Cursor,
+    opts: &ExtractOptions,
     py: Python<'py>,
 ) -> Result<PyObject, ParseError> {
-    if cursor.matches(b"true") {
-        cursor.advance(4);
-        Ok(PyBool::new(py, true).into())
-    } else if cursor.matches(b"false") {
-        cursor.advance(5);
-        Ok(PyBool::new(py, false).into())
+    let literal = parse_bool_speculative(cursor, py);
+    if literal.is_ok() || (opts.truthy_words.is_empty() && opts.falsy_words.is_empty()) {
+        return literal;
+    }
+    let start = cursor.checkpoint();
+    let value = parse_string_speculative(cursor, py)
+        .ok()
+        .and_then(|word| word.extract::<String>(py).ok())
+        .and_then(|word| bool_word(&word, opts));
+    match value {
+        Some(value) => Ok(PyBool::new(py, value).into()),
+        None => {
+            cursor.restore(start);
+            Err(ParseError::TypeMismatch("boolean"))
+        }
+    }
+}
+
+/// `truthy_words` / `falsy_words` 里的写法，比较前去空白并转小写
+pub(crate) fn bool_word(word: &str, opts: &ExtractOptions) -> Option<bool> {
+    let word = word.trim().to_lowercase();
+    if opts.truthy_words.contains(&word) {
+        Some(true)
+    } else if opts.falsy_words.contains(&word) {
+        Some(false)
     } else {
-        // 也许是 "True" 或 "False" (Python 风格)
-        if cursor.matches(b"True") {
-            cursor.advance(4);
-            Ok(PyBool::new(py, true).into())
-        } else if cursor.matches(b"False") {
-            cursor.advance(5);
-            Ok(PyBool::new(py, false).into())
-        } else {
-            Ok(py.None().into())
+        None
+    }
+}
+
+fn parse_bool_speculative<'py>(
+    cursor: &mut Cursor,
+    py: Python<'py>,
+) -> Result<PyObject, ParseError> {
+    // 标准字面量、Python 风格 True/False，以及被引号包住的 "true"/"false"
+    const LITERALS: [(&[u8], bool); 8] = [
+        (b"true", true),
+        (b"false", false),
+        (b"True", true),
+        (b"False", false),
+        (b"\"true\"", true),
+        (b"\"false\"", false),
+        (b"'true'", true),
+        (b"'false'", false),
+    ];
+    for (literal, value) in LITERALS {
+        if matches_word(cursor, literal) {
+            cursor.advance(literal.len());
+            return Ok(PyBool::new(py, value).into());
         }
     }
+    Err(ParseError::TypeMismatch("boolean"))
+}
+
+fn parse_null_speculative<'py>(
+    cursor: &mut Cursor,
+    py: Python<'py>,
+) -> Result<PyObject, ParseError> {
+    for literal in [b"null".as_slice(), b"None"] {
+        if matches_word(cursor, literal) {
+            cursor.advance(literal.len());
+            return Ok(py.None());
+        }
+    }
+    Err(ParseError::TypeMismatch("null"))
+}
+
+/// 游标处是 `literal`，且其后不紧跟标识符字符（`truex`、`nullable` 不算）
+fn matches_word(cursor: &Cursor, literal: &[u8]) -> bool {
+    cursor.matches(literal)
+        && cursor
+            .remaining()
+            .get(literal.len())
+            .is_none_or(|&b| !(b.is_ascii_alphanumeric() || b == b'_' || b == b'$'))
 }
 
 fn is_structural_closure(input: &[u8]) -> bool {
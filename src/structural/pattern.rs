@@ -0,0 +1,476 @@
+//! JSON Schema `pattern` 用的最小正则子集：编译成 Thompson NFA 做线性时间匹配，
+//! 按 ECMA 语义做子串搜索（需要整串匹配时自己写 `^...$`）
+//!
+//! 支持字面量、`.`、字符类 `[a-z_]` / `[^...]`、`\d \w \s`（及大写取反）、锚点 `^ $`、
+//! 分组 `(...)` / `(?:...)`、选择 `|`，以及贪婪量词 `* + ? {n} {n,} {n,m}`。
+//! 其余语法（反向引用、断言、`\b` 等）编译时报错，而不是悄悄按字面量处理。
+
+/// 一个编译好的 pattern
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    source: String,
+    program: Vec<Inst>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class {
+        items: Vec<ClassItem>,
+        negated: bool,
+    },
+    Start,
+    End,
+    Group(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+impl Node {
+    /// 单字符原子是否接受 `ch`；其他节点总是 false
+    fn accepts(&self, ch: char) -> bool {
+        match self {
+            Node::Char(c) => *c == ch,
+            Node::Any => ch != '\n',
+            Node::Class { items, negated } => items.iter().any(|item| item.matches(ch)) != *negated,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    fn matches(&self, ch: char) -> bool {
+        match *self {
+            ClassItem::Range(lo, hi) => (lo..=hi).contains(&ch),
+            ClassItem::Digit(negated) => ch.is_ascii_digit() != negated,
+            ClassItem::Word(negated) => (ch.is_ascii_alphanumeric() || ch == '_') != negated,
+            ClassItem::Space(negated) => ch.is_whitespace() != negated,
+        }
+    }
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Self, String> {
+        let mut reader = Reader {
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+        let root = reader.alternation()?;
+        if let Some(ch) = reader.peek() {
+            return Err(format!("unexpected {ch:?} at position {}", reader.pos));
+        }
+        let mut compiler = Compiler {
+            program: Vec::new(),
+        };
+        compiler.alternation(&root)?;
+        compiler.emit(Inst::Match)?;
+        Ok(Pattern {
+            source: source.to_string(),
+            program: compiler.program,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// 文本中任意位置出现匹配即为真。所有候选状态同步逐字符推进，
+    /// 耗时与 pattern 状态数 × 文本长度成正比，嵌套量词不会指数回溯
+    pub fn is_match(&self, text: &str) -> bool {
+        let chars: Vec<char> = text.chars().collect();
+        let program = &self.program;
+        let mut current = StateSet::new(program.len());
+        let mut next = StateSet::new(program.len());
+        let mut stack = Vec::new();
+        for pos in 0..=chars.len() {
+            // 每个位置都从头起一条线程，即子串搜索
+            if add_thread(program, &mut current, &mut stack, 0, (pos, chars.len())) {
+                return true;
+            }
+            let Some(&ch) = chars.get(pos) else {
+                break;
+            };
+            next.dense.clear();
+            for &pc in &current.dense {
+                let Inst::Atom(node) = &program[pc] else {
+                    continue;
+                };
+                let at = (pos + 1, chars.len());
+                if node.accepts(ch) && add_thread(program, &mut next, &mut stack, pc + 1, at) {
+                    return true;
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+        }
+        false
+    }
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        Some(ch)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let matched = self.peek() == Some(expected);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn alternation(&mut self) -> Result<Vec<Vec<Node>>, String> {
+        let mut branches = vec![self.sequence()?];
+        while self.eat('|') {
+            branches.push(self.sequence()?);
+        }
+        Ok(branches)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>, String> {
+        let mut nodes = Vec::new();
+        while let Some(ch) = self.peek() {
+            if ch == '|' || ch == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        let at = self.pos;
+        match self.bump() {
+            Some('(') => {
+                if self.eat('?') && !self.eat(':') {
+                    return Err(format!("unsupported group syntax at position {at}"));
+                }
+                let inner = self.alternation()?;
+                if !self.eat(')') {
+                    return Err(format!("unclosed group at position {at}"));
+                }
+                Ok(Node::Group(inner))
+            }
+            Some('[') => self.class(at),
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('\\') => self.escape(at).map(|item| match item {
+                ClassItem::Range(ch, _) => Node::Char(ch),
+                item => Node::Class {
+                    items: vec![item],
+                    negated: false,
+                },
+            }),
+            Some(ch @ ('*' | '+' | '?' | '{')) => {
+                Err(format!("nothing to repeat before {ch:?} at position {at}"))
+            }
+            Some(ch) => Ok(Node::Char(ch)),
+            None => Err("unexpected end of pattern".to_string()),
+        }
+    }
+
+    /// `\` 之后的转义；普通字符以单字符区间返回
+    fn escape(&mut self, at: usize) -> Result<ClassItem, String> {
+        let item = match self.bump() {
+            Some('d') => ClassItem::Digit(false),
+            Some('D') => ClassItem::Digit(true),
+            Some('w') => ClassItem::Word(false),
+            Some('W') => ClassItem::Word(true),
+            Some('s') => ClassItem::Space(false),
+            Some('S') => ClassItem::Space(true),
+            Some('n') => ClassItem::Range('\n', '\n'),
+            Some('t') => ClassItem::Range('\t', '\t'),
+            Some('r') => ClassItem::Range('\r', '\r'),
+            Some(ch) if !ch.is_ascii_alphanumeric() => ClassItem::Range(ch, ch),
+            Some(ch) => return Err(format!("unsupported escape \\{ch} at position {at}")),
+            None => return Err("pattern ends with a backslash".to_string()),
+        };
+        Ok(item)
+    }
+
+    fn class(&mut self, at: usize) -> Result<Node, String> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        let mut first = true;
+        loop {
+            let item = match self.bump() {
+                None => return Err(format!("unclosed character class at position {at}")),
+                Some(']') if !first => break,
+                Some('\\') => self.escape(self.pos - 1)?,
+                Some(ch) => ClassItem::Range(ch, ch),
+            };
+            first = false;
+            // `a-z` 区间；`-` 在末尾时按字面量处理
+            match item {
+                ClassItem::Range(lo, _) if self.peek() == Some('-') => {
+                    if self.chars.get(self.pos + 1).is_some_and(|&c| c != ']') {
+                        self.pos += 1;
+                        let hi = match self.bump() {
+                            Some('\\') => match self.escape(self.pos - 1)? {
+                                ClassItem::Range(hi, _) => hi,
+                                _ => return Err(format!("invalid class range at position {at}")),
+                            },
+                            Some(hi) => hi,
+                            None => unreachable!(),
+                        };
+                        if hi < lo {
+                            return Err(format!("invalid class range at position {at}"));
+                        }
+                        items.push(ClassItem::Range(lo, hi));
+                        continue;
+                    }
+                    items.push(item);
+                }
+                item => items.push(item),
+            }
+        }
+        Ok(Node::Class { items, negated })
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, String> {
+        let at = self.pos;
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+                let bounds = self.bounds(at)?;
+                return self.repeat(atom, bounds, at);
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        self.repeat(atom, (min, max), at)
+    }
+
+    fn repeat(
+        &mut self,
+        atom: Node,
+        (min, max): (usize, Option<usize>),
+        at: usize,
+    ) -> Result<Node, String> {
+        if matches!(atom, Node::Start | Node::End) {
+            return Err(format!("nothing to repeat at position {at}"));
+        }
+        if self.eat('?') {
+            return Err(format!(
+                "lazy quantifiers are not supported (position {at})"
+            ));
+        }
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    /// `{n}`、`{n,}`、`{n,m}`，`{` 已消费
+    fn bounds(&mut self, at: usize) -> Result<(usize, Option<usize>), String> {
+        let invalid = || format!("invalid repetition at position {at}");
+        let min = self.number().ok_or_else(invalid)?;
+        let max = if self.eat(',') {
+            match self.peek() {
+                Some('}') => None,
+                _ => Some(self.number().ok_or_else(invalid)?),
+            }
+        } else {
+            Some(min)
+        };
+        if !self.eat('}') || max.is_some_and(|max| max < min) {
+            return Err(invalid());
+        }
+        Ok((min, max))
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
+}
+
+/// NFA 状态数上限；`(a{1000}){1000}` 这类展开后过大的 pattern 编译时报错
+const MAX_STATES: usize = 10_000;
+
+/// Thompson NFA 的指令；`Split` / `Jump` 是不消费字符的空转移
+#[derive(Debug, Clone)]
+enum Inst {
+    Atom(Node),
+    Start,
+    End,
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+/// 把 `Node` 树展开成指令序列；计数量词按次数复制子程序
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    fn emit(&mut self, inst: Inst) -> Result<usize, String> {
+        if self.program.len() >= MAX_STATES {
+            return Err(format!(
+                "pattern is too large (more than {MAX_STATES} states)"
+            ));
+        }
+        self.program.push(inst);
+        Ok(self.program.len() - 1)
+    }
+
+    /// 回填 `Split` 的第二个目标或 `Jump` 的目标
+    fn patch(&mut self, at: usize, target: usize) {
+        match &mut self.program[at] {
+            Inst::Split(_, to) | Inst::Jump(to) => *to = target,
+            _ => unreachable!("only jumps are patched"),
+        }
+    }
+
+    fn alternation(&mut self, branches: &[Vec<Node>]) -> Result<(), String> {
+        let Some((last, rest)) = branches.split_last() else {
+            return Ok(());
+        };
+        let mut exits = Vec::new();
+        for branch in rest {
+            let split = self.emit(Inst::Split(self.program.len() + 1, 0))?;
+            self.sequence(branch)?;
+            exits.push(self.emit(Inst::Jump(0))?);
+            self.patch(split, self.program.len());
+        }
+        self.sequence(last)?;
+        for exit in exits {
+            self.patch(exit, self.program.len());
+        }
+        Ok(())
+    }
+
+    fn sequence(&mut self, nodes: &[Node]) -> Result<(), String> {
+        nodes.iter().try_for_each(|node| self.node(node))
+    }
+
+    fn node(&mut self, node: &Node) -> Result<(), String> {
+        match node {
+            Node::Char(_) | Node::Any | Node::Class { .. } => {
+                self.emit(Inst::Atom(node.clone()))?;
+            }
+            Node::Start => {
+                self.emit(Inst::Start)?;
+            }
+            Node::End => {
+                self.emit(Inst::End)?;
+            }
+            Node::Group(branches) => self.alternation(branches)?,
+            Node::Repeat { node, min, max } => self.repeat(node, *min, *max)?,
+        }
+        Ok(())
+    }
+
+    /// `x{n,m}` 展开成 n 个 `x` 加 m-n 个可跳过的 `x`；无上限时末尾接一个 `x*` 循环
+    fn repeat(&mut self, node: &Node, min: usize, max: Option<usize>) -> Result<(), String> {
+        for _ in 0..min {
+            self.node(node)?;
+        }
+        let Some(max) = max else {
+            let split = self.emit(Inst::Split(self.program.len() + 1, 0))?;
+            self.node(node)?;
+            self.emit(Inst::Jump(split))?;
+            self.patch(split, self.program.len());
+            return Ok(());
+        };
+        let mut skips = Vec::new();
+        for _ in min..max {
+            skips.push(self.emit(Inst::Split(self.program.len() + 1, 0))?);
+            self.node(node)?;
+        }
+        for skip in skips {
+            self.patch(skip, self.program.len());
+        }
+        Ok(())
+    }
+}
+
+/// 稀疏集合：插入与清空都是 O(1)，按插入顺序遍历
+struct StateSet {
+    dense: Vec<usize>,
+    sparse: Vec<usize>,
+}
+
+impl StateSet {
+    fn new(size: usize) -> Self {
+        StateSet {
+            dense: Vec::with_capacity(size),
+            sparse: vec![0; size],
+        }
+    }
+
+    fn insert(&mut self, pc: usize) -> bool {
+        let slot = self.sparse[pc];
+        if self.dense.get(slot) == Some(&pc) {
+            return false;
+        }
+        self.sparse[pc] = self.dense.len();
+        self.dense.push(pc);
+        true
+    }
+}
+
+/// 从 `pc` 沿空转移走到所有可达状态并加入 `set`；可达 `Match` 时返回真。
+/// 已在集合里的状态不再展开，`(a*)*` 这类空循环自然终止
+fn add_thread(
+    program: &[Inst],
+    set: &mut StateSet,
+    stack: &mut Vec<usize>,
+    pc: usize,
+    at: (usize, usize),
+) -> bool {
+    let (pos, len) = at;
+    stack.push(pc);
+    while let Some(pc) = stack.pop() {
+        if !set.insert(pc) {
+            continue;
+        }
+        match program[pc] {
+            Inst::Atom(_) => {}
+            Inst::Start if pos == 0 => stack.push(pc + 1),
+            Inst::End if pos == len => stack.push(pc + 1),
+            Inst::Start | Inst::End => {}
+            Inst::Jump(to) => stack.push(to),
+            Inst::Split(first, second) => stack.extend([second, first]),
+            Inst::Match => {
+                stack.clear();
+                return true;
+            }
+        }
+    }
+    false
+}
@@ -1,10 +1,14 @@
+use super::pattern::Pattern;
 use ahash::{AHashMap, AHashSet};
+use pyo3::PyObject;
 use smallvec::SmallVec;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, Weak};
 
 /// 阈值：字段数少于 16 时，线性扫描通常比 Hash 计算快，且省内存
 pub const SMALL_MAP_THRESHOLD: usize = 16;
 
+// Small 变体刻意内联存储，换取缓存局部性
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum FieldLookup {
     /// 极速路径：CPU 缓存友好的线性存储
@@ -28,19 +32,206 @@ impl FieldLookup {
             FieldLookup::Large(map) => map.get(key),
         }
     }
+
+    /// 按谓词查找字段，返回 Schema 中的字段名与子 Schema；用于规范化后的 key 匹配
+    pub fn find(&self, pred: impl Fn(&[u8]) -> bool) -> Option<(&[u8], &Arc<SchemaNode>)> {
+        match self {
+            FieldLookup::Small(vec) => vec
+                .iter()
+                .find(|(k, _)| pred(k))
+                .map(|(k, node)| (k.as_slice(), node)),
+            FieldLookup::Large(map) => map
+                .iter()
+                .find(|(k, _)| pred(k))
+                .map(|(k, node)| (k.as_slice(), node)),
+        }
+    }
+}
+
+/// `$ref` 目标的槽位，整棵树编译完后才填充
+pub type RefSlot = OnceLock<SchemaNode>;
+
+/// 数组元素约束：minItems / maxItems / uniqueItems
+#[derive(Debug, Clone, Default)]
+pub struct ArrayBounds {
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub unique_items: bool,
+}
+
+/// 对象字段个数约束：minProperties / maxProperties
+#[derive(Debug, Clone, Default)]
+pub struct PropertyBounds {
+    pub min_properties: Option<usize>,
+    pub max_properties: Option<usize>,
+}
+
+/// 数字叶子约束：multipleOf
+#[derive(Debug, Clone, Default)]
+pub struct NumberBounds {
+    pub multiple_of: Option<f64>,
 }
 
+/// 字符串叶子的 `format`；未列出的格式按普通字符串处理（同 JSON Schema 对未知格式的约定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringFormat {
+    DateTime,
+    Date,
+    Time,
+    Email,
+    Uuid,
+}
+
+impl StringFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "date-time" => Some(Self::DateTime),
+            "date" => Some(Self::Date),
+            "time" => Some(Self::Time),
+            "email" => Some(Self::Email),
+            "uuid" => Some(Self::Uuid),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::DateTime => "date-time",
+            Self::Date => "date",
+            Self::Time => "time",
+            Self::Email => "email",
+            Self::Uuid => "uuid",
+        }
+    }
+
+    /// 该格式的字面形状，首次使用时编译。email / uuid 以此校验；
+    /// 日期时间类只用来从 `examples` 推断格式，真正的校验交给 `fromisoformat`
+    pub fn pattern(self) -> &'static Pattern {
+        static PATTERNS: [OnceLock<Pattern>; 5] = [const { OnceLock::new() }; 5];
+        let source = match self {
+            Self::DateTime => {
+                r"^\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?([Zz]|[+-]\d{2}:?\d{2})?$"
+            }
+            Self::Date => r"^\d{4}-\d{2}-\d{2}$",
+            Self::Time => r"^\d{2}:\d{2}(:\d{2}(\.\d+)?)?([Zz]|[+-]\d{2}:?\d{2})?$",
+            Self::Email => r"^[^@\s]+@[^@\s.]+(\.[^@\s.]+)+$",
+            Self::Uuid => "^[0-9a-fA-F]{8}(-[0-9a-fA-F]{4}){3}-[0-9a-fA-F]{12}$",
+        };
+        PATTERNS[self as usize]
+            .get_or_init(|| Pattern::new(source).expect("built-in format pattern compiles"))
+    }
+
+    /// `text` 的字面形状符合该格式；先按长度排除，超长串不进入 pattern 匹配。
+    /// email 最长 254 个字符（RFC 5321），uuid 恰好 36 个，date 恰好 10 个
+    pub fn matches(self, text: &str) -> bool {
+        let len = text.chars().count();
+        let plausible = match self {
+            Self::Email => len <= 254,
+            Self::Uuid => len == 36,
+            Self::Date => len == 10,
+            Self::DateTime | Self::Time => true,
+        };
+        plausible && self.pattern().is_match(text)
+    }
+
+    /// 所有 `examples` 都符合同一种格式时返回它；没有例子时为 None
+    pub fn infer(examples: &[String]) -> Option<Self> {
+        if examples.is_empty() {
+            return None;
+        }
+        [
+            Self::DateTime,
+            Self::Date,
+            Self::Time,
+            Self::Uuid,
+            Self::Email,
+        ]
+        .into_iter()
+        .find(|format| examples.iter().all(|e| format.matches(e)))
+    }
+}
+
+/// 字段 Schema 上的 `readOnly` / `writeOnly` / `deprecated`，配合 `drop_mode` 丢弃字段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FieldFlags {
+    pub read_only: bool,
+    pub write_only: bool,
+    pub deprecated: bool,
+}
+
+/// `propertyNames`：对象 key 需满足的字符串约束
+#[derive(Debug, Clone, Default)]
+pub struct KeyRule {
+    pub pattern: Option<Pattern>,
+    pub allowed: Option<Vec<String>>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+}
+
+impl KeyRule {
+    /// key 满足所有约束；长度按字符计
+    pub fn accepts(&self, key: &str) -> bool {
+        let len = key.chars().count();
+        self.pattern.as_ref().is_none_or(|p| p.is_match(key))
+            && self
+                .allowed
+                .as_ref()
+                .is_none_or(|values| values.iter().any(|v| v == key))
+            && self.min_length.is_none_or(|min| len >= min)
+            && self.max_length.is_none_or(|max| len <= max)
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum SchemaNode {
     PrimitiveString,
-    PrimitiveNumber,
+    /// 带已知 `format` 的字符串；`inferred` 表示格式由 `examples` 推断而来，
+    /// 此时不论 `coerce_formats` 是否开启都校验并转换
+    FormattedString {
+        format: StringFormat,
+        inferred: bool,
+    },
+    PrimitiveNumber(NumberBounds),
+    PrimitiveInteger(NumberBounds),
     PrimitiveBool,
-    Array(Arc<SchemaNode>),
+    PrimitiveNull,
+    Array {
+        items: Arc<SchemaNode>,
+        bounds: ArrayBounds,
+    },
     Object {
         fields: FieldLookup,
         required: AHashSet<Vec<u8>>,
         /// Aho-Corasick 自动机，用于快速查找 Key
         ac: Arc<aho_corasick::AhoCorasick>,
+        bounds: PropertyBounds,
+        /// 只记录带标记的字段
+        flags: AHashMap<Vec<u8>, FieldFlags>,
+    },
+    /// 没有 `properties` 的动态对象：逐个读取 key，按 `names` 校验，值按 `values` 解析
+    Map {
+        names: KeyRule,
+        values: Arc<SchemaNode>,
+        bounds: PropertyBounds,
+    },
+    /// const：按 `inner` 解析后必须等于 `value`（Python `==`）
+    Const {
+        value: PyObject,
+        inner: Arc<SchemaNode>,
+    },
+    /// 字符串 enum，保存声明顺序的规范写法
+    Enum(Vec<String>),
+    /// anyOf：接受第一个能解析的分支
+    AnyOf(Vec<SchemaNode>),
+    /// oneOf：恰好一个分支能解析
+    OneOf(Vec<SchemaNode>),
+    /// `$ref`：弱引用槽位，避免递归 Schema 形成 Arc 环
+    Ref(Weak<RefSlot>),
+    /// 含 `$ref` 的编译结果根节点，持有所有槽位的强引用
+    Document {
+        root: Arc<SchemaNode>,
+        slots: Vec<Arc<RefSlot>>,
     },
     Any, // 对应 Schema 中的 {}，放弃 Schema 驱动，退化为通用解析
 }
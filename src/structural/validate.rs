@@ -0,0 +1,388 @@
+//! 在内存中的 Python 值上按编译后的 Schema 校验并转换，不再回到原文
+//!
+//! 与 `parser` 共用同一套规则：字段按 `key_normalization` 匹配并改写成 Schema 中的写法，
+//! 未声明的字段丢弃，`field_fallbacks` 兜底，数字字符串与布尔同义词按选项转换。
+
+use super::fuzzy;
+use super::options::{ExtractOptions, KeyNormalization};
+use super::parser::{
+    bool_word, check_array_bounds, check_big_multiple_of, check_integer_multiple_of,
+    check_multiple_of, check_property_bounds, check_required, coerce_format, fallback_value,
+    float_to_integer, normalized_field, parse_integer, parse_number_robust, ParseError,
+    PathSegment, MAX_DEPTH,
+};
+use super::schema::{FieldFlags, FieldLookup, KeyRule, NumberBounds, SchemaNode, StringFormat};
+use crate::repair::{self, RepairOptions};
+use crate::utils::cursor::Cursor;
+use ahash::{AHashMap, AHashSet};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString};
+use std::sync::Arc;
+
+/// 先按 `repair_opts` 宽松修复 `text`，再用 `schema` 校验修复出的值
+pub fn repair_and_validate(
+    py: Python,
+    text: &str,
+    schema: &SchemaNode,
+    repair_opts: &RepairOptions,
+    opts: &ExtractOptions,
+) -> PyResult<PyObject> {
+    let value = repair::repair_json(py, text, repair_opts)?;
+    Ok(validate(value.as_ref(py), schema, opts, 0)?)
+}
+
+/// 按 `schema` 校验 `value`，返回转换后的新对象；错误路径从根开始
+pub fn validate(
+    value: &PyAny,
+    schema: &SchemaNode,
+    opts: &ExtractOptions,
+    depth: usize,
+) -> Result<PyObject, ParseError> {
+    if depth > MAX_DEPTH {
+        return Err(ParseError::RecursionLimit);
+    }
+    let py = value.py();
+    match schema {
+        SchemaNode::PrimitiveString => validate_string(value),
+        SchemaNode::FormattedString { format, inferred } => {
+            validate_formatted(value, *format, *inferred || opts.coerce_formats)
+        }
+        SchemaNode::PrimitiveNumber(bounds) => validate_number(value, bounds, opts),
+        SchemaNode::PrimitiveInteger(bounds) => validate_integer(value, bounds, opts),
+        SchemaNode::PrimitiveBool => validate_bool(value, opts),
+        SchemaNode::PrimitiveNull if value.is_none() => Ok(py.None()),
+        SchemaNode::PrimitiveNull => Err(ParseError::TypeMismatch("null")),
+        SchemaNode::Array { items, bounds } => {
+            let list = validate_array(value, items, opts, depth)?;
+            check_array_bounds(list, bounds)?;
+            Ok(list.into())
+        }
+        SchemaNode::Object {
+            fields,
+            required,
+            bounds,
+            flags,
+            ..
+        } => {
+            let (dict, found) = validate_object(value, fields, flags, opts, depth)?;
+            check_required(required, &found, flags, opts)?;
+            check_property_bounds(dict, bounds)?;
+            Ok(dict.into())
+        }
+        SchemaNode::Map {
+            names,
+            values,
+            bounds,
+        } => {
+            let dict = validate_map(value, names, values, opts, depth)?;
+            check_property_bounds(dict, bounds)?;
+            Ok(dict.into())
+        }
+        SchemaNode::Const {
+            value: expected,
+            inner,
+        } => validate_const(value, expected.as_ref(py), inner, opts, depth),
+        SchemaNode::Enum(values) => validate_enum(value, values, opts),
+        SchemaNode::AnyOf(alternatives) => alternatives
+            .iter()
+            .find_map(|alt| validate(value, alt, opts, depth + 1).ok())
+            .ok_or(ParseError::NoAlternativeMatched),
+        SchemaNode::OneOf(alternatives) => validate_one_of(value, alternatives, opts, depth),
+        SchemaNode::Ref(slot) => {
+            let target = slot.upgrade().ok_or(ParseError::DanglingRef)?;
+            let node = target.get().ok_or(ParseError::DanglingRef)?;
+            validate(value, node, opts, depth + 1)
+        }
+        SchemaNode::Document { root, .. } => validate(value, root, opts, depth),
+        SchemaNode::Any => Ok(value.into()),
+    }
+}
+
+/// 字符串原样返回；数字与布尔转成 JSON 写法的文本（`true` / `false`）
+fn validate_string(value: &PyAny) -> Result<PyObject, ParseError> {
+    let py = value.py();
+    if value.is_instance_of::<PyString>() {
+        return Ok(value.into());
+    }
+    if let Ok(b) = value.downcast::<PyBool>() {
+        return Ok(if b.is_true() { "true" } else { "false" }.into_py(py));
+    }
+    if value.is_instance_of::<PyLong>() || value.is_instance_of::<PyFloat>() {
+        let text = value
+            .str()
+            .map_err(|_| ParseError::TypeMismatch("string"))?;
+        return Ok(text.into());
+    }
+    Err(ParseError::TypeMismatch("string"))
+}
+
+fn validate_formatted(
+    value: &PyAny,
+    format: StringFormat,
+    coerce: bool,
+) -> Result<PyObject, ParseError> {
+    let text = validate_string(value)?;
+    if !coerce {
+        return Ok(text);
+    }
+    let py = value.py();
+    let raw: String = text
+        .extract(py)
+        .map_err(|_| ParseError::TypeMismatch("string"))?;
+    coerce_format(raw, text, format, py)
+}
+
+/// 数字字符串按原文解析的规则读取，且必须整段都是数字
+fn parse_numeric_text(
+    value: &PyAny,
+    read: impl FnOnce(&mut Cursor) -> Result<PyObject, ParseError>,
+    expected: &'static str,
+) -> Result<PyObject, ParseError> {
+    let text = value
+        .downcast::<PyString>()
+        .ok()
+        .and_then(|s| s.to_str().ok())
+        .ok_or(ParseError::TypeMismatch(expected))?;
+    let text = text.trim();
+    let mut cursor = Cursor::new(text.as_bytes());
+    let parsed = read(&mut cursor)?;
+    cursor.skip_whitespace();
+    if !cursor.remaining().is_empty() {
+        return Err(ParseError::TypeMismatch(expected));
+    }
+    Ok(parsed)
+}
+
+fn validate_number(
+    value: &PyAny,
+    bounds: &NumberBounds,
+    opts: &ExtractOptions,
+) -> Result<PyObject, ParseError> {
+    let py = value.py();
+    if value.is_instance_of::<PyBool>() {
+        return Err(ParseError::TypeMismatch("number"));
+    }
+    if value.is_instance_of::<PyLong>() || value.is_instance_of::<PyFloat>() {
+        let f: f64 = value
+            .extract()
+            .map_err(|_| ParseError::TypeMismatch("number"))?;
+        check_multiple_of(f, bounds)?;
+        return Ok(PyFloat::new(py, f).into());
+    }
+    parse_numeric_text(
+        value,
+        |cursor| parse_number_robust(cursor, bounds, opts, py),
+        "number",
+    )
+}
+
+/// int 原样校验（超出 i64 的同样保留为 Python int）；小数部分为零的 float 收敛为 int
+fn validate_integer(
+    value: &PyAny,
+    bounds: &NumberBounds,
+    opts: &ExtractOptions,
+) -> Result<PyObject, ParseError> {
+    let py = value.py();
+    if value.is_instance_of::<PyBool>() {
+        return Err(ParseError::TypeMismatch("integer"));
+    }
+    if value.is_instance_of::<PyLong>() {
+        if let Ok(i) = value.extract::<i64>() {
+            check_integer_multiple_of(i, bounds)?;
+            return Ok(i.into_py(py));
+        }
+        check_big_multiple_of(value, bounds)?;
+        return Ok(value.into());
+    }
+    if value.is_instance_of::<PyFloat>() {
+        let f: f64 = value
+            .extract()
+            .map_err(|_| ParseError::TypeMismatch("integer"))?;
+        return float_to_integer(py, f, bounds);
+    }
+    parse_numeric_text(
+        value,
+        |cursor| parse_integer(cursor, bounds, opts, py),
+        "integer",
+    )
+}
+
+/// bool 原样返回；字符串认 `true` / `false` 与 `truthy_words` / `falsy_words`
+fn validate_bool(value: &PyAny, opts: &ExtractOptions) -> Result<PyObject, ParseError> {
+    let py = value.py();
+    if value.is_instance_of::<PyBool>() {
+        return Ok(value.into());
+    }
+    let word = value
+        .downcast::<PyString>()
+        .ok()
+        .and_then(|s| s.to_str().ok())
+        .ok_or(ParseError::TypeMismatch("boolean"))?;
+    let literal = match word.trim() {
+        "true" | "True" => Some(true),
+        "false" | "False" => Some(false),
+        _ => bool_word(word, opts),
+    };
+    match literal {
+        Some(b) => Ok(PyBool::new(py, b).into()),
+        None => Err(ParseError::TypeMismatch("boolean")),
+    }
+}
+
+/// list 逐个元素校验；`coerce_scalar_to_array` 时单个值包成一元素 list
+fn validate_array<'py>(
+    value: &'py PyAny,
+    items: &SchemaNode,
+    opts: &ExtractOptions,
+    depth: usize,
+) -> Result<&'py PyList, ParseError> {
+    let py = value.py();
+    let Ok(list) = value.downcast::<PyList>() else {
+        if !opts.coerce_scalar_to_array {
+            return Err(ParseError::TypeMismatch("array"));
+        }
+        let item =
+            validate(value, items, opts, depth + 1).map_err(|e| e.within(PathSegment::Index(0)))?;
+        return Ok(PyList::new(py, [item]));
+    };
+    let mut validated = Vec::with_capacity(list.len());
+    for (i, item) in list.iter().enumerate() {
+        let item =
+            validate(item, items, opts, depth + 1).map_err(|e| e.within(PathSegment::Index(i)))?;
+        validated.push(item);
+    }
+    Ok(PyList::new(py, validated))
+}
+
+/// 输入 dict 的 key 对应的 Schema 字段：`Exact` 直接查表，其余按规范化后比较
+fn lookup_field<'a>(
+    fields: &'a FieldLookup,
+    key: &'a str,
+    mode: KeyNormalization,
+) -> Option<(&'a [u8], &'a Arc<SchemaNode>)> {
+    match mode {
+        KeyNormalization::Exact => fields
+            .get(key.as_bytes())
+            .map(|node| (key.as_bytes(), node)),
+        mode => normalized_field(fields, key, mode),
+    }
+}
+
+/// 同 `parser` 的对象：返回 dict 与找到的字段名，被 `drop_mode` 丢弃的字段不校验、不计入
+fn validate_object<'py>(
+    value: &'py PyAny,
+    fields: &FieldLookup,
+    flags: &AHashMap<Vec<u8>, FieldFlags>,
+    opts: &ExtractOptions,
+    depth: usize,
+) -> Result<(&'py PyDict, AHashSet<Vec<u8>>), ParseError> {
+    let py = value.py();
+    let source = value
+        .downcast::<PyDict>()
+        .map_err(|_| ParseError::TypeMismatch("object"))?;
+    let dict = PyDict::new(py);
+    let mut found_keys = AHashSet::new();
+    for (key, item) in source {
+        let Ok(key) = key.extract::<&str>() else {
+            continue;
+        };
+        // 未声明的字段与 parser 一样被丢弃
+        let Some((name, sub_schema)) = lookup_field(fields, key, opts.key_normalization) else {
+            continue;
+        };
+        if opts.drops(flags.get(name)) {
+            continue;
+        }
+        let name_str = String::from_utf8_lossy(name);
+        let validated = match validate(item, sub_schema, opts, depth + 1) {
+            Ok(validated) => validated,
+            Err(e) => match fallback_value(&name_str, &e, opts) {
+                Some(fallback) => fallback.clone_ref(py),
+                None => return Err(e.within(PathSegment::Key(name_str.to_string()))),
+            },
+        };
+        dict.set_item(name_str.as_ref(), validated)
+            .map_err(|_| ParseError::InvalidUtf8)?;
+        found_keys.insert(name.to_vec());
+    }
+    Ok((dict, found_keys))
+}
+
+fn validate_map<'py>(
+    value: &'py PyAny,
+    names: &KeyRule,
+    values: &SchemaNode,
+    opts: &ExtractOptions,
+    depth: usize,
+) -> Result<&'py PyDict, ParseError> {
+    let py = value.py();
+    let source = value
+        .downcast::<PyDict>()
+        .map_err(|_| ParseError::TypeMismatch("object"))?;
+    let dict = PyDict::new(py);
+    for (key, item) in source {
+        let key: String = key
+            .extract()
+            .map_err(|_| ParseError::TypeMismatch("string key"))?;
+        let in_key = |e: ParseError| e.within(PathSegment::Key(key.clone()));
+        if !names.accepts(&key) {
+            return Err(in_key(ParseError::InvalidPropertyName(key.clone())));
+        }
+        let validated = validate(item, values, opts, depth + 1).map_err(in_key)?;
+        dict.set_item(&key, validated)
+            .map_err(|_| ParseError::InvalidUtf8)?;
+    }
+    Ok(dict)
+}
+
+fn validate_const(
+    value: &PyAny,
+    expected: &PyAny,
+    inner: &SchemaNode,
+    opts: &ExtractOptions,
+    depth: usize,
+) -> Result<PyObject, ParseError> {
+    let validated = validate(value, inner, opts, depth)?;
+    if validated.as_ref(value.py()).eq(expected).unwrap_or(false) {
+        return Ok(validated);
+    }
+    let repr = expected
+        .repr()
+        .map_or_else(|_| "<const>".to_string(), |r| r.to_string());
+    Err(ParseError::ConstMismatch(repr))
+}
+
+fn validate_enum(
+    value: &PyAny,
+    values: &[String],
+    opts: &ExtractOptions,
+) -> Result<PyObject, ParseError> {
+    let raw = value
+        .downcast::<PyString>()
+        .ok()
+        .and_then(|s| s.to_str().ok())
+        .ok_or(ParseError::TypeMismatch("string"))?;
+    match fuzzy::match_enum(values, raw, opts.enum_fuzzy, opts.enum_max_distance) {
+        Some(matched) => Ok(PyString::new(value.py(), matched).into()),
+        None => Err(ParseError::NotInEnum(raw.to_string())),
+    }
+}
+
+/// oneOf：必须恰好一个分支校验通过
+fn validate_one_of(
+    value: &PyAny,
+    alternatives: &[SchemaNode],
+    opts: &ExtractOptions,
+    depth: usize,
+) -> Result<PyObject, ParseError> {
+    let mut matched = None;
+    for alt in alternatives {
+        if let Ok(validated) = validate(value, alt, opts, depth + 1) {
+            if matched.is_some() {
+                return Err(ParseError::AmbiguousAlternatives);
+            }
+            matched = Some(validated);
+        }
+    }
+    matched.ok_or(ParseError::NoAlternativeMatched)
+}
@@ -23,6 +23,39 @@ impl<'a> Cursor<'a> {
         self.pos += n;
     }
 
+    /// 记录当前读取位置，供推测解析失败时回退
+    #[inline(always)]
+    pub fn checkpoint(&self) -> usize {
+        self.pos
+    }
+
+    /// 回到 `checkpoint` 记录的位置。
+    ///
+    /// 只回退游标本身：若检查点之后已经修改了外部状态（例如往结果 dict 里写了 key），
+    /// 回退不会撤销这些修改，因此不要跨过这类写入点回退。
+    #[inline(always)]
+    pub fn restore(&mut self, checkpoint: usize) {
+        self.pos = checkpoint;
+    }
+
+    /// 返回 (offset, line, column)，行列均从 1 开始，列按字符计。
+    ///
+    /// 行列只在报错时才需要，这里按需从输入开头数换行，
+    /// 热路径上的 advance 和 checkpoint 因此保持为单纯的整数运算。
+    pub fn position(&self) -> (usize, usize, usize) {
+        let offset = self.pos.min(self.input.len());
+        let consumed = &self.input[..offset];
+        let line = memchr::memchr_iter(b'\n', consumed).count() + 1;
+        let line_start = memchr::memrchr(b'\n', consumed).map_or(0, |i| i + 1);
+        // 只数 UTF-8 首字节，不把续字节算作列
+        let column = consumed[line_start..]
+            .iter()
+            .filter(|&&b| b & 0xC0 != 0x80)
+            .count()
+            + 1;
+        (offset, line, column)
+    }
+
     /// 极速跳过空白字符
     pub fn skip_whitespace(&mut self) {
         while self.pos < self.input.len() {
@@ -1 +1,2 @@
 pub mod cursor;
+pub mod unicode;
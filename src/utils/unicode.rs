@@ -0,0 +1,15 @@
+/// 全角数字 `０`–`９` 与全角 `＋` `－` `．` 对应的 ASCII 字符；其他字符返回 None
+pub fn ascii_number_char(ch: char) -> Option<char> {
+    match ch {
+        '\u{FF10}'..='\u{FF19}' => char::from_u32(ch as u32 - 0xFF10 + u32::from(b'0')),
+        '\u{FF0B}' => Some('+'),
+        '\u{FF0D}' => Some('-'),
+        '\u{FF0E}' => Some('.'),
+        _ => None,
+    }
+}
+
+/// Unicode 减号 U+2212 `−` 与全角 `－` 对应 ASCII `-`；其他字符返回 None
+pub fn ascii_minus(ch: char) -> Option<char> {
+    matches!(ch, '\u{2212}' | '\u{FF0D}').then_some('-')
+}
@@ -0,0 +1,35 @@
+use llm_json_utils::utils::cursor::Cursor;
+
+#[test]
+fn test_checkpoint_restore() {
+    let mut cursor = Cursor::new(b"  {\"a\": 1}");
+    cursor.skip_whitespace();
+    let mark = cursor.checkpoint();
+    assert!(cursor.matches(b"{"));
+
+    cursor.advance(4);
+    assert_eq!(cursor.remaining(), b": 1}");
+
+    cursor.restore(mark);
+    assert_eq!(cursor.checkpoint(), 2);
+    assert_eq!(cursor.remaining(), b"{\"a\": 1}");
+}
+
+#[test]
+fn test_position_tracks_lines_and_columns() {
+    let input = "{\n  \"名字\": x\n}".as_bytes();
+    let mut cursor = Cursor::new(input);
+    assert_eq!(cursor.position(), (0, 1, 1));
+
+    cursor.advance(2);
+    assert_eq!(cursor.position(), (2, 2, 1));
+
+    // 跳过 `  "名字": `，中文按字符计列
+    cursor.advance(12);
+    assert_eq!(cursor.remaining(), b"x\n}");
+    assert_eq!(cursor.position(), (14, 2, 9));
+
+    // 越界位置会截到输入末尾
+    cursor.advance(100);
+    assert_eq!(cursor.position(), (input.len(), 3, 2));
+}
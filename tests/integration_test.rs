@@ -51,35 +51,46 @@ fn setup_python_and_schema(py: Python) -> PyResult<llm_json_utils::structural::s
     compiler::compile(schema_dict)
 }
 
+/// 规范化比较：两边都用 `json.dumps(sort_keys=True)` 序列化，NaN 也能比较
+fn canonical(py: Python, value: &PyAny) -> PyResult<String> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("sort_keys", true)?;
+    py.import("json")?
+        .call_method("dumps", (value,), Some(kwargs))?
+        .extract()
+}
+
 #[test]
 fn test_repair_suite() -> PyResult<()> {
     pyo3::prepare_freethreaded_python();
     Python::with_gil(|py| {
+        let json = py.import("json")?;
         let repair_dir = Path::new("tests/success/repair");
-        if repair_dir.exists() {
-            let mut entries: Vec<_> = fs::read_dir(repair_dir)
-                .expect("Failed to read tests/success/repair directory")
-                .map(|res| res.map(|e| e.path()))
-                .collect::<Result<_, _>>()
-                .expect("Failed to collect paths");
-            entries.sort();
+        let mut entries: Vec<_> = fs::read_dir(repair_dir)
+            .expect("Failed to read tests/success/repair directory")
+            .map(|res| res.map(|e| e.path()))
+            .collect::<Result<_, _>>()
+            .expect("Failed to collect paths");
+        entries.sort();
 
-            for path in entries {
-                if path.extension().and_then(|s| s.to_str()) == Some("txt") {
-                    println!("Testing REPAIR case: {:?}", path);
-                    let content = fs::read_to_string(&path).expect("Failed to read file");
-                    let res = llm_json_utils::repair_json(py, &content)?;
-                    let dict = res.downcast::<PyDict>(py)?;
-                    // Verify we got a dict back. Specific content verification is hard without expected output files.
-                    // But for these specific cases, we know they should parse.
-                    // We can check if "a" exists for our specific test cases.
-                    if dict.contains("a")? {
-                        println!("  [PASS] Repaired and found key 'a'");
-                    } else {
-                        println!("  [WARN] Repaired but key 'a' not found (might be expected for some cases)");
-                    }
-                }
+        for path in entries {
+            if path.extension().and_then(|s| s.to_str()) != Some("txt") {
+                continue;
             }
+            // 每个用例旁边都必须有同名的 .json 期望结果
+            let expected_path = path.with_extension("json");
+            let content = fs::read_to_string(&path).expect("Failed to read file");
+            let expected = fs::read_to_string(&expected_path)
+                .unwrap_or_else(|_| panic!("Missing expected output {:?}", expected_path));
+
+            let res = llm_json_utils::repair_json(py, &content, None)?;
+            let want = json.call_method1("loads", (expected,))?;
+            assert_eq!(
+                canonical(py, res.as_ref(py))?,
+                canonical(py, want)?,
+                "repair mismatch for {:?}",
+                path
+            );
         }
         Ok(())
     })
@@ -102,7 +113,7 @@ fn test_repair_failure_suite() -> PyResult<()> {
                 if path.extension().and_then(|s| s.to_str()) == Some("txt") {
                     println!("Testing REPAIR FAILURE case: {:?}", path);
                     let content = fs::read_to_string(&path).expect("Failed to read file");
-                    let res = llm_json_utils::repair_json(py, &content);
+                    let res = llm_json_utils::repair_json(py, &content, None);
                     if res.is_ok() {
                         panic!("  [FAIL] Expected failure but passed for {:?}", path);
                     } else {
@@ -115,6 +126,24 @@ fn test_repair_failure_suite() -> PyResult<()> {
     })
 }
 
+/// 期望结果写的是正确行为、但解析器目前还做不到的用例：(文件名, 现状)
+const KNOWN_STRUCTURAL_FAILURES: &[(&str, &str)] = &[
+    (
+        "06_fullwidth",
+        "fullwidth braces and quotes are not recognised; yields {}",
+    ),
+    (
+        "12_fake_struct",
+        "string value stops at the embedded '\"a' key quote",
+    ),
+    ("13_missing_comma", "string value runs past the next key"),
+    ("14_extra_comma", "string value runs past the doubled comma"),
+    (
+        "15_nested_trunc",
+        "nested 'id' is hoisted out of the unknown 'meta' object",
+    ),
+];
+
 #[test]
 fn test_structural_suite() -> PyResult<()> {
     pyo3::prepare_freethreaded_python();
@@ -122,81 +151,55 @@ fn test_structural_suite() -> PyResult<()> {
         // Use the complex schema for all structural tests to ensure compatibility
         let schema = setup_python_and_schema(py)?;
 
-        // Success cases
+        // Success cases：每个用例旁边都有同名 .json，解析结果必须与之完全相等
         let success_dir = Path::new("tests/success/structural");
-        if success_dir.exists() {
-            let mut entries: Vec<_> = fs::read_dir(success_dir)
-                .expect("Failed to read tests/success/structural directory")
-                .map(|res| res.map(|e| e.path()))
-                .collect::<Result<_, _>>()
-                .expect("Failed to collect paths");
-            entries.sort();
+        let mut entries: Vec<_> = fs::read_dir(success_dir)
+            .expect("Failed to read tests/success/structural directory")
+            .map(|res| res.map(|e| e.path()))
+            .collect::<Result<_, _>>()
+            .expect("Failed to collect paths");
+        entries.sort();
 
-            for path in entries {
-                if path.extension().and_then(|s| s.to_str()) == Some("txt") {
-                    println!("Testing STRUCTURAL SUCCESS case: {:?}", path);
-                    let content = fs::read_to_string(&path).expect("Failed to read file");
-
-                    // Logic from legacy test_linus_suite: try to find '{' and parse
-                    let bytes = content.as_bytes();
-                    let mut current_pos = 0;
-                    let mut found_valid = false;
-
-                    while let Some(idx) = memchr::memchr(b'{', &bytes[current_pos..]) {
-                        let start_idx = current_pos + idx;
-                        let mut cursor = Cursor::new(&bytes[start_idx..]);
-                        let result = parser::parse_node(&mut cursor, &schema, py, 0);
-
-                        match result {
-                            Ok(obj) => {
-                                if let Ok(dict) = obj.downcast::<PyDict>(py) {
-                                    // Check for "summary" (legacy cases) or "id" (new cases)
-                                    // If it has either, we consider it a pass for now.
-                                    // Ideally we should have per-file expectations, but for now we merge logic.
-                                    let has_summary = dict.contains("summary").unwrap_or(false);
-                                    let has_id = dict.contains("id").unwrap_or(false);
+        let json = py.import("json")?;
+        let mut seen_known = Vec::new();
+        for path in entries {
+            if path.extension().and_then(|s| s.to_str()) != Some("txt") {
+                continue;
+            }
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            let expected_path = path.with_extension("json");
+            let content = fs::read_to_string(&path).expect("Failed to read file");
+            let expected = fs::read_to_string(&expected_path)
+                .unwrap_or_else(|_| panic!("Missing expected output {:?}", expected_path));
 
-                                    if has_summary || has_id {
-                                        println!(
-                                            "  [PASS] Parsed successfully at offset {}",
-                                            start_idx
-                                        );
-                                        found_valid = true;
-                                        break;
-                                    }
-                                }
-                            }
-                            Err(_) => {}
-                        }
-                        current_pos = start_idx + 1;
-                    }
+            // 字段锚点在整段文本里搜索，因此从开头解析即可，不必先找 '{'
+            let mut cursor = Cursor::new(content.as_bytes());
+            let got = parser::parse_node(&mut cursor, &schema, &Default::default(), py, 0)
+                .map_err(PyErr::from)
+                .and_then(|obj| canonical(py, obj.as_ref(py)));
+            let want = canonical(py, json.call_method1("loads", (expected,))?)?;
+            let matches = got.as_ref().is_ok_and(|got| *got == want);
 
-                    if !found_valid {
-                        // Special handling for fullwidth skip
-                        if path
-                            .file_name()
-                            .unwrap()
-                            .to_str()
-                            .unwrap()
-                            .contains("fullwidth")
-                        {
-                            println!("  [SKIP] Skipping fullwidth test");
-                        } else {
-                            // Try simple parse for new cases that might not need search
-                            let mut cursor = Cursor::new(content.as_bytes());
-                            if let Ok(res) = parser::parse_node(&mut cursor, &schema, py, 0) {
-                                let dict = res.downcast::<PyDict>(py)?;
-                                if dict.contains("id")? || dict.contains("summary")? {
-                                    println!("  [PASS] Parsed successfully (direct)");
-                                    continue;
-                                }
-                            }
-                            panic!("  [FAIL] No valid JSON found in {:?}", path);
-                        }
-                    }
-                }
+            if let Some((_, reason)) = KNOWN_STRUCTURAL_FAILURES.iter().find(|(n, _)| *n == name) {
+                // 已知缺陷修好后要把它从清单里移除，期望结果才会真正生效
+                assert!(
+                    !matches,
+                    "{name} now matches its expected output; remove it from KNOWN_STRUCTURAL_FAILURES"
+                );
+                println!("  [KNOWN FAILURE] {name}: {reason}");
+                seen_known.push(name.to_string());
+                continue;
             }
+            assert_eq!(got?, want, "structural mismatch for {:?}", path);
         }
+        assert_eq!(
+            seen_known.len(),
+            KNOWN_STRUCTURAL_FAILURES.len(),
+            "KNOWN_STRUCTURAL_FAILURES lists a case that does not exist"
+        );
 
         // Failure cases
         let failure_dir = Path::new("tests/failure/structural");
@@ -217,7 +220,8 @@ fn test_structural_suite() -> PyResult<()> {
                     let start_pos = memchr::memchr(b'{', content.as_bytes());
                     if let Some(idx) = start_pos {
                         let mut cursor = Cursor::new(&content.as_bytes()[idx..]);
-                        let result = parser::parse_node(&mut cursor, &schema, py, 0);
+                        let result =
+                            parser::parse_node(&mut cursor, &schema, &Default::default(), py, 0);
 
                         match result {
                             Ok(obj) => {
@@ -237,7 +241,7 @@ fn test_structural_suite() -> PyResult<()> {
                     } else {
                         // Try direct parse for unquoted case
                         let mut cursor = Cursor::new(content.as_bytes());
-                        match parser::parse_node(&mut cursor, &schema, py, 0) {
+                        match parser::parse_node(&mut cursor, &schema, &Default::default(), py, 0) {
                             Ok(res) => {
                                 let dict = res.downcast::<PyDict>(py)?;
                                 if dict.contains("id")? {
@@ -0,0 +1,100 @@
+use llm_json_utils::structural::pattern::Pattern;
+use std::time::{Duration, Instant};
+
+fn matches(pattern: &str, text: &str) -> bool {
+    Pattern::new(pattern)
+        .unwrap_or_else(|e| panic!("{pattern:?} failed to compile: {e}"))
+        .is_match(text)
+}
+
+#[test]
+fn test_anchored_classes() {
+    for key in ["snake_case", "a", "_x"] {
+        assert!(matches("^[a-z_]+$", key), "{key:?}");
+    }
+    for key in ["Snake", "with space", "", "kebab-case"] {
+        assert!(!matches("^[a-z_]+$", key), "{key:?}");
+    }
+    assert!(matches(r"^\d{3}-\d{4}$", "555-1234"));
+    assert!(!matches(r"^\d{3}-\d{4}$", "555-12345"));
+    assert!(matches(r"^[^\s]+$", "no_spaces"));
+    assert!(!matches(r"^\S+$", "two words"));
+    assert!(matches("^[a-c-]+$", "a-b-c"));
+}
+
+#[test]
+fn test_search_semantics() {
+    // 不写锚点时在任意位置出现即可，同 JSON Schema
+    assert!(matches("id", "user_id"));
+    assert!(!matches("^id", "user_id"));
+    assert!(matches("", "anything"));
+}
+
+#[test]
+fn test_groups_and_quantifiers() {
+    assert!(matches("^(foo|bar)_[0-9]{1,2}$", "bar_42"));
+    assert!(!matches("^(foo|bar)_[0-9]{1,2}$", "baz_1"));
+    assert!(!matches("^(foo|bar)_[0-9]{1,2}$", "foo_123"));
+    assert!(matches("^(?:ab)+c?$", "ababc"));
+    assert!(matches("^a.c$", "abc"));
+    assert!(matches(r"^a\.c$", "a.c"));
+    assert!(!matches(r"^a\.c$", "abc"));
+    // 可以匹配空串的重复不会死循环
+    assert!(matches("^(a*)*b$", "aaab"));
+    assert!(!matches("^(a*)*b$", "aaaa"));
+    assert!(matches("^x{2,}$", "xxxx"));
+    assert!(matches("^名[字称]$", "名称"));
+}
+
+#[test]
+fn test_long_input_does_not_overflow_the_stack() {
+    // 单字符原子的重复按循环匹配，不随输入长度递归
+    let long = "a".repeat(300_000);
+    assert!(matches("^[a-z]+$", &long));
+    assert!(!matches("^[a-z]+$", &format!("{long}1")));
+    assert!(matches(r"^a*\d?$", &format!("{long}7")));
+    assert!(matches("^.{2,}b$", &format!("{long}b")));
+    assert!(!matches(r"^[^@\s]+@[^@\s.]+(\.[^@\s.]+)+$", &long));
+}
+
+#[test]
+fn test_nested_quantifiers_stay_linear() {
+    // 回溯实现下 28 个字符就要近 10 秒，这里的长度远超那个量级
+    let started = Instant::now();
+    let key = format!("{}b", "a".repeat(5_000));
+    assert!(!matches("^(a+)+$", &key));
+    assert!(!matches("^(a|aa)*$", &key));
+    assert!(!matches(
+        r"^(\w+\s?)*$",
+        &format!("{}!", "word ".repeat(1_000))
+    ));
+    assert!(matches(r"^(\w+\s?)*$", &"word ".repeat(1_000)));
+    assert!(matches("(a*)*b", &key));
+    let elapsed = started.elapsed();
+    assert!(elapsed < Duration::from_secs(2), "took {elapsed:?}");
+}
+
+#[test]
+fn test_oversized_repetition_is_rejected() {
+    let err = Pattern::new("(a{1000}){1000}").unwrap_err();
+    assert!(err.contains("too large"), "{err}");
+    assert!(matches("^[a-z]{1,255}$", "short"));
+}
+
+#[test]
+fn test_unsupported_syntax_is_rejected() {
+    for bad in [
+        "(unclosed",
+        "[abc",
+        "a{2,1}",
+        "*a",
+        r"\bword",
+        "(?=x)",
+        "a+?",
+        r"(a)\1",
+        "a)",
+        "^*",
+    ] {
+        assert!(Pattern::new(bad).is_err(), "{bad:?} should not compile");
+    }
+}
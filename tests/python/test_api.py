@@ -1,6 +1,40 @@
+import collections
+import dataclasses
+import datetime
+import io
+import json
+
 import pytest
 
-from llm_json_utils import JsonExtractor, repair_json
+from llm_json_utils import (
+    JsonDepthError,
+    JsonExtractor,
+    JsonRepairError,
+    JsonRepairer,
+    JsonSyntaxError,
+    JsonTruncatedError,
+    StreamingParser,
+    can_repair,
+    compile_schema,
+    extract_all_json,
+    parse_into,
+    parse_value_at,
+    repair_and_normalize,
+    repair_and_validate,
+    repair_json,
+    repair_json_batch,
+    repair_json_batch_parallel,
+    repair_json_batch_stats,
+    repair_json_concatenated,
+    repair_json_or_none,
+    repair_json_status,
+    repair_json_verbose,
+    repair_json_with_comments,
+    repair_last_json,
+    repair_report,
+    repair_to_json_str,
+    repair_to_writer,
+)
 
 
 def test_repair_json_trailing_comma_and_comments():
@@ -48,5 +82,699 @@ def test_schema_extractor_missing_required_field_raises():
         "required": ["summary"],
     }
     extractor = JsonExtractor(schema)
-    with pytest.raises(ValueError):
+    with pytest.raises(ValueError, match=r"Missing field: summary at line 1, column 14"):
         extractor.extract(b"{'score': 10}")
+
+
+def test_schema_extractor_error_reports_path():
+    schema = {
+        "type": "object",
+        "properties": {"scores": {"type": "array", "items": {"type": "integer"}}},
+    }
+    extractor = JsonExtractor(schema)
+    with pytest.raises(ValueError, match=r"^validation failed at data\.scores\[2\]: Expected integer"):
+        extractor.extract(b'{"scores": [1, 2, 3.5]}')
+
+
+def test_repair_json_json5_numbers_flag():
+    assert repair_json("[0xFF, 0o17, 0b1010]", json5_numbers=True) == [255, 15, 10]
+    with pytest.raises(ValueError):
+        repair_json("[0xFF]")
+
+
+def test_repair_json_rejects_unknown_option():
+    with pytest.raises(TypeError):
+        repair_json("{}", no_such_option=True)
+
+
+def test_compiled_schema_is_reusable():
+    compiled = compile_schema(
+        {
+            "type": "object",
+            "properties": {"summary": {"type": "string"}},
+            "required": ["summary"],
+        }
+    )
+    first = JsonExtractor(compiled).extract(b'{"summary": "a"}')
+    second = JsonExtractor(compiled).extract(b'noise {"summary": "b"}')
+    assert first == {"summary": "a"}
+    assert second == {"summary": "b"}
+
+
+def test_repair_json_with_comments():
+    text = '{"a": 1, // the answer\n "b": /* two */ 2}'
+    assert repair_json_with_comments(text) == ({"a": 1, "b": 2}, [(9, "the answer"), (29, "two")])
+    assert repair_json_with_comments('{"a": 1}') == ({"a": 1}, [])
+
+
+def test_parse_value_at_walks_a_document():
+    text = 'a: {"x": 1} b: [2, 3]'
+    value, end = parse_value_at(text, 3)
+    assert (value, end) == ({"x": 1}, 11)
+    assert parse_value_at(text, text.index("[")) == ([2, 3], len(text))
+    with pytest.raises(ValueError, match="out of range"):
+        parse_value_at(text, 100)
+    with pytest.raises(ValueError, match="character boundary"):
+        parse_value_at("é1", 1)
+
+
+def test_repair_json_escaped_keys():
+    text = r'{"a\u0062c": 1, "x\ny": 2, "\ud83d\ude00": 3}'
+    assert repair_json(text) == {"abc": 1, "x\ny": 2, "\U0001F600": 3}
+
+
+def test_repair_json_verbose_lists_repairs():
+    value, repairs = repair_json_verbose("{'a': [1, 2,]")
+    assert value == {"a": [1, 2]}
+    assert [kind for _, kind, _ in repairs] == [
+        "single_quote",
+        "extra_comma",
+        "closed_container",
+    ]
+    assert repair_json_verbose('{"a": 1}') == ({"a": 1}, [])
+
+
+def test_repair_json_comment_styles():
+    text = '{"a": 1, -- note\n "b": 2}'
+    assert repair_json(text, comment_styles={"dash"}) == {"a": 1, "b": 2}
+    with pytest.raises(ValueError):
+        repair_json("[1, # note\n 2]", comment_styles=())
+    with pytest.raises(ValueError):
+        repair_json("[1]", comment_styles=["semicolon"])
+    with pytest.raises(TypeError):
+        repair_json("[1]", comment_styles="hash")
+
+
+def test_repair_json_unclosed_comments():
+    text = '{"a": 1 /* note, "b": 2}'
+    assert repair_json(text) == {"a": 1}
+    assert repair_json("[1, 2 /* oops") == [1, 2]
+    _, repairs = repair_json_verbose(text)
+    assert (8, "unclosed_comment", "/*") in repairs
+    with pytest.raises(ValueError, match="Unterminated block comment"):
+        repair_json(text, unclosed_comments="error")
+    assert repair_json(text, unclosed_comments="recover") == {"a": 1}
+    _, repairs = repair_json_verbose(text, unclosed_comments="recover")
+    assert (8, "unclosed_comment", "/*") in repairs
+    with pytest.raises(ValueError):
+        repair_json(text, unclosed_comments="ignore")
+
+
+def test_repair_json_control_char_policy():
+    text = '["a\tb\x1b"]'
+    assert repair_json(text) == ["a\tb\x1b"]
+    assert repair_json(text, control_char_policy="escape") == ["a\\tb\\u001b"]
+    assert repair_json(text, control_char_policy="strip") == ["ab"]
+    with pytest.raises(ValueError):
+        repair_json(text, control_char_policy="drop")
+
+
+def test_repair_json_bad_unicode_policy():
+    text = r'["a\u12G4b"]'
+    assert repair_json(text) == ["a\\u12G4b"]
+    assert repair_json(text, bad_unicode_policy="replace") == ["a\ufffdb"]
+    with pytest.raises(ValueError, match="Invalid unicode escape"):
+        repair_json(text, bad_unicode_policy="error")
+    with pytest.raises(ValueError):
+        repair_json(text, bad_unicode_policy="drop")
+
+
+def test_repair_json_raw_numbers():
+    assert repair_json('{"score": 0.300, "n": 1E+05}', raw_numbers=True) == {
+        "score": "0.300",
+        "n": "1E+05",
+    }
+    assert repair_json('{"score": 0.300}') == {"score": 0.3}
+
+
+def test_repair_json_strip_html():
+    text = '<pre><code>{"html": "<b>x</b>"}</code></pre>'
+    assert repair_json(text, strip_html=True) == {"html": "<b>x</b>"}
+    _, repairs = repair_json_verbose(text, strip_html=True)
+    assert [kind for _, kind, _ in repairs] == ["skipped_html_tag"] * 4
+
+
+def test_repair_json_strip_markdown_emphasis():
+    text = '{**"name"**: "x", _"age"_: 3}'
+    assert repair_json(text, strip_markdown_emphasis=True) == {"name": "x", "age": 3}
+    _, repairs = repair_json_verbose(text, strip_markdown_emphasis=True)
+    assert [kind for _, kind, _ in repairs] == ["stripped_emphasis"] * 4
+    with pytest.raises(ValueError):
+        repair_json(text)
+
+
+def test_repair_json_skip_leading_garbage():
+    assert repair_json('}{"a": 1}', skip_leading_garbage=True) == {"a": 1}
+    _, repairs = repair_json_verbose('] ,42', skip_leading_garbage=True)
+    assert repairs == [(0, "skipped_garbage", "]"), (2, "skipped_garbage", ",")]
+    with pytest.raises(ValueError):
+        repair_json("] 42")
+
+
+def test_repair_json_lenient_separators():
+    assert repair_json('{"a" => 1, "b" = "x=y"}', lenient_separators=True) == {"a": 1, "b": "x=y"}
+    _, repairs = repair_json_verbose('{"a" => 1}', lenient_separators=True)
+    assert repairs == [(5, "key_separator", "=>")]
+    with pytest.raises(ValueError):
+        repair_json('{"a" => 1}')
+
+
+def test_repair_json_skip_zero_width():
+    text = '\ufeff{"a":\u200b 1,\u2060 "b": "x\u200by"}'
+    assert repair_json(text, skip_zero_width=True) == {"a": 1, "b": "x\u200by"}
+    with pytest.raises(ValueError):
+        repair_json(text)
+
+
+def test_repair_json_auto_unstringify():
+    payload = json.dumps({"result": json.dumps({"a": [1, 2], "b": json.dumps([True])})})
+    assert repair_json(payload, auto_unstringify=True) == {"result": {"a": [1, 2], "b": [True]}}
+    assert repair_json(payload) == json.loads(payload)
+
+
+def test_repair_json_batch_stats():
+    stats = repair_json_batch_stats(['{"a": 1}', "{'a': 1,}", "[1, 2", '{"a" 1}'])
+    assert stats == {
+        "total": 4,
+        "succeeded": 3,
+        "failed": 1,
+        "truncated": 1,
+        "repairs": {"closed_container": 1, "extra_comma": 1, "single_quote": 1},
+    }
+
+
+def test_repair_json_max_items():
+    assert repair_json("[1, 2, 3]", max_items=3) == [1, 2, 3]
+    assert repair_json('{"a": 1, "b": 2}', max_items=4) == {"a": 1, "b": 2}
+    with pytest.raises(JsonRepairError, match="max_items=3"):
+        repair_json('{"a": 1, "b": 2}', max_items=3)
+
+
+def test_repair_json_normalize_unicode_minus():
+    assert repair_json("[−5, 1e−2]", normalize_unicode_minus=True) == [-5, 0.01]
+    with pytest.raises(ValueError):
+        repair_json("[−5]")
+
+
+def test_repair_json_lenient_commas():
+    assert repair_json('[1 2 "a" "b"]', lenient_commas=True) == [1, 2, "a", "b"]
+    with pytest.raises(ValueError, match="Expected ','"):
+        repair_json("[1 2]")
+
+
+def test_repair_json_error_hierarchy():
+    with pytest.raises(JsonSyntaxError) as info:
+        repair_json('{"a": 1,\n "b" 2}')
+    assert (info.value.offset, info.value.line, info.value.column) == (14, 2, 6)
+    with pytest.raises(JsonTruncatedError):
+        repair_json("[1, -")
+    with pytest.raises(JsonDepthError):
+        repair_json('[{"a": ' * 1000)
+    assert repair_json("[[[1]]]", max_depth=3) == [[[1]]]
+    with pytest.raises(JsonDepthError, match="Maximum nesting depth 2"):
+        JsonRepairer(max_depth=2).repair("[[[1]]]")
+    with pytest.raises(JsonRepairError):
+        repair_json("[1]", max_bytes=1)
+    assert issubclass(JsonSyntaxError, ValueError)
+
+
+def test_repair_last_json():
+    message = 'Draft: {"answer": 1}\nFinal answer:\n```json\n{"answer": 2}\n```'
+    assert repair_last_json(message) == {"answer": 2}
+    assert repair_last_json('{"a": 1} then {"a": 2') == {"a": 1}
+    with pytest.raises(ValueError):
+        repair_last_json("nothing here")
+
+
+def test_repair_json_max_bytes():
+    assert repair_json('["ab"]', max_bytes=6) == ["ab"]
+    with pytest.raises(ValueError, match="max_bytes=6"):
+        repair_json('["é中"]', max_bytes=6)
+
+
+def test_repair_json_value_hook():
+    import datetime
+
+    def to_date(value, tag):
+        if tag == "str" and value[:4].isdigit() and value[4:5] == "-":
+            return datetime.date.fromisoformat(value)
+        return value
+
+    data = repair_json('{"day": "2024-05-01", "name": "x", "n": 3}', value_hook=to_date)
+    assert data == {"day": datetime.date(2024, 5, 1), "name": "x", "n": 3}
+    with pytest.raises(TypeError, match="callable"):
+        repair_json("[1]", value_hook=42)
+    with pytest.raises(TypeError):
+        repair_to_json_str("[1]", value_hook=to_date)
+
+
+def test_repair_json_bigint_mode():
+    text = "[123456789012345678901]"
+    assert repair_json(text) == [123456789012345678901]
+    assert repair_json(text, bigint_mode="float") == [1.2345678901234568e20]
+    assert repair_json(text, bigint_mode="string") == ["123456789012345678901"]
+    with pytest.raises(ValueError):
+        repair_json(text, bigint_mode="decimal")
+
+
+def test_schema_extractor_enum_fuzzy():
+    schema = {
+        "type": "object",
+        "properties": {"status": {"enum": ["approved", "rejected", "pending"]}},
+    }
+    text = b'{"status": "Approve"}'
+    with pytest.raises(ValueError, match="not one of the enum values"):
+        JsonExtractor(schema).extract(text)
+    fuzzy = JsonExtractor(schema, enum_fuzzy=True, enum_max_distance=1)
+    assert fuzzy.extract(text) == {"status": "approved"}
+    with pytest.raises(TypeError):
+        JsonExtractor(schema, enum_fuzz=True)
+
+
+def test_repair_and_normalize():
+    value, text = repair_and_normalize("{'b': [1, 2,], 'a': 'é'")
+    assert value == {"b": [1, 2], "a": "é"}
+    assert text == json.dumps(value, ensure_ascii=False, separators=(",", ":"))
+    _, sorted_text = repair_and_normalize('{"b": 1, "a": {}}', sort_keys=True)
+    assert sorted_text == '{"a":{},"b":1}'
+    value, text = repair_and_normalize("[1]", value_hook=lambda v, tag: v * 10)
+    assert (value, text) == ([10], "[1]")
+
+
+def test_repair_json_object_pairs_hook():
+    text = '{"a": 1, "b": {"c": 2}, "a": 3}'
+    assert repair_json(text, object_pairs_hook=list) == [("a", 1), ("b", [("c", 2)]), ("a", 3)]
+    value = repair_json(text, object_pairs_hook=collections.OrderedDict)
+    assert isinstance(value["b"], collections.OrderedDict) and value["a"] == 3
+    with pytest.raises(TypeError, match="object_pairs_hook must be callable"):
+        repair_json(text, object_pairs_hook=1)
+
+
+def test_repair_to_writer():
+    text = "{'a': [1, {}], 'b': 'é'} // trailing"
+    out = io.StringIO()
+    assert repair_to_writer(text, out, indent=2) is None
+    assert out.getvalue() == repair_to_json_str(text, indent=2)
+    with pytest.raises(TypeError, match="write method"):
+        repair_to_writer(text, object())
+    with pytest.raises(TypeError, match="value_hook"):
+        repair_to_writer(text, out, value_hook=str)
+
+
+def test_repair_to_json_str_indent():
+    text = "{'a': [1, {}], 'b': [], 'c': 'é'} // trailing"
+    value = repair_json(text)
+    assert repair_to_json_str(text) == json.dumps(value, ensure_ascii=False)
+    assert repair_to_json_str(text, indent=2) == json.dumps(value, ensure_ascii=False, indent=2)
+    assert repair_to_json_str(text, 4) == json.dumps(value, ensure_ascii=False, indent=4)
+    assert repair_to_json_str("[0x10]", json5_numbers=True) == "[16]"
+
+
+def test_repair_to_json_str_escape_forward_slashes():
+    text = '{"tag": "</script>"}'
+    assert repair_to_json_str(text, escape_forward_slashes=True) == '{"tag": "<\\/script>"}'
+    assert repair_to_json_str(text) == '{"tag": "</script>"}'
+
+
+def test_repair_json_concatenated():
+    assert repair_json_concatenated('{"a":1}{"b":2}') == [{"a": 1}, {"b": 2}]
+    assert repair_json_concatenated("[0x1] [0x2]", json5_numbers=True) == [[1], [2]]
+
+
+def test_extractor_key_normalization():
+    schema = {
+        "type": "object",
+        "properties": {"first_name": {"type": "string"}},
+        "required": ["first_name"],
+    }
+    text = b'{"First Name": "Ada"}'
+    with pytest.raises(ValueError):
+        JsonExtractor(schema).extract(text)
+    assert JsonExtractor(schema, key_normalization="snake").extract(text) == {
+        "first_name": "Ada"
+    }
+    with pytest.raises(ValueError, match="key_normalization"):
+        JsonExtractor(schema, key_normalization="kebab")
+
+
+def test_streaming_parser():
+    parser = StreamingParser()
+    assert parser.feed("Here: ") is None
+    assert parser.feed('{"items": [1, 2') == {"items": [1, 2]}
+    assert parser.feed(', nu') == {"items": [1, 2]}
+    assert parser.feed('ll], "done": tr') == {"items": [1, 2, None]}
+    assert parser.feed("ue") == {"items": [1, 2, None], "done": True}
+    assert parser.finish() == {"items": [1, 2, None], "done": True}
+    # finish 之后缓冲区已清空，可以接着处理下一条消息
+    assert parser.feed("[0x1") == []
+    assert StreamingParser(json5_numbers=True).feed("[0x1") == [1]
+    with pytest.raises(ValueError):
+        StreamingParser().finish()
+
+
+def test_repair_json_status():
+    assert repair_json_status('{"a": [1, 2]} // ok') == ({"a": [1, 2]}, True)
+    assert repair_json_status('{"a": [1, 2') == ({"a": [1, 2]}, False)
+    assert repair_json_status('["tail') == (["tail"], False)
+    assert repair_json_status('{"score": 3.') == ({"score": 3.0}, False)
+
+
+def test_can_repair_and_repair_report():
+    assert can_repair('{"a": [1, 2')
+    assert not can_repair("no json here")
+    assert repair_report('{"a": [1, 2') == {"valid": True, "truncated": True, "depth": 2, "value_count": 4}
+    assert repair_report("nope") == {"valid": False, "truncated": False, "depth": 0, "value_count": 0}
+
+
+def test_repair_json_sort_keys():
+    text = '{"b": 1, "a": {"d": 2, "c": 3}}'
+    assert list(repair_json(text)) == ["b", "a"]
+    data = repair_json(text, sort_keys=True)
+    assert list(data) == ["a", "b"] and list(data["a"]) == ["c", "d"]
+    assert repair_to_json_str(text, sort_keys=True) == '{"a": {"c": 3, "d": 2}, "b": 1}'
+
+
+def test_repair_json_lenient_brackets():
+    assert repair_json('{"a": [1, 2}', lenient_brackets=True) == {"a": [1, 2]}
+    _, repairs = repair_json_verbose("[1, 2}", lenient_brackets=True)
+    assert repairs == [(5, "mismatched_bracket", "]")]
+
+
+def test_normalize_unicode_digits():
+    assert repair_json("[１２３．５]", normalize_unicode_digits=True) == [123.5]
+    schema = {"type": "object", "properties": {"score": {"type": "number"}}}
+    extractor = JsonExtractor(schema, normalize_unicode_digits=True)
+    assert extractor.extract('{"score": ９５}'.encode()) == {"score": 95.0}
+
+
+def test_repair_json_or_none():
+    assert repair_json_or_none('{"a": 1,}') == {"a": 1}
+    assert repair_json_or_none('{"a" 1}') is None
+    assert repair_json_or_none("[1]", max_bytes=1) is None
+    with pytest.raises(TypeError):
+        repair_json_or_none("[1]", no_such_option=True)
+
+
+def test_repair_json_batch():
+    inputs = ['{"a": 1', "not json", "[1, 2,]", '{"a" 1}']
+    assert repair_json_batch(inputs) == [{"a": 1}, None, [1, 2], None]
+    assert repair_json_batch([]) == []
+    assert repair_json_batch(["[0x10]"], json5_numbers=True) == [[16]]
+
+
+def test_repair_json_batch_parallel():
+    inputs = ['{"i": %d' % i if i % 7 else "not json" for i in range(200)]
+    expected = [{"i": i} if i % 7 else None for i in range(200)]
+    assert repair_json_batch_parallel(inputs) == expected
+    assert repair_json_batch_parallel(inputs) == repair_json_batch(inputs)
+    assert repair_json_batch_parallel([]) == []
+
+
+def test_repair_json_coerce_whole_floats():
+    value = repair_json("[1.0, 2e3, 1.5]", coerce_whole_floats=True)
+    assert value == [1, 2000, 1.5]
+    assert [type(v) for v in value] == [int, int, float]
+    assert type(repair_json("1.0")) is float
+
+
+def test_extract_all_json():
+    message = 'Intro {"a": 1} text\n```json\n[1, 2]\n```\nmore {"b": [true]}'
+    assert extract_all_json(message) == [{"a": 1}, [1, 2], {"b": [True]}]
+    assert extract_all_json("nothing here") == []
+
+
+def test_schema_extractor_type_list():
+    schema = {
+        "type": "object",
+        "properties": {"nickname": {"type": ["string", "null"]}, "age": {"type": "integer"}},
+    }
+    extractor = JsonExtractor(schema)
+    assert extractor.extract(b'{"nickname": null, "age": 3}') == {"nickname": None, "age": 3}
+    assert extractor.extract(b'{"nickname": "Al", "age": 3}') == {"nickname": "Al", "age": 3}
+
+
+def test_repair_json_line_continuations():
+    assert repair_json('"one \\\ntwo"', line_continuations=True) == "one two"
+    assert repair_json('"one \\\r\ntwo"', line_continuations=True) == "one two"
+    assert repair_json('"one \\\ntwo"') == "one \\\ntwo"
+
+
+def test_repair_json_intern_keys():
+    rows = repair_json('[{"id": 1}, {"id": 2}]', intern_keys=True)
+    assert rows == [{"id": 1}, {"id": 2}]
+    first, second = (next(iter(row)) for row in rows)
+    assert first is second
+
+
+def test_schema_extractor_property_names():
+    schema = {
+        "type": "object",
+        "properties": {
+            "scores": {
+                "type": "object",
+                "propertyNames": {"pattern": "^[a-z_]+$"},
+                "additionalProperties": {"type": "number"},
+            }
+        },
+        "required": ["scores"],
+    }
+    extractor = JsonExtractor(schema)
+    assert extractor.extract(b'{"scores": {"math": 90, "art_history": 75.5}}') == {
+        "scores": {"math": 90.0, "art_history": 75.5}
+    }
+    with pytest.raises(ValueError, match=r'^validation failed at data\.scores\.Math: Property name "Math"'):
+        extractor.extract(b'{"scores": {"Math": 90}}')
+
+
+def test_repair_json_js_literals():
+    assert repair_json('{"a": undefined}', js_literals=True) == {"a": None}
+    with pytest.raises(ValueError):
+        repair_json('{"a": undefined}')
+    with pytest.raises(ValueError):
+        repair_json("[trueValue]")
+
+
+def test_json_repairer_reuses_options():
+    repairer = JsonRepairer(json5_numbers=True, sort_keys=True)
+    assert repairer.repair('{"b": 0x10, "a": 1,}') == {"a": 1, "b": 16}
+    assert list(repairer.repair('{"b": 1, "a": 2}')) == ["a", "b"]
+    value, repairs = repairer.repair_verbose("[1,]")
+    assert value == [1]
+    assert [kind for _, kind, _ in repairs] == ["extra_comma"]
+    assert repairer.repair_status("[1, 2") == ([1, 2], False)
+    with pytest.raises(ValueError):
+        repairer.repair('{"a" 1}')
+    with pytest.raises(TypeError):
+        JsonRepairer(no_such_option=True)
+
+
+def test_repair_json_extra_commas():
+    assert repair_json('{"a": 1,,}') == {"a": 1}
+    with pytest.raises(ValueError, match="offset 8"):
+        repair_json('{"a": 1,,}', extra_commas="error")
+    assert repair_json("[1, 2,]", extra_commas="error") == [1, 2]
+    with pytest.raises(ValueError):
+        repair_json("[1]", extra_commas="sometimes")
+
+
+def test_schema_extractor_coerce_formats():
+    schema = {
+        "type": "object",
+        "properties": {
+            "when": {"type": "string", "format": "date-time"},
+            "email": {"type": "string", "format": "email"},
+        },
+    }
+    extractor = JsonExtractor(schema, coerce_formats=True)
+    assert extractor.extract(b'{"when": "2024-01-02T03:04:05+08:00", "email": "x@y.io"}') == {
+        "when": datetime.datetime(
+            2024, 1, 2, 3, 4, 5, tzinfo=datetime.timezone(datetime.timedelta(hours=8))
+        ),
+        "email": "x@y.io",
+    }
+    with pytest.raises(ValueError, match=r'^validation failed at data\.email: Value "oops" is not a valid email'):
+        extractor.extract(b'{"email": "oops"}')
+    assert JsonExtractor(schema).extract(b'{"when": "soon"}') == {"when": "soon"}
+
+
+def test_schema_extractor_infers_format_from_examples():
+    schema = {
+        "type": "object",
+        "properties": {
+            "day": {"type": "string", "examples": ["2024-01-31", "1999-12-01"]},
+            "note": {"type": "string", "examples": ["2024-01-31", "soon"]},
+        },
+    }
+    extractor = JsonExtractor(schema)
+    assert extractor.extract(b'{"day": "2024-05-01", "note": "later"}') == {
+        "day": datetime.date(2024, 5, 1),
+        "note": "later",
+    }
+    with pytest.raises(ValueError, match="is not a valid date"):
+        extractor.extract(b'{"day": "someday"}')
+
+
+def test_schema_extractor_grouped_numbers():
+    schema = {
+        "type": "object",
+        "properties": {
+            "price": {"type": "number"},
+            "ids": {"type": "array", "items": {"type": "integer"}},
+        },
+    }
+    extractor = JsonExtractor(schema, parse_grouped_numbers=True)
+    assert extractor.extract(b'{"price": 1 234,56, "ids": [1,2,3]}') == {
+        "price": 1234.56,
+        "ids": [1, 2, 3],
+    }
+
+
+def test_schema_extractor_bool_words():
+    schema = {"type": "object", "properties": {"ok": {"type": "boolean"}}}
+    extractor = JsonExtractor(schema, truthy_words=["yes", "On"], falsy_words=("no",))
+    assert extractor.extract(b'{"ok": Yes}') == {"ok": True}
+    assert extractor.extract(b'{"ok": "no"}') == {"ok": False}
+    assert extractor.extract(b'{"ok": on}') == {"ok": True}
+    with pytest.raises(TypeError):
+        JsonExtractor(schema, truthy_words="yes")
+
+
+def test_schema_extractor_coerce_scalar_to_array():
+    schema = {"type": "object", "properties": {"tags": {"type": "array", "items": {"type": "string"}}}}
+    extractor = JsonExtractor(schema, coerce_scalar_to_array=True)
+    assert extractor.extract(b'{"tags": "urgent"}') == {"tags": ["urgent"]}
+    assert extractor.extract(b'{"tags": ["a", "b"]}') == {"tags": ["a", "b"]}
+
+
+def test_schema_extractor_nullable():
+    schema = {"type": "object", "properties": {"age": {"type": "integer", "nullable": True}}}
+    extractor = JsonExtractor(schema)
+    assert extractor.extract(b'{"age": null}') == {"age": None}
+    assert extractor.extract(b'{"age": 7}') == {"age": 7}
+
+
+def test_schema_extractor_multiple_of():
+    schema = {"type": "object", "properties": {"qty": {"type": "integer", "multipleOf": 5}}, "required": ["qty"]}
+    extractor = JsonExtractor(schema)
+    assert extractor.extract(b'{"qty": 25}') == {"qty": 25}
+    with pytest.raises(ValueError, match=r"^validation failed at data\.qty: Expected a multiple of 5, found 12"):
+        extractor.extract(b'{"qty": 12}')
+
+
+def test_schema_extractor_big_integers():
+    schema = {"type": "object", "properties": {"a": {"type": "integer"}}}
+    extractor = JsonExtractor(schema)
+    assert extractor.extract(b'{"a": 12345678901234567890}') == {"a": 12345678901234567890}
+    assert extractor.extract(b'{"a": 1e20}') == {"a": 10**20}
+    assert type(extractor.extract(b'{"a": 1e20}')["a"]) is int
+    with pytest.raises(ValueError, match=r"^validation failed at data\.a: Expected integer"):
+        extractor.extract(b'{"a": 12345678901234567890.5}')
+    assert repair_and_validate('{"a": 12345678901234567890}', schema) == {"a": 12345678901234567890}
+
+
+def test_schema_extractor_field_fallbacks():
+    schema = {
+        "type": "object",
+        "properties": {"name": {"type": "string"}, "age": {"type": "integer"}},
+        "required": ["name", "age"],
+    }
+    extractor = JsonExtractor(schema, field_fallbacks={"age": None})
+    text = b'{"name": "ann", "age": "unknown"}'
+    assert extractor.extract(text) == {"name": "ann", "age": None}
+    value, repairs = extractor.extract_verbose(text)
+    assert value == {"name": "ann", "age": None}
+    assert [(offset, kind) for offset, kind, _ in repairs] == [(23, "field_fallback")]
+    assert extractor.extract_verbose(b'{"name": "ann", "age": 3}') == ({"name": "ann", "age": 3}, [])
+
+
+def test_schema_extractor_drop_mode():
+    schema = {
+        "type": "object",
+        "properties": {
+            "id": {"type": "integer", "readOnly": True},
+            "password": {"type": "string", "writeOnly": True},
+            "nick": {"type": "string", "deprecated": True},
+            "name": {"type": "string"},
+        },
+        "required": ["id", "name"],
+    }
+    text = b'{"id": 1, "password": "pw", "nick": "a", "name": "ann"}'
+    assert JsonExtractor(schema, drop_mode="write").extract(text) == {"password": "pw", "nick": "a", "name": "ann"}
+    assert JsonExtractor(schema, drop_mode="read", drop_deprecated=True).extract(text) == {"id": 1, "name": "ann"}
+    assert JsonExtractor(schema, drop_mode="write").extract(b'{"name": "ann"}') == {"name": "ann"}
+    assert repair_and_validate(text.decode(), schema, drop_mode="read") == {"id": 1, "nick": "a", "name": "ann"}
+    with pytest.raises(ValueError, match="Unknown drop_mode"):
+        JsonExtractor(schema, drop_mode="both")
+
+
+def test_schema_extractor_property_bounds():
+    schema = {
+        "type": "object",
+        "properties": {"meta": {"type": "object", "properties": {"k": {"type": "string"}}, "minProperties": 1}},
+        "required": ["meta"],
+    }
+    extractor = JsonExtractor(schema)
+    assert extractor.extract(b'{"meta": {"k": "v"}}') == {"meta": {"k": "v"}}
+    with pytest.raises(ValueError, match=r"^validation failed at data\.meta: Expected at least 1 properties, found 0"):
+        extractor.extract(b'{"meta": {}}')
+
+
+def test_schema_extractor_root_self_ref():
+    schema = {
+        "type": "object",
+        "required": ["text"],
+        "properties": {"text": {"type": "string"}, "replies": {"type": "array", "items": {"$ref": "#"}}},
+    }
+    extractor = JsonExtractor(schema)
+    text = b'{"text": "a", "replies": [{"text": "b", "replies": [{"text": "c"}]}]}'
+    assert extractor.extract(text) == {"text": "a", "replies": [{"text": "b", "replies": [{"text": "c"}]}]}
+    deep = b'{"text": "x", "replies": [' * 200 + b'{"text": "x"}' + b"]}" * 200
+    with pytest.raises(RecursionError):
+        extractor.extract(deep)
+
+
+def test_repair_and_validate():
+    schema = {
+        "type": "object",
+        "properties": {"name": {"type": "string"}, "age": {"type": "integer"}, "tags": {"type": "array", "items": {"type": "string"}}},
+        "required": ["name"],
+    }
+    text = "{'Name': 'ann', 'age': '42', 'tags': ['a', 1,], 'extra': true"
+    assert repair_and_validate(text, schema, key_normalization="lower") == {"name": "ann", "age": 42, "tags": ["a", "1"]}
+    assert repair_and_validate("{'name': 'ann'}", compile_schema(schema)) == {"name": "ann"}
+    assert repair_and_validate('"name": "ann"', schema, {"assume_object": True}) == {"name": "ann"}
+    with pytest.raises(ValueError, match=r"^validation failed at data\.tags\[1\]: Expected string"):
+        repair_and_validate('{"name": "ann", "tags": ["a", null]}', schema)
+    with pytest.raises(ValueError, match="Missing field: name"):
+        repair_and_validate('{"age": 1}', schema)
+    with pytest.raises(TypeError):
+        repair_and_validate("{}", schema, {"no_such_option": True})
+    number = {"type": "object", "properties": {"a": {"type": "number"}}}
+    assert repair_and_validate('{"a": " 1.5 "}', number) == {"a": 1.5}
+    for bad in ["1.2.3", "1-2", "1e"]:
+        with pytest.raises(ValueError, match=r"^validation failed at data\.a: Expected number"):
+            repair_and_validate('{"a": "%s"}' % bad, number)
+
+
+@dataclasses.dataclass
+class _Point:
+    x: int
+    y: int = 0
+
+
+class _Model:
+    @classmethod
+    def model_validate(cls, value):
+        return ("validated", value)
+
+
+def test_parse_into():
+    assert parse_into("{'x': 1, 'y': 2,}", _Point) == _Point(1, 2)
+    assert parse_into('```json\n{"x": 3}\n```', _Point) == _Point(3)
+    assert parse_into("[1, 2", _Model) == ("validated", [1, 2])
+    with pytest.raises(TypeError, match="to build _Point, got list"):
+        parse_into("[1]", _Point)
+    with pytest.raises(TypeError, match="'z'"):
+        parse_into('{"z": 1}', _Point)
+    with pytest.raises(JsonSyntaxError):
+        parse_into("{]", _Point)
@@ -0,0 +1,2611 @@
+use llm_json_utils::repair::{
+    extract_all_values, parse_into, repair_into, repair_json, repair_json_concatenated,
+    repair_json_status, repair_json_verbose, repair_last_value, repair_to_json_str,
+    repair_to_value, repair_to_value_batch, repair_to_values, repair_value_at, BadUnicodePolicy,
+    BatchStats, BigIntMode, CommentStyles, ControlCharPolicy, ErrorKind, ExtraCommas, Json,
+    PyBuilder, RepairKind, RepairOptions, RepairReport, StreamBuffer, UnclosedComments, ValueSink,
+};
+use llm_json_utils::utils::cursor::Cursor;
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// 修复 `input`，并与 Python 字面量 `expected` 求值结果比较
+fn assert_repairs(py: Python, opts: &RepairOptions, input: &str, expected: &str) {
+    let got =
+        repair_json(py, input, opts).unwrap_or_else(|e| panic!("repair failed for {input:?}: {e}"));
+    let want = py.eval(expected, None, None).unwrap();
+    assert!(
+        got.as_ref(py).eq(want).unwrap(),
+        "{input:?}: got {}, want {expected}",
+        got.as_ref(py).repr().unwrap()
+    );
+}
+
+fn assert_rejects(py: Python, opts: &RepairOptions, input: &str) {
+    if let Ok(got) = repair_json(py, input, opts) {
+        panic!(
+            "expected failure for {input:?}, got {}",
+            got.as_ref(py).repr().unwrap()
+        );
+    }
+}
+
+fn json5() -> RepairOptions {
+    RepairOptions {
+        json5_numbers: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_radix_integers() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = json5();
+        assert_repairs(py, &opts, "[0xFF, 0o17, 0b1010]", "[255, 15, 10]");
+        assert_repairs(py, &opts, "[-0x10, +0B11, 0XaB]", "[-16, 3, 171]");
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"big": 0xFFFFFFFFFFFFFFFFFF}"#,
+            "{'big': 0xFFFFFFFFFFFFFFFFFF}",
+        );
+        assert_repairs(py, &opts, "[0, 0.5, 10]", "[0, 0.5, 10]");
+        assert_rejects(py, &opts, "[0x]");
+        assert_rejects(py, &opts, "[0b102]");
+    })
+}
+
+#[test]
+fn test_radix_integers_require_json5_numbers() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions::default();
+        assert_rejects(py, &opts, "[0xFF]");
+        assert_rejects(py, &opts, r#"{"a": 0b1}"#);
+    })
+}
+
+#[test]
+fn test_bare_dot_numbers() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = json5();
+        assert_repairs(py, &opts, "[.5, 5., -.25]", "[0.5, 5.0, -0.25]");
+        assert_repairs(py, &opts, r#"{"a": .5e1}"#, "{'a': 5.0}");
+        assert_rejects(py, &opts, "[.]");
+
+        let strict = RepairOptions::default();
+        assert_repairs(py, &strict, "[0.5, 5.0, -0.25]", "[0.5, 5.0, -0.25]");
+        assert_rejects(py, &strict, "[.5]");
+        assert_rejects(py, &strict, "[5.]");
+        assert_rejects(py, &strict, r#"{"a": -.25}"#);
+    })
+}
+
+#[test]
+fn test_plus_sign() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = json5();
+        assert_repairs(
+            py,
+            &opts,
+            "[+5, +5.0, 5e+3, +1e-2]",
+            "[5, 5.0, 5000.0, 0.01]",
+        );
+        assert_repairs(
+            py,
+            &opts,
+            "[+123456789012345678901234]",
+            "[123456789012345678901234]",
+        );
+        assert_rejects(py, &opts, "[++5]");
+        assert_rejects(py, &opts, "[+-5]");
+
+        // 标准 JSON 只允许指数里的 `+`
+        let strict = RepairOptions::default();
+        assert_repairs(py, &strict, "[5e+3, 1E+2]", "[5000.0, 100.0]");
+        assert_eq!(
+            repair_error(&strict, "[1, +5]"),
+            "No valid JSON found (at offset 4: Leading '+' is not allowed in number \"+5\" (enable json5_numbers))"
+        );
+        assert_rejects(py, &strict, "[+5.0]");
+        assert_rejects(py, &strict, "[++5]");
+    })
+}
+
+/// 返回修复记录中的 (offset, kind) 序列
+fn repair_kinds(py: Python, input: &str) -> Vec<(usize, RepairKind)> {
+    let (_, repairs) = repair_json_verbose(py, input, &RepairOptions::default()).unwrap();
+    repairs.into_iter().map(|r| (r.offset, r.kind)).collect()
+}
+
+#[test]
+fn test_verbose_repairs() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert!(repair_kinds(py, r#"{"a": [1, 2], "b": null}"#).is_empty());
+        assert_eq!(
+            repair_kinds(py, "{'a': [1, 2,], // note\n"),
+            vec![
+                (1, RepairKind::SingleQuote),
+                (11, RepairKind::ExtraComma),
+                (13, RepairKind::ExtraComma),
+                (15, RepairKind::SkippedComment),
+                (23, RepairKind::ClosedContainer),
+            ]
+        );
+        assert_eq!(
+            repair_kinds(py, r#"Answer: {"a": None, "b": "x\q"} thanks"#),
+            vec![
+                (0, RepairKind::SkippedPrefix),
+                (14, RepairKind::Literal),
+                (27, RepairKind::InvalidEscape),
+                (32, RepairKind::IgnoredSuffix),
+            ]
+        );
+        assert_eq!(
+            repair_kinds(py, r#"[,1,,2, "tail"#),
+            vec![
+                (1, RepairKind::ExtraComma),
+                (4, RepairKind::ExtraComma),
+                (13, RepairKind::ClosedString),
+                (13, RepairKind::ClosedContainer),
+            ]
+        );
+    })
+}
+
+#[test]
+fn test_assume_object_wraps_bare_members() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            assume_object: true,
+            ..Default::default()
+        };
+        assert_repairs(py, &opts, "a: 1, b: 2", "{'a': 1, 'b': 2}");
+        assert_repairs(
+            py,
+            &opts,
+            "\n  \"name\": 'x', tags: [1, 2],\n",
+            "{'name': 'x', 'tags': [1, 2]}",
+        );
+        assert_repairs(py, &opts, "user_id-2: null", "{'user_id-2': None}");
+        // 真正的标量/容器不受影响
+        assert_repairs(py, &opts, "42", "42");
+        assert_repairs(py, &opts, "\"hello\"", "'hello'");
+        assert_repairs(py, &opts, "true", "True");
+        assert_repairs(py, &opts, r#"{"a": 1}"#, "{'a': 1}");
+        // 只有顶层允许裸 key
+        assert_rejects(py, &opts, "a: {b: 1}");
+
+        let strict = RepairOptions::default();
+        assert_rejects(py, &strict, "a: 1, b: 2");
+    })
+}
+
+#[test]
+fn test_repair_to_value_needs_no_python() {
+    let repaired = std::thread::spawn(|| {
+        repair_to_value(
+            "{'a': [1, 2.5, true, null, 123456789012345678901]",
+            &Default::default(),
+        )
+    })
+    .join()
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        repaired.value,
+        Json::Object(vec![(
+            "a".to_string(),
+            Json::Array(vec![
+                Json::Int(1),
+                Json::Float(2.5),
+                Json::Bool(true),
+                Json::Null,
+                Json::BigInt {
+                    digits: "123456789012345678901".to_string(),
+                    radix: 10,
+                },
+            ]),
+        )])
+    );
+    assert_eq!(repaired.repairs.len(), 2);
+}
+
+#[test]
+fn test_repair_to_value_batch_keeps_order() {
+    let texts: Vec<String> = (0..100)
+        .map(|i| match i % 5 {
+            0 => "{\"a\" 1}".to_string(),
+            _ => format!("[{i}"),
+        })
+        .collect();
+    let inputs: Vec<&str> = texts.iter().map(String::as_str).collect();
+    let results = repair_to_value_batch(&inputs, &Default::default());
+    assert_eq!(results.len(), inputs.len());
+    for (i, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(repaired) => assert_eq!(repaired.value, Json::Array(vec![Json::Int(i as i64)])),
+            Err(_) => assert_eq!(i % 5, 0, "input {i} should repair"),
+        }
+    }
+    assert!(repair_to_value_batch(&[], &Default::default()).is_empty());
+}
+
+#[test]
+fn test_batch_stats() {
+    let inputs = [
+        r#"{"a": 1}"#,
+        "{'a': [1, 2,],}",
+        "[1, 2,",
+        "{\"a\" 1}",
+        "{'b': 'x'}",
+    ];
+    let stats = BatchStats::collect(&inputs, &RepairOptions::default());
+    assert_eq!(
+        (stats.total, stats.succeeded, stats.failed, stats.truncated),
+        (5, 4, 1, 1)
+    );
+    // 同一输入里的两个单引号、两个多余逗号各只算一次
+    assert_eq!(stats.kinds[&RepairKind::SingleQuote], 2);
+    assert_eq!(stats.kinds[&RepairKind::ExtraComma], 2);
+    assert_eq!(stats.kinds[&RepairKind::ClosedContainer], 1);
+    assert!(!stats.kinds.contains_key(&RepairKind::SkippedPrefix));
+    assert_eq!(
+        BatchStats::collect(&[], &RepairOptions::default()),
+        BatchStats::default()
+    );
+}
+
+#[test]
+fn test_large_input_scan_releases_gil() {
+    pyo3::prepare_freethreaded_python();
+    // 输入足够大，扫描耗时远超另一个线程拿到 GIL 所需的时间
+    let big = format!("[{}]", vec!["1.5"; 1 << 20].join(","));
+    let started = AtomicBool::new(false);
+    let finished = AtomicBool::new(false);
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            Python::with_gil(|py| {
+                started.store(true, Ordering::SeqCst);
+                repair_json(py, &big, &RepairOptions::default()).unwrap();
+                finished.store(true, Ordering::SeqCst);
+            })
+        });
+        while !started.load(Ordering::SeqCst) {
+            std::hint::spin_loop();
+        }
+        // 若扫描期间仍持有 GIL，这里要等整个调用结束才能进入
+        let overlapped = Python::with_gil(|_| !finished.load(Ordering::SeqCst));
+        assert!(overlapped, "scan of a large input did not release the GIL");
+    });
+}
+
+#[test]
+fn test_slash_after_numbers() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions::default();
+        // 紧贴数字的 `//` 与 `/* */` 仍是注释
+        assert_repairs(py, &opts, "{\"a\": 1//note\n}", "{'a': 1}");
+        assert_repairs(py, &opts, "{\"a\": 1 // note\n}", "{'a': 1}");
+        assert_repairs(py, &opts, r#"{"a": 1/*x*/, "b": 2}"#, "{'a': 1, 'b': 2}");
+        assert_repairs(py, &opts, "[1.5//x\n, 2]", "[1.5, 2]");
+        // 单独的 `/` 是错误，不能吞掉后面的内容
+        assert_rejects(py, &opts, r#"{"ratio": 3/4}"#);
+        assert_rejects(py, &opts, "[1/2]");
+        assert_rejects(py, &opts, r#"{"a": 1 / 2, "b": 3}"#);
+        assert_rejects(py, &opts, r#"{"a": 1e5/}"#);
+        // 顶层值之后的 `/4` 与其他尾随文本一样被忽略
+        assert_repairs(py, &opts, "3/4", "3");
+    })
+}
+
+fn comments(styles: CommentStyles) -> RepairOptions {
+    RepairOptions {
+        comment_styles: styles,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_comment_styles() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input = "{\"a\": 1, # hash\n \"b\": 2 // slash\n, \"c\": /* block */ 3}";
+        let all = "{'a': 1, 'b': 2, 'c': 3}";
+        assert_repairs(py, &RepairOptions::default(), input, all);
+
+        let dash = comments(CommentStyles::default() | CommentStyles::DASH);
+        assert_repairs(
+            py,
+            &dash,
+            "{\"a\": 1 -- sql\n, \"b\": -2}",
+            "{'a': 1, 'b': -2}",
+        );
+        assert_rejects(py, &RepairOptions::default(), "{\"a\": 1 -- sql\n}");
+
+        // 只关掉其中一种，其余照常
+        let no_hash = comments(CommentStyles::SLASH | CommentStyles::BLOCK);
+        assert_rejects(py, &no_hash, input);
+        assert_repairs(py, &no_hash, "[1, // x\n 2]", "[1, 2]");
+        assert_rejects(py, &comments(CommentStyles::SLASH), "[1, /* x */ 2]");
+
+        // 空集合：任何注释符都是错误
+        let strict = comments(CommentStyles::empty());
+        for bad in [
+            "[1, # x\n 2]",
+            "[1, // x\n 2]",
+            "[1, /* x */ 2]",
+            "[1 -- x\n]",
+        ] {
+            assert_rejects(py, &strict, bad);
+        }
+        assert_repairs(
+            py,
+            &strict,
+            "[\"# not // a /* comment\"]",
+            "['# not // a /* comment']",
+        );
+    })
+}
+
+#[test]
+fn test_repair_value_at_cursor() {
+    let opts = RepairOptions::default();
+    let input = r#"first: {"a": 1,} then [2, 3] and "tail"#;
+    let mut cursor = Cursor::new(input.as_bytes());
+    cursor.advance(6);
+    let value = repair_value_at(input, &mut cursor, &opts).unwrap();
+    assert_eq!(value, Json::Object(vec![("a".into(), Json::Int(1))]));
+    assert_eq!(&input[cursor.pos..], " then [2, 3] and \"tail");
+
+    // 起点不是值时报错且游标不动，不会向后寻找其他起点
+    let err = repair_value_at(input, &mut cursor, &opts).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Syntax);
+    assert_eq!(&input[cursor.pos..], " then [2, 3] and \"tail");
+
+    cursor.advance(6);
+    let value = repair_value_at(input, &mut cursor, &opts).unwrap();
+    assert_eq!(value, Json::Array(vec![Json::Int(2), Json::Int(3)]));
+    assert_eq!(&input[cursor.pos..], " and \"tail");
+}
+
+#[test]
+fn test_capture_comments() {
+    let opts = RepairOptions {
+        comment_styles: CommentStyles::default() | CommentStyles::DASH | CommentStyles::HTML,
+        capture_comments: true,
+        ..Default::default()
+    };
+    let input =
+        "# header\n{\"a\": 1, // the answer\n \"b\": /* two */ 2, -- sql\n \"c\": <!-- html --> 3}";
+    let repaired = repair_to_value(input, &opts).unwrap();
+    let comments: Vec<_> = repaired
+        .comments
+        .iter()
+        .map(|c| (c.offset, c.text.as_str()))
+        .collect();
+    assert_eq!(
+        comments,
+        [
+            (0, "header"),
+            (18, "the answer"),
+            (38, "two"),
+            (51, "sql"),
+            (64, "html")
+        ]
+    );
+    for &(offset, _) in &comments {
+        assert!(repaired
+            .repairs
+            .iter()
+            .any(|r| r.offset == offset && r.kind == RepairKind::SkippedComment));
+    }
+
+    // 推测解析中途读到的注释不会留下；未闭合的块注释保留到截断处
+    let wrapped = RepairOptions {
+        assume_object: true,
+        unclosed_comments: UnclosedComments::Consume,
+        ..opts.clone()
+    };
+    let repaired = repair_to_value("a: 1 /* open", &wrapped).unwrap();
+    assert_eq!(repaired.comments.len(), 1);
+    assert_eq!(repaired.comments[0].text, "open");
+
+    // 默认不收集
+    let plain = repair_to_value("[1 /* x */]", &RepairOptions::default()).unwrap();
+    assert!(plain.comments.is_empty());
+}
+
+#[test]
+fn test_doc_comment_slashes() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions::default();
+        assert_repairs(
+            py,
+            &opts,
+            "//! generated answer\n/// the user\n{\n  /// display name\n  \"name\": \"ann\", //! trailing\n  \"tags\": [1, //// four\n 2]\n}",
+            "{'name': 'ann', 'tags': [1, 2]}",
+        );
+        // 行末没有换行时也整行吞掉，不留下多余的 `/` 或 `!`
+        assert_repairs(py, &opts, "[1, 2] ///", "[1, 2]");
+        assert_repairs(py, &opts, "{\"a\": 1 //!}", "{'a': 1}");
+        assert_eq!(kinds(&opts, "[1 /// x\n]"), [RepairKind::SkippedComment]);
+    })
+}
+
+#[test]
+fn test_html_comments() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let html = comments(CommentStyles::default() | CommentStyles::HTML);
+        assert_repairs(
+            py,
+            &html,
+            "{<!-- key -->\"a\": 1, \"b\": [2 <!-- between --> , 3]}",
+            "{'a': 1, 'b': [2, 3]}",
+        );
+        assert_repairs(
+            py,
+            &html,
+            "<!--\n  generated\n  answer\n-->\n{\"a\": \"<!-- kept -->\"}",
+            "{'a': '<!-- kept -->'}",
+        );
+        assert_eq!(kinds(&html, "[1 <!-- x -->]"), [RepairKind::SkippedComment]);
+        // 不启用时 `<!--` 是普通字符
+        assert_rejects(py, &RepairOptions::default(), "[1 <!-- x -->]");
+
+        // 没有 `-->` 时与 `/*` 一样按 unclosed_comments 处理
+        assert_repairs(py, &html, "[1 <!-- open", "[1]");
+        let strict = RepairOptions {
+            unclosed_comments: UnclosedComments::Error,
+            ..html
+        };
+        assert_eq!(
+            repair_error(&strict, "[1 <!-- open"),
+            "No valid JSON found (at offset 3: Unterminated block comment)"
+        );
+    })
+}
+
+#[test]
+fn test_comments_at_every_token_boundary() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let tokens = [
+            "{", "\"a\"", ":", "[", "1", ",", "{", "\"b\"", ":", "null", "}", ",", "]", ",",
+            "\"c\"", ":", "'x'", "}",
+        ];
+        let expected = "{'a': [1, {'b': None}], 'c': 'x'}";
+        let all = comments(CommentStyles::default() | CommentStyles::DASH | CommentStyles::HTML);
+        for comment in [
+            " /* c */ ",
+            "/**/",
+            " // c\n",
+            "# c\n",
+            " -- c\n",
+            "<!-- c -->",
+        ] {
+            // 每个 token 之间都插入注释，首尾也各有一个
+            let input = format!("{comment}{}{comment}", tokens.join(comment));
+            assert_repairs(py, &all, &input, expected);
+        }
+
+        let opts = RepairOptions::default();
+        assert_repairs(
+            py,
+            &opts,
+            "{ /* k */ \"a\" /* after key */ : /* before val */ 1 }",
+            "{'a': 1}",
+        );
+        // 省略花括号的对象体和非字符串 key 同样如此
+        let wrapped = RepairOptions {
+            assume_object: true,
+            coerce_keys: true,
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &wrapped,
+            "a /* x */ : 1, /* y */ b # z\n : 2",
+            "{'a': 1, 'b': 2}",
+        );
+        assert_repairs(py, &wrapped, "{1 /* x */ : 'one'}", "{'1': 'one'}");
+    })
+}
+
+#[test]
+fn test_bigint_mode() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input = "[123456789012345678901, -0x10000000000000000, 7]";
+        let mode = |bigint_mode| RepairOptions {
+            json5_numbers: true,
+            bigint_mode,
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &mode(BigIntMode::PythonInt),
+            input,
+            "[123456789012345678901, -0x10000000000000000, 7]",
+        );
+        assert_repairs(
+            py,
+            &mode(BigIntMode::Float),
+            input,
+            "[1.2345678901234568e20, -1.8446744073709552e19, 7]",
+        );
+        assert_repairs(
+            py,
+            &mode(BigIntMode::String),
+            input,
+            "['123456789012345678901', '-0x10000000000000000', 7]",
+        );
+        // 放得进 i64 的整数不受影响
+        assert_repairs(
+            py,
+            &mode(BigIntMode::String),
+            "[-9223372036854775808]",
+            "[-9223372036854775808]",
+        );
+    })
+}
+
+#[test]
+fn test_skip_separators() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            skip_separators: true,
+            ..Default::default()
+        };
+        assert_repairs(py, &opts, "---\n{\"a\": 1}\n---\n", "{'a': 1}");
+        assert_repairs(py, &opts, "  =====  \r\n[1,\n---\n 2]", "[1, 2]");
+        // 不是整行分隔线的不动
+        assert_rejects(py, &opts, "[1,\n---5\n]");
+        assert_rejects(py, &opts, "[1, ---\n 2]");
+        assert_rejects(py, &opts, "[1,\n--\n 2]");
+
+        let (_, repairs) = repair_json_verbose(py, "===\n[]", &opts).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(repairs[0].kind, RepairKind::SkippedSeparator);
+        assert_eq!(repairs[0].detail, "===");
+
+        assert_rejects(py, &RepairOptions::default(), "[1,\n---\n 2]");
+    })
+}
+
+#[test]
+fn test_reserialize_matches_json_dumps() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let json = py.import("json").unwrap();
+        let opts = json5();
+        let inputs = [
+            "{'a': [1, 2.5, true, null], 'b': {}, 'c': [], 'd': {'e': [[]]}}",
+            r#"["tab\there", "quote\"", "back\\slash", "\u0001", "中文"]"#,
+            "[0x1FFFFFFFFFFFFFFFFF, -123456789012345678901, 0.001, 123.456, NaN, Infinity]",
+            "42",
+        ];
+        for input in inputs {
+            let value = repair_json(py, input, &opts).unwrap();
+            for indent in [None, Some(0), Some(2), Some(4)] {
+                let kwargs = pyo3::types::PyDict::new(py);
+                kwargs.set_item("ensure_ascii", false).unwrap();
+                kwargs.set_item("indent", indent).unwrap();
+                let want: String = json
+                    .call_method("dumps", (value.as_ref(py),), Some(kwargs))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                let got = repair_to_json_str(py, input, &opts, indent).unwrap();
+                assert_eq!(got, want, "{input:?} indent={indent:?}");
+            }
+        }
+    })
+}
+
+#[test]
+fn test_float_formatting_matches_python_repr() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        for f in [
+            1.0,
+            -0.0,
+            0.1,
+            0.30000000000000004,
+            1e-4,
+            1.5e-7,
+            1e-5,
+            123.456,
+            9999999999999998.0,
+            1e16,
+            1.2345678901234568e17,
+            1e300,
+            f64::MIN_POSITIVE,
+            5e-324,
+            f64::MAX,
+            // 最短位数下有两种写法都能还原，Python 取离真值最近的
+            -1113178120592002.2,
+            111659285584252.12,
+        ] {
+            // Rust 的 Debug 写法是合法的 Python 浮点字面量，且还原出同一个 f64
+            let want: String = py
+                .eval(&format!("repr({f:?})"), None, None)
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(Json::Float(f).to_minified_string(false), want, "{f:e}");
+            let back: f64 = want.parse().unwrap();
+            assert_eq!(back.to_bits(), f.to_bits(), "{want} does not round-trip");
+        }
+        assert_eq!(
+            Json::Float(-1113178120592002.2).to_minified_string(false),
+            "-1113178120592002.2"
+        );
+    })
+}
+
+#[test]
+fn test_indent_layout() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let got = repair_to_json_str(
+            py,
+            "{'a': [1, {}], 'b': []}",
+            &RepairOptions::default(),
+            Some(2),
+        );
+        assert_eq!(
+            got.unwrap(),
+            "{\n  \"a\": [\n    1,\n    {}\n  ],\n  \"b\": []\n}"
+        );
+    })
+}
+
+#[test]
+fn test_escape_forward_slashes() {
+    pyo3::prepare_freethreaded_python();
+    let input = r#"{"html": "<\/script>", "url/path": "a/b"}"#;
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            escape_forward_slashes: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            repair_to_json_str(py, input, &opts, None).unwrap(),
+            r#"{"html": "<\/script>", "url\/path": "a\/b"}"#
+        );
+        // 默认同 `json.dumps`，`/` 不转义；解析结果不受影响
+        assert_eq!(
+            repair_to_json_str(py, input, &RepairOptions::default(), None).unwrap(),
+            r#"{"html": "</script>", "url/path": "a/b"}"#
+        );
+        assert_repairs(py, &opts, input, "{'html': '</script>', 'url/path': 'a/b'}");
+    });
+    let value = Json::Array(vec![Json::Str("/".into())]);
+    assert_eq!(value.to_minified_string(true), r#"["\/"]"#);
+    assert_eq!(value.to_json_string(Some(1), true), "[\n \"\\/\"\n]");
+}
+
+#[test]
+fn test_lenient_missing_values() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            lenient_missing_values: true,
+            ..Default::default()
+        };
+        assert_repairs(py, &opts, r#"{"a": 1, "b":}"#, "{'a': 1, 'b': None}");
+        assert_repairs(py, &opts, r#"{"a":, "b": 2}"#, "{'a': None, 'b': 2}");
+        assert_repairs(py, &opts, r#"{"a": 1, "b":"#, "{'a': 1, 'b': None}");
+        assert_repairs(py, &opts, r#"{"a": 1, "b""#, "{'a': 1, 'b': None}");
+        assert_repairs(py, &opts, r#"{"a": 1, "b" }"#, "{'a': 1, 'b': None}");
+        assert_repairs(py, &opts, r#"[{"a":}]"#, "[{'a': None}]");
+        // 只补缺失的值，其他错误照旧
+        assert_rejects(py, &opts, r#"{"a" 1}"#);
+        assert_rejects(py, &opts, r#"{"a": , 1}"#);
+
+        let (_, repairs) = repair_json_verbose(py, r#"{"b":}"#, &opts).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(
+            (repairs[0].offset, repairs[0].kind),
+            (5, RepairKind::MissingValue)
+        );
+
+        let strict = RepairOptions::default();
+        assert_rejects(py, &strict, r#"{"a": 1, "b":}"#);
+        assert_rejects(py, &strict, r#"{"a": 1, "b""#);
+    })
+}
+
+fn assert_concatenated(py: Python, input: &str, expected: &str) {
+    let got = repair_json_concatenated(py, input, &RepairOptions::default()).unwrap();
+    let want = py.eval(expected, None, None).unwrap();
+    assert!(
+        got.as_ref(py).eq(want).unwrap(),
+        "{input:?}: got {}, want {expected}",
+        got.as_ref(py).repr().unwrap()
+    );
+}
+
+#[test]
+fn test_concatenated_values() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_concatenated(py, r#"{"a":1}{"b":2}"#, "[{'a': 1}, {'b': 2}]");
+        assert_concatenated(
+            py,
+            "{\"a\":1}\n// next\n[2, 3]  \"x\" 4",
+            "[{'a': 1}, [2, 3], 'x', 4]",
+        );
+        assert_concatenated(py, r#"{"a":1}, {"b":2},"#, "[{'a': 1}, {'b': 2}]");
+        assert_concatenated(py, r#"{"a":1}"#, "[{'a': 1}]");
+        // 前缀、尾随文本与截断的最后一个值
+        assert_concatenated(py, r#"Result: {"a":1}{"b":2} done"#, "[{'a': 1}, {'b': 2}]");
+        assert_concatenated(py, r#"{"a":1}{"b":"#, "[{'a': 1}]");
+        assert_concatenated(py, r#"{"a":1}{"b": [2"#, "[{'a': 1}, {'b': [2]}]");
+        assert!(repair_json_concatenated(py, "no json", &RepairOptions::default()).is_err());
+    })
+}
+
+#[test]
+fn test_leading_zero_as_string() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            leading_zero_as_string: true,
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"id": 0042, "code": -007, "x": 007.50}"#,
+            "{'id': '0042', 'code': '-007', 'x': '007.50'}",
+        );
+        // 单个 0 和 0. 开头的小数仍是数字
+        assert_repairs(py, &opts, "[0, 0.5, -0, 0e3, 10]", "[0, 0.5, 0, 0.0, 10]");
+
+        let default = RepairOptions::default();
+        assert_repairs(py, &default, "[0042, 007]", "[42, 7]");
+    })
+}
+
+fn repair_error(opts: &RepairOptions, input: &str) -> String {
+    match repair_to_value(input, opts) {
+        Ok(got) => panic!("expected failure for {input:?}, got {:?}", got.value),
+        Err(e) => e.message,
+    }
+}
+
+#[test]
+fn test_string_fast_path_boundaries() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions::default();
+        // 整段复制的前缀之后仍要正确处理转义、另一种引号、控制字符与 EOF
+        assert_repairs(py, &opts, r#"["plain 中文 😀"]"#, "['plain 中文 😀']");
+        assert_repairs(py, &opts, r#"["abc\"def\n"]"#, "['abc\"def\\n']");
+        assert_repairs(py, &opts, r#"['it"s']"#, "['it\"s']");
+        assert_repairs(py, &opts, "[\"tab\there\"]", "['tab\\there']");
+        assert_repairs(py, &opts, r#"["unclosed é"#, "['unclosed é']");
+        assert_repairs(py, &opts, r#"["", '']"#, "['', '']");
+    })
+}
+
+#[test]
+fn test_numeric_key_matrix() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let coerce = RepairOptions {
+            coerce_keys: true,
+            ..Default::default()
+        };
+        let coerce_json5 = RepairOptions {
+            json5_numbers: true,
+            ..coerce.clone()
+        };
+        let option_sets = [RepairOptions::default(), json5(), coerce, coerce_json5];
+        // 每行：输入，以及上面四组选项下的期望（None 表示报错）
+        let matrix: [(&str, [Option<&str>; 4]); 9] = [
+            // 带引号的数字 key 始终原样保留为字符串
+            (r#"{"12": 1}"#, [Some("{'12': 1}"); 4]),
+            ("{'12': 1}", [Some("{'12': 1}"); 4]),
+            (r#"{"-1.50": 1}"#, [Some("{'-1.50': 1}"); 4]),
+            (r#"{"1e3": 1}"#, [Some("{'1e3': 1}"); 4]),
+            (r#"{"0x1F": 1}"#, [Some("{'0x1F': 1}"); 4]),
+            // 裸数字 key 只在 coerce_keys 下接受，且按原文转成字符串，不会变成 int 或规范化的数字
+            (
+                "{12: 1}",
+                [None, None, Some("{'12': 1}"), Some("{'12': 1}")],
+            ),
+            (
+                "{-1.50: 1}",
+                [None, None, Some("{'-1.50': 1}"), Some("{'-1.50': 1}")],
+            ),
+            (
+                "{12345678901234567890123: 1}",
+                [
+                    None,
+                    None,
+                    Some("{'12345678901234567890123': 1}"),
+                    Some("{'12345678901234567890123': 1}"),
+                ],
+            ),
+            // 进制前缀还需要 json5_numbers 才是一个数字
+            ("{0x1F: 1}", [None, None, None, Some("{'0x1F': 1}")]),
+        ];
+        for (input, expected) in matrix {
+            for (opts, want) in option_sets.iter().zip(expected) {
+                match want {
+                    Some(want) => assert_repairs(py, opts, input, want),
+                    None => assert_rejects(py, opts, input),
+                }
+            }
+        }
+        // 同一个数字，带不带引号得到同一个字符串 key，合并时后者覆盖前者
+        assert_repairs(
+            py,
+            &option_sets[2],
+            r#"{"7": "quoted", 7: "bare"}"#,
+            "{'7': 'bare'}",
+        );
+    })
+}
+
+#[test]
+fn test_coerce_keys() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            coerce_keys: true,
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &opts,
+            "{1: 'a', -2.50: 'b', true: 'c', None: 'd', null: 'e'}",
+            "{'1': 'a', '-2.50': 'b', 'true': 'c', 'null': 'e'}",
+        );
+        assert_repairs(py, &opts, "{False: 0}", "{'false': 0}");
+
+        let (_, repairs) = repair_json_verbose(py, "{True: 1}", &opts).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(
+            (repairs[0].kind, repairs[0].detail.as_str()),
+            (RepairKind::CoercedKey, "true")
+        );
+
+        // 关闭时报错信息带上原始 key
+        let strict = RepairOptions::default();
+        assert_eq!(
+            repair_error(&strict, "{\"a\": {12: 'x'}}"),
+            "No valid JSON found (at offset 7: Object keys must be strings, got 12)"
+        );
+        assert_eq!(
+            repair_error(&strict, "{None: 1}"),
+            "No valid JSON found (at offset 1: Object keys must be strings, got None)"
+        );
+        assert_eq!(
+            repair_error(&strict, "{@: 1}"),
+            "No valid JSON found (at offset 1: Object keys must be strings, got '@')"
+        );
+    })
+}
+
+#[test]
+fn test_nesting_limit() {
+    let opts = RepairOptions::default();
+    let ok = format!("{}{}", "[".repeat(500), "]".repeat(500));
+    assert!(repair_to_value(&ok, &opts).is_ok());
+
+    // 超过上限时报错而不是爆栈；最内层缺值，回退扫描也救不回来
+    let deep = "[{\"a\": ".repeat(1000);
+    let message = repair_error(&opts, &deep);
+    assert!(
+        message.contains("Maximum nesting depth 512 exceeded"),
+        "{message}"
+    );
+}
+
+#[test]
+fn test_error_kinds() {
+    let opts = RepairOptions::default();
+    let error = |input: &str| repair_to_value(input, &opts).unwrap_err();
+
+    let input = "[1,\n -";
+    let truncated = error(input);
+    assert_eq!(
+        (truncated.kind, truncated.offset, truncated.position(input)),
+        (ErrorKind::Truncated, 6, (2, 3))
+    );
+
+    let input = "[1, 2 @ 3]";
+    let syntax = error(input);
+    assert_eq!(
+        (syntax.kind, syntax.offset, syntax.position(input)),
+        (ErrorKind::Syntax, 6, (1, 7))
+    );
+
+    // 列按字符计，多字节字符只算一列
+    let input = "[\"é\" @]";
+    let syntax = error(input);
+    assert_eq!((syntax.offset, syntax.position(input)), (6, (1, 6)));
+
+    assert_eq!(error(&"[{\"a\": ".repeat(1000)).kind, ErrorKind::Depth);
+    let shallow = RepairOptions {
+        max_depth: Some(2),
+        ..RepairOptions::default()
+    };
+    assert!(repair_to_value("[[1]]", &shallow).is_ok());
+    let deep = repair_to_value("[[[1]]]", &shallow).unwrap_err();
+    assert_eq!(
+        (deep.kind, deep.message.as_str()),
+        (ErrorKind::Depth, "Maximum nesting depth 2 exceeded")
+    );
+
+    let limited = RepairOptions {
+        max_bytes: Some(2),
+        ..RepairOptions::default()
+    };
+    let too_large = repair_to_value("[1, 2]", &limited).unwrap_err();
+    assert_eq!(too_large.kind, ErrorKind::TooLarge);
+}
+
+/// 固定种子的 xorshift，生成可复现的随机输入
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next() % items.len() as u64) as usize]
+    }
+}
+
+const FRAGMENTS: &[&str] = &[
+    "{",
+    "}",
+    "[",
+    "]",
+    ":",
+    ",",
+    "\"",
+    "'",
+    "\\",
+    "\\u",
+    "\\u12",
+    "0x",
+    "-",
+    "+",
+    ".",
+    "e",
+    "1",
+    "0042",
+    "99999999999999999999",
+    "true",
+    "None",
+    "nan",
+    "Infinity",
+    "//",
+    "/*",
+    "*/",
+    "#",
+    "--",
+    "---\n",
+    "```json\n",
+    "```",
+    "\n",
+    " ",
+    "\t",
+    "a",
+    "key",
+    "é",
+    "中",
+    "😀",
+    "\u{0}",
+];
+
+#[test]
+fn test_random_input_never_panics() {
+    let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+    let everything = RepairOptions {
+        json5_numbers: true,
+        assume_object: true,
+        comment_styles: CommentStyles::HASH
+            | CommentStyles::SLASH
+            | CommentStyles::BLOCK
+            | CommentStyles::DASH
+            | CommentStyles::HTML,
+        skip_separators: true,
+        skip_zero_width: true,
+        strip_html: true,
+        strip_markdown_emphasis: true,
+        skip_leading_garbage: true,
+        escape_forward_slashes: true,
+        lenient_separators: true,
+        capture_comments: true,
+        lenient_missing_values: true,
+        leading_zero_as_string: true,
+        coerce_whole_floats: true,
+        raw_numbers: true,
+        normalize_unicode_digits: true,
+        normalize_unicode_minus: true,
+        coerce_keys: true,
+        bigint_mode: BigIntMode::String,
+        lenient_commas: true,
+        lenient_brackets: true,
+        js_literals: true,
+        extra_commas: ExtraCommas::Ignore,
+        auto_unstringify: true,
+        sort_keys: true,
+        unclosed_comments: UnclosedComments::Recover,
+        control_char_policy: ControlCharPolicy::Escape,
+        bad_unicode_policy: BadUnicodePolicy::Replace,
+        line_continuations: true,
+        max_bytes: None,
+        max_depth: None,
+        max_items: None,
+        intern_keys: true,
+        value_hook: None,
+        object_pairs_hook: None,
+    };
+    let option_sets = [RepairOptions::default(), everything];
+
+    for _ in 0..3000 {
+        let len = rng.next() % 48;
+        let input: String = (0..len).map(|_| rng.pick(FRAGMENTS)).collect();
+        for opts in &option_sets {
+            if let Ok(repaired) = repair_to_value(&input, opts) {
+                repaired.value.to_json_string(Some(2), false);
+            }
+            let _ = repair_to_values(&input, opts);
+        }
+    }
+}
+
+/// 随机字符串用的字符：引号、反斜杠、控制字符、注释起始符和多字节字符
+const STRING_CHARS: &[char] = &[
+    'a', 'Z', '0', ' ', '"', '\'', '\\', '/', '*', '#', '\n', '\t', '\u{1}', '\u{1f}', 'é', '中',
+    '😀', '\u{2028}', '{', ']', ',', ':',
+];
+
+impl XorShift {
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn string(&mut self) -> String {
+        let len = self.below(8);
+        (0..len)
+            .map(|_| STRING_CHARS[self.below(STRING_CHARS.len() as u64) as usize])
+            .collect()
+    }
+
+    /// 递归生成一个合法的 JSON 值；不生成 NaN，它与自身不相等
+    fn value(&mut self, depth: usize) -> Json {
+        let leaf_only = depth >= 4;
+        match self.below(if leaf_only { 6 } else { 8 }) {
+            0 => Json::Null,
+            1 => Json::Bool(self.next() & 1 == 1),
+            2 => Json::Int(self.next() as i64 >> self.below(64)),
+            3 => loop {
+                let f = f64::from_bits(self.next());
+                if f.is_finite() {
+                    break Json::Float(f);
+                }
+            },
+            4 => Json::BigInt {
+                digits: format!("-{}{:018}", u64::MAX, self.below(10u64.pow(18))),
+                radix: 10,
+            },
+            5 => Json::Str(self.string()),
+            6 => Json::Array((0..self.below(4)).map(|_| self.value(depth + 1)).collect()),
+            _ => Json::Object(
+                (0..self.below(4))
+                    .map(|_| (self.string(), self.value(depth + 1)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// 记号之间随机插入的空白与注释
+    fn noise(&mut self, out: &mut String) {
+        out.push_str(match self.below(6) {
+            0 => " // line comment\n",
+            1 => "/* block */",
+            2 => "# hash comment\n",
+            3 => "\n\t ",
+            _ => "",
+        });
+    }
+}
+
+/// 把值写成等价但带注释、单引号字符串和尾随逗号的文本
+fn write_dirty(rng: &mut XorShift, value: &Json, out: &mut String) {
+    rng.noise(out);
+    match value {
+        Json::Str(s) => write_dirty_string(rng, s, out),
+        Json::Array(items) => {
+            out.push('[');
+            for item in items {
+                write_dirty(rng, item, out);
+                rng.noise(out);
+                out.push(',');
+            }
+            trim_last_comma(rng, out);
+            out.push(']');
+        }
+        Json::Object(members) => {
+            out.push('{');
+            for (key, item) in members {
+                rng.noise(out);
+                write_dirty_string(rng, key, out);
+                rng.noise(out);
+                out.push(':');
+                write_dirty(rng, item, out);
+                out.push(',');
+            }
+            trim_last_comma(rng, out);
+            out.push('}');
+        }
+        leaf => out.push_str(&leaf.to_minified_string(false)),
+    }
+    rng.noise(out);
+}
+
+/// 非空容器一半保留尾随逗号
+fn trim_last_comma(rng: &mut XorShift, out: &mut String) {
+    if out.ends_with(',') && rng.next() & 1 == 0 {
+        out.pop();
+    }
+}
+
+fn write_dirty_string(rng: &mut XorShift, s: &str, out: &mut String) {
+    let clean = Json::Str(s.to_string()).to_minified_string(false);
+    if rng.next() & 1 == 0 {
+        out.push_str(&clean);
+        return;
+    }
+    // 换成单引号：内部的 `'` 需要转义，`"` 不再需要
+    let body = &clean[1..clean.len() - 1];
+    out.push('\'');
+    out.push_str(&body.replace("\\\"", "\"").replace('\'', "\\'"));
+    out.push('\'');
+}
+
+#[test]
+fn test_roundtrip_random_values() {
+    let mut rng = XorShift(0x2545_F491_4F6C_DD1D);
+    let opts = RepairOptions::default();
+    for _ in 0..2000 {
+        let value = rng.value(0);
+        for text in [
+            value.to_minified_string(false),
+            value.to_json_string(Some(2), false),
+        ] {
+            let repaired = repair_to_value(&text, &opts)
+                .unwrap_or_else(|e| panic!("{text:?} failed to parse: {e}"));
+            assert_eq!(repaired.value, value, "{text:?}");
+            assert!(
+                repaired.repairs.is_empty(),
+                "{text:?}: {:?}",
+                repaired.repairs
+            );
+        }
+    }
+}
+
+#[test]
+fn test_roundtrip_dirtied_values() {
+    let mut rng = XorShift(0xD1B5_4A32_D192_ED03);
+    let opts = RepairOptions::default();
+    for _ in 0..2000 {
+        let value = rng.value(0);
+        let mut text = String::new();
+        write_dirty(&mut rng, &value, &mut text);
+        let repaired = repair_to_value(&text, &opts)
+            .unwrap_or_else(|e| panic!("{text:?} failed to parse: {e}"));
+        assert_eq!(repaired.value, value, "{text:?}");
+    }
+}
+
+#[test]
+fn test_write_json_chunks() {
+    let records = format!(
+        "[{}]",
+        vec![r#"{"text": "é 中 😀", "n": [1, 2.5, null]}"#; 4000].join(",")
+    );
+    let value = repair_to_value(&records, &RepairOptions::default())
+        .unwrap()
+        .value;
+    for indent in [None, Some(2)] {
+        let mut chunks = Vec::new();
+        value
+            .write_json_chunks(indent, false, |chunk| {
+                chunks.push(chunk.to_string());
+                Ok::<(), ()>(())
+            })
+            .unwrap();
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), value.to_json_string(indent, false));
+    }
+    // 第一次出错后不再输出
+    let mut calls = 0;
+    let result = value.write_json_chunks(None, false, |_| {
+        calls += 1;
+        Err("disk full")
+    });
+    assert_eq!((result, calls), (Err("disk full"), 1));
+}
+
+fn kinds(opts: &RepairOptions, input: &str) -> Vec<RepairKind> {
+    let repaired = repair_to_value(input, opts).unwrap();
+    repaired.repairs.into_iter().map(|r| r.kind).collect()
+}
+
+fn unclosed(policy: UnclosedComments) -> RepairOptions {
+    RepairOptions {
+        unclosed_comments: policy,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_unclosed_block_comments() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input = r#"{"a": 1 /* note, "b": 2}"#;
+        // 默认一直吞到输入结束，并记一条 unclosed_comment
+        let defaults = RepairOptions::default();
+        assert_repairs(py, &defaults, input, "{'a': 1}");
+        assert_repairs(py, &defaults, "[1, 2 /* oops", "[1, 2]");
+        assert!(kinds(&defaults, input).contains(&RepairKind::UnclosedComment));
+
+        let strict = unclosed(UnclosedComments::Error);
+        assert_eq!(
+            repair_error(&strict, input),
+            "No valid JSON found (at offset 8: Unterminated block comment)"
+        );
+        // 闭合的注释与关闭块注释语法时不受影响
+        assert_repairs(py, &strict, r#"{"a": 1 /* ok */}"#, "{'a': 1}");
+        assert_rejects(
+            py,
+            &RepairOptions {
+                comment_styles: CommentStyles::HASH,
+                ..strict.clone()
+            },
+            input,
+        );
+
+        let recover = unclosed(UnclosedComments::Recover);
+        assert_repairs(py, &recover, input, "{'a': 1}");
+        assert_repairs(py, &recover, "[1, /* two\n 3]", "[1]");
+        assert_eq!(
+            kinds(&recover, "[1 /* x"),
+            [
+                RepairKind::UnclosedComment,
+                RepairKind::SkippedComment,
+                RepairKind::ClosedContainer
+            ]
+        );
+
+        let consume = unclosed(UnclosedComments::Consume);
+        assert_repairs(py, &consume, r#"{"a": 1 /* } ] "b": 2"#, "{'a': 1}");
+        assert!(kinds(&consume, "[1] /* x").contains(&RepairKind::UnclosedComment));
+    })
+}
+
+#[test]
+fn test_mixed_quote_strings() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions::default();
+        for (input, expected) in [
+            // 非定界引号原样保留，定界引号的转义被还原
+            (r#"['it\'s "quoted"']"#, r#"['it\'s "quoted"']"#),
+            (r#"["it's \"quoted\""]"#, r#"['it\'s "quoted"']"#),
+            // 两种转义在两种定界符里都还原成引号本身
+            (r#"['say \"hi\"']"#, r#"['say "hi"']"#),
+            (r#"["it\'s"]"#, r#"["it's"]"#),
+            (r#"{'k"ey': "v'al"}"#, r#"{'k"ey': "v'al"}"#),
+            (r#"['\\\'']"#, r#"["\\'"]"#),
+        ] {
+            assert_repairs(py, &opts, input, expected);
+        }
+        assert_rejects(py, &opts, r#"['it's']"#);
+    })
+}
+
+#[test]
+fn test_value_hook() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        // 把 tag 附在每个叶子上，容器本身不经过 hook
+        let tagging = RepairOptions {
+            value_hook: Some(py.eval("lambda v, t: (t, v)", None, None).unwrap().into()),
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &tagging,
+            "{'a': [1, 2.5, 'x', true, None, 123456789012345678901234567890]}",
+            "{'a': [('int', 1), ('float', 2.5), ('str', 'x'), ('bool', True), \
+             ('null', None), ('int', 123456789012345678901234567890)]}",
+        );
+
+        let clamp = RepairOptions {
+            value_hook: Some(
+                py.eval("lambda v, t: min(v, 10) if t == 'int' else v", None, None)
+                    .unwrap()
+                    .into(),
+            ),
+            ..Default::default()
+        };
+        assert_repairs(py, &clamp, "[5, 50, '50']", "[5, 10, '50']");
+
+        let failing = RepairOptions {
+            value_hook: Some(py.eval("lambda v, t: 1 / 0", None, None).unwrap().into()),
+            ..Default::default()
+        };
+        let err = repair_json(py, "[1]", &failing).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyZeroDivisionError>(py));
+        assert!(repair_to_json_str(py, "[1]", &failing, None).is_err());
+    })
+}
+
+fn control_chars(policy: ControlCharPolicy) -> RepairOptions {
+    RepairOptions {
+        control_char_policy: policy,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_control_char_policy() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input = "[\"a\tb\nc\u{1b}d\", \"keep \\n escapes\"]";
+        assert_repairs(
+            py,
+            &control_chars(ControlCharPolicy::Keep),
+            input,
+            "['a\\tb\\nc\\x1bd', 'keep \\n escapes']",
+        );
+        assert_repairs(
+            py,
+            &control_chars(ControlCharPolicy::Escape),
+            input,
+            r"['a\\tb\\nc\\u001bd', 'keep \n escapes']",
+        );
+        assert_repairs(
+            py,
+            &control_chars(ControlCharPolicy::Strip),
+            input,
+            "['abcd', 'keep \\n escapes']",
+        );
+
+        // 转义后重新序列化不再含原始控制字符
+        let escaped = repair_to_value(input, &control_chars(ControlCharPolicy::Escape)).unwrap();
+        let text = escaped.value.to_json_string(None, false);
+        assert!(!text.chars().any(|c| c < '\u{20}'), "{text:?}");
+        assert_eq!(
+            escaped
+                .repairs
+                .iter()
+                .map(|r| r.detail.as_str())
+                .collect::<Vec<_>>(),
+            ["\\t", "\\n", "\\u001b"]
+        );
+    })
+}
+
+#[test]
+fn test_max_bytes() {
+    let opts = RepairOptions {
+        max_bytes: Some(8),
+        ..Default::default()
+    };
+    assert!(repair_to_value("[\"abcd\"]", &opts).is_ok());
+    // 按 UTF-8 字节计：4 个字符、9 个字节
+    assert_eq!(
+        repair_error(&opts, "[\"é中\"]"),
+        "Input is 9 bytes, exceeding max_bytes=8"
+    );
+    assert!(repair_to_values("[1] [2] [3]", &opts).is_err());
+}
+
+#[test]
+fn test_max_items() {
+    let opts = RepairOptions {
+        max_items: Some(4),
+        ..Default::default()
+    };
+    // 只计标量与 key，容器本身不计
+    assert!(repair_to_value("[1, 2, 3]", &opts).is_ok());
+    assert!(repair_to_value(r#"{"a": 1, "b": 2}"#, &opts).is_ok());
+    assert!(repair_to_value("[[], {}, [[]], 1]", &opts).is_ok());
+    let err = repair_to_value(r#"[{"a": 1}, 2, 3, 4]"#, &opts).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::TooLarge);
+    assert_eq!(
+        err.message,
+        "Input has more than 4 values and keys, exceeding max_items=4"
+    );
+    // 超出预算后不再换起点重试
+    let nested = format!("x [{}]", "[1, 2], ".repeat(4));
+    assert_eq!(
+        repair_to_value(&nested, &opts).unwrap_err().kind,
+        ErrorKind::TooLarge
+    );
+}
+
+#[test]
+fn test_lenient_commas() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            lenient_commas: true,
+            ..Default::default()
+        };
+        assert_repairs(py, &opts, "[1 2 3]", "[1, 2, 3]");
+        assert_repairs(
+            py,
+            &opts,
+            "[[1] {'a': 1}\n true null]",
+            "[[1], {'a': 1}, True, None]",
+        );
+        assert_repairs(py, &opts, r#"{"a": 1 "b": [2 3]}"#, "{'a': 1, 'b': [2, 3]}");
+        // 相邻字符串不拼接，而是两个元素
+        assert_repairs(py, &opts, r#"["a" "b"]"#, "['a', 'b']");
+        // 对象里第二个字符串被当成下一个 key，没有冒号就报错
+        assert_rejects(py, &opts, r#"{"k": "a" "b"}"#);
+        assert_eq!(kinds(&opts, "[1 2]"), [RepairKind::MissingComma]);
+        // 补上逗号后仍要是合法成员
+        assert_rejects(py, &opts, "[1 )]");
+        assert_rejects(py, &opts, r#"{"a": 1 2: 3}"#);
+
+        let strict = RepairOptions::default();
+        assert_rejects(py, &strict, "[1 2 3]");
+        assert_rejects(py, &strict, r#"["a" "b"]"#);
+    })
+}
+
+/// 逐块喂入，返回每次 feed 之后的部分结果
+fn stream_partials(chunks: &[&str]) -> Vec<Option<Json>> {
+    let opts = RepairOptions::default();
+    let mut buffer = StreamBuffer::default();
+    chunks
+        .iter()
+        .map(|chunk| {
+            buffer.feed(chunk);
+            buffer.partial(&opts).cloned()
+        })
+        .collect()
+}
+
+#[test]
+fn test_stream_buffer_partials() {
+    let int_array =
+        |items: &[i64]| Some(Json::Array(items.iter().map(|&i| Json::Int(i)).collect()));
+    assert_eq!(
+        stream_partials(&["[1, 2", ", tr", "ue, -", "3]"]),
+        [
+            int_array(&[1, 2]),
+            int_array(&[1, 2]),
+            Some(Json::Array(vec![
+                Json::Int(1),
+                Json::Int(2),
+                Json::Bool(true)
+            ])),
+            Some(Json::Array(vec![
+                Json::Int(1),
+                Json::Int(2),
+                Json::Bool(true),
+                Json::Int(-3)
+            ])),
+        ]
+    );
+
+    // 半截的 key 和字符串值：丢掉悬空的 key，截断的字符串先按已收到的部分给出
+    let partials = stream_partials(&["Sure: {\"a\": 1, \"na", "me\": \"Ad", "a\"}"]);
+    let object = |members: &[(&str, Json)]| {
+        Some(Json::Object(
+            members
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        ))
+    };
+    assert_eq!(
+        partials,
+        [
+            object(&[("a", Json::Int(1))]),
+            object(&[("a", Json::Int(1)), ("name", Json::Str("Ad".into()))]),
+            object(&[("a", Json::Int(1)), ("name", Json::Str("Ada".into()))]),
+        ]
+    );
+
+    assert_eq!(stream_partials(&["thinking..."]), [None]);
+
+    // 截断处之后的边界超过重试上限时，沿用上一次的结果而不是变回 None
+    let garbage = ", @".repeat(12);
+    assert_eq!(
+        stream_partials(&["[1, 2", &garbage]),
+        [int_array(&[1, 2]), int_array(&[1, 2])]
+    );
+
+    // 续读时已定型的成员仍计入 max_items
+    let opts = RepairOptions {
+        max_items: Some(3),
+        ..Default::default()
+    };
+    let mut buffer = StreamBuffer::default();
+    buffer.feed("[[1], 2, ");
+    assert!(buffer.partial(&opts).is_some());
+    buffer.feed("3, 4]");
+    assert_eq!(
+        buffer.partial(&opts),
+        Some(&Json::Array(vec![
+            Json::Array(vec![Json::Int(1)]),
+            Json::Int(2),
+            Json::Int(3)
+        ]))
+    );
+}
+
+#[test]
+fn test_stream_buffer_byte_at_a_time() {
+    let items: Vec<String> = (0..60)
+        .map(|i| format!(r#"{{"id": {i}, "tags": ["a", "b,c"], "ok": true, "note": null}}"#))
+        .collect();
+    let text = format!("Here you go: [{}] done", items.join(", "));
+    let opts = RepairOptions::default();
+    let mut buffer = StreamBuffer::default();
+    let mut seen = 0;
+    for (i, ch) in text.char_indices() {
+        buffer.feed(&text[i..i + ch.len_utf8()]);
+        match buffer.partial(&opts) {
+            // 一旦给出过结果就不会再变回 None，元素个数也不会倒退
+            Some(Json::Array(values)) => {
+                assert!(values.len() >= seen, "{} after {seen}", values.len());
+                seen = seen.max(values.len());
+            }
+            other => assert!(seen == 0 && other.is_none(), "{other:?} at byte {i}"),
+        }
+    }
+    let full = repair_to_value(&text, &opts).unwrap().value;
+    assert_eq!(buffer.partial(&opts), Some(&full));
+    assert_eq!(seen, 60);
+}
+
+/// 按 16 字节一块喂完 `text`，返回耗时与最后的部分结果
+fn stream_in_chunks(text: &str, opts: &RepairOptions) -> (Duration, Option<Json>) {
+    let mut buffer = StreamBuffer::default();
+    let started = Instant::now();
+    for chunk in text.as_bytes().chunks(16) {
+        buffer.feed(std::str::from_utf8(chunk).unwrap());
+        buffer.partial(opts);
+    }
+    (started.elapsed(), buffer.partial(opts).cloned())
+}
+
+#[test]
+fn test_stream_buffer_scales_with_chunk_count() {
+    let rows = |n: usize| {
+        let items: Vec<String> = (0..n)
+            .map(|i| format!(r#"{{"id": {i}, "name": "row, {i}", "tags": ["a", [1, 2]]}}"#))
+            .collect();
+        format!(r#"{{"items": [{}], "total": {n}}}"#, items.join(", "))
+    };
+    let opts = RepairOptions::default();
+    let (small, _) = stream_in_chunks(&rows(500), &opts);
+    let text = rows(4_000);
+    let (large, value) = stream_in_chunks(&text, &opts);
+    assert_eq!(value, Some(repair_to_value(&text, &opts).unwrap().value));
+    // 每块都从头重扫时 8 倍的文本要慢 64 倍；续读下与块数成正比，留足余量
+    assert!(
+        large < small * 24 + Duration::from_millis(200),
+        "{small:?} for 500 rows, {large:?} for 4000 rows"
+    );
+}
+
+#[test]
+fn test_was_complete() {
+    let opts = RepairOptions::default();
+    for (input, complete) in [
+        (r#"{"a": [1, 2]}"#, true),
+        // 尾逗号、注释、前后缀都不算截断
+        ("Result: {'a': [1, 2,],} // done", true),
+        (r#"{"a": [1, 2"#, false),
+        (r#"{"a": "unfinished"#, false),
+        (r#"["x", {"b": 1}"#, false),
+    ] {
+        let repaired = repair_to_value(input, &opts).unwrap();
+        assert_eq!(repaired.was_complete(), complete, "{input:?}");
+    }
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let (value, complete) = repair_json_status(py, "[1, [2", &opts).unwrap();
+        assert!(value
+            .as_ref(py)
+            .eq(py.eval("[1, [2]]", None, None).unwrap())
+            .unwrap());
+        assert!(!complete);
+    })
+}
+
+#[test]
+fn test_sort_keys() {
+    let opts = RepairOptions {
+        sort_keys: true,
+        ..Default::default()
+    };
+    let input = r#"{"b": 1, "a": {"z": 1, "é": 2, "Z": 3}, "c": [{"y": 1, "x": 2}], "a": 0}"#;
+    let repaired = repair_to_value(input, &opts).unwrap();
+    // 重复的 "a" 保持先后，序列化/物化时后者生效
+    assert_eq!(
+        repaired.value.to_json_string(None, false),
+        r#"{"a": {"Z": 3, "z": 1, "é": 2}, "a": 0, "b": 1, "c": [{"x": 2, "y": 1}]}"#
+    );
+    assert_eq!(
+        repaired.value.to_minified_string(false),
+        r#"{"a":{"Z":3,"z":1,"é":2},"a":0,"b":1,"c":[{"x":2,"y":1}]}"#
+    );
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let value = repair_json(py, input, &opts).unwrap();
+        let dict: &pyo3::types::PyDict = value.downcast(py).unwrap();
+        let keys: Vec<String> = dict.keys().extract().unwrap();
+        assert_eq!(keys, ["a", "b", "c"]);
+        assert_repairs(
+            py,
+            &opts,
+            input,
+            "{'a': 0, 'b': 1, 'c': [{'x': 2, 'y': 1}]}",
+        );
+    })
+}
+
+#[test]
+fn test_lenient_brackets() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            lenient_brackets: true,
+            ..Default::default()
+        };
+        // 写错的括号：直接当作本层的闭括号
+        assert_repairs(py, &opts, "[1, 2}", "[1, 2]");
+        assert_repairs(py, &opts, r#"{"a": 1]"#, "{'a': 1}");
+        assert_repairs(py, &opts, "[1, 2,}", "[1, 2]");
+        // 漏掉的闭括号：外层正等着这个括号，留给外层
+        assert_repairs(py, &opts, r#"{"a": [1, 2}"#, "{'a': [1, 2]}");
+        assert_repairs(py, &opts, r#"[{"a": 1]"#, "[{'a': 1}]");
+        assert_eq!(
+            kinds(&opts, r#"{"a": [1}"#),
+            [RepairKind::MismatchedBracket]
+        );
+        assert!(repair_to_value(r#"{"a": [1}"#, &opts)
+            .unwrap()
+            .was_complete());
+
+        let strict = RepairOptions::default();
+        assert_rejects(py, &strict, "[1, 2}");
+        assert_rejects(py, &strict, r#"{"a": [1, 2}"#);
+    })
+}
+
+#[test]
+fn test_normalize_unicode_digits() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            normalize_unicode_digits: true,
+            ..Default::default()
+        };
+        assert_repairs(py, &opts, "[１２３, －４．５, 1e＋１]", "[123, -4.5, 10.0]");
+        // 全角正号归一化后同样只在 json5_numbers 下允许
+        assert_rejects(py, &opts, "[＋7]");
+        let signed = RepairOptions {
+            json5_numbers: true,
+            ..opts.clone()
+        };
+        assert_repairs(py, &signed, "[＋7]", "[7]");
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"n": １２３４５６７８９０１２３４５６７８９０}"#,
+            "{'n': 12345678901234567890}",
+        );
+        // 字符串里的全角数字不受影响
+        assert_repairs(py, &opts, r#"["１２３"]"#, "['１２３']");
+        assert_eq!(
+            repair_to_value("[１２]", &opts).unwrap().repairs[0].detail,
+            "１２"
+        );
+        assert_rejects(py, &opts, "[．]");
+
+        let strict = RepairOptions::default();
+        assert_rejects(py, &strict, "[１２３]");
+    })
+}
+
+#[test]
+fn test_normalize_unicode_minus() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            normalize_unicode_minus: true,
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &opts,
+            "[−5, −2.5e−3, 1E−2, －7]",
+            "[-5, -0.0025, 0.01, -7]",
+        );
+        assert_eq!(
+            repair_to_value("{\"t\": −40}", &opts).unwrap().repairs[0].detail,
+            "−40"
+        );
+        // 只映射减号，全角数字仍需 normalize_unicode_digits；字符串不受影响
+        assert_rejects(py, &opts, "[−５]");
+        assert_repairs(py, &opts, "[\"−5\"]", "['−5']");
+        assert_rejects(py, &RepairOptions::default(), "[−5]");
+    })
+}
+
+#[test]
+fn test_coerce_whole_floats() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            coerce_whole_floats: true,
+            ..Default::default()
+        };
+        let value = repair_json(py, "[1.0, -2.00, 2e3, 1.5E1, -0.0, 1.5, 1e-1]", &opts).unwrap();
+        assert_eq!(
+            value.as_ref(py).repr().unwrap().to_string(),
+            "[1, -2, 2000, 15, 0, 1.5, 0.1]"
+        );
+        // 超出 i64 或非有限值仍是 float
+        let value = repair_json(py, "[1e19, -1e300, Infinity, NaN]", &opts).unwrap();
+        assert_eq!(
+            value.as_ref(py).repr().unwrap().to_string(),
+            "[1e+19, -1e+300, inf, nan]"
+        );
+        assert_eq!(
+            repair_to_json_str(py, "{\"n\": 3.0}", &opts, None).unwrap(),
+            "{\"n\": 3}"
+        );
+        assert!(repair_json(py, "[1.0]", &Default::default())
+            .unwrap()
+            .as_ref(py)
+            .get_item(0)
+            .unwrap()
+            .is_instance_of::<pyo3::types::PyFloat>());
+    })
+}
+
+#[test]
+fn test_extract_all_values() {
+    let opts = RepairOptions::default();
+    let message = "Here are two:\n```json\n{\"a\": 1}\n```\nand inline [1, 2] then\n\
+                   ```\n[{\"b\": [3]}]\n```\nplus {broken and a trailing {\"c\": true";
+    let repaired = extract_all_values(message, &opts).unwrap();
+    assert_eq!(
+        repaired.value.to_json_string(None, false),
+        r#"[{"a": 1}, [1, 2], [{"b": [3]}], {"c": true}]"#
+    );
+    // 已提取值内部的括号不会再被当作起点
+    let nested = extract_all_values("x {\"s\": \"[1]\", \"t\": {\"u\": 2}} y", &opts).unwrap();
+    assert_eq!(
+        nested.value.to_json_string(None, false),
+        r#"[{"s": "[1]", "t": {"u": 2}}]"#
+    );
+    let none = extract_all_values("no json {here} at all", &opts).unwrap();
+    assert_eq!(none.value, Json::Array(vec![]));
+}
+
+#[test]
+fn test_repair_last_value() {
+    let opts = RepairOptions::default();
+    let last =
+        |text: &str| repair_last_value(text, &opts).map(|r| r.value.to_minified_string(false));
+    let message = "First try: {\"a\": 1}\nCorrection:\n```json\n{\"a\": 2, \"b\": [3]}\n```\nDone.";
+    assert_eq!(last(message).unwrap(), r#"{"a":2,"b":[3]}"#);
+    // 最后一个值里的嵌套括号不是候选
+    assert_eq!(
+        last("[1] then {\"x\": {\"y\": 1}}").unwrap(),
+        r#"{"x":{"y":1}}"#
+    );
+    // 被截断的最后一版让位于之前完整的一版；全部截断时才用它
+    assert_eq!(
+        last("{\"a\": 1} and {\"a\": 2, \"b\"").unwrap(),
+        r#"{"a":1}"#
+    );
+    assert_eq!(last("so {\"a\": [1, 2").unwrap(), r#"{"a":[1,2]}"#);
+    assert!(last("no json {here} at all").is_err());
+}
+
+#[test]
+fn test_line_continuations() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            line_continuations: true,
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &opts,
+            "[\"line one \\\nline two\"]",
+            "['line one line two']",
+        );
+        assert_repairs(py, &opts, "['a\\\r\nb', \"c\\\rd\"]", "['ab', 'cd']");
+        // 只有紧跟换行的反斜杠才算续行
+        assert_repairs(py, &opts, "[\"a\\ \nb\"]", "['a\\\\ \\nb']");
+        assert_eq!(
+            kinds(&opts, "[\"x\\\r\ny\"]"),
+            [RepairKind::LineContinuation]
+        );
+        let (_, repairs) = repair_json_verbose(py, "\"x\\\r\ny\"", &opts).unwrap();
+        assert_eq!(
+            (repairs[0].offset, repairs[0].detail.as_str()),
+            (2, "\\\r\n")
+        );
+        // 默认保留为未知转义
+        assert_repairs(
+            py,
+            &RepairOptions::default(),
+            "[\"a\\\nb\"]",
+            "['a\\\\\\nb']",
+        );
+    })
+}
+
+#[test]
+fn test_intern_keys() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input = r#"[{"id": 1, "name": "a"}, {"id": 2, "name": "id"}]"#;
+        let first_keys = |opts: &RepairOptions| {
+            let value = repair_json(py, input, opts).unwrap();
+            let rows = value.as_ref(py);
+            let key = |row: usize| {
+                let dict: &pyo3::types::PyDict = rows.get_item(row).unwrap().downcast().unwrap();
+                dict.keys().get_item(0).unwrap().as_ptr()
+            };
+            (key(0), key(1))
+        };
+        let interned = RepairOptions {
+            intern_keys: true,
+            ..Default::default()
+        };
+        let (a, b) = first_keys(&interned);
+        assert_eq!(a, b);
+        let (a, b) = first_keys(&RepairOptions::default());
+        assert_ne!(a, b);
+        // 只驻留 key，字符串值照常构建
+        assert_repairs(
+            py,
+            &interned,
+            input,
+            "[{'id': 1, 'name': 'a'}, {'id': 2, 'name': 'id'}]",
+        );
+    })
+}
+
+#[test]
+fn test_js_literals() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            js_literals: true,
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &opts,
+            "{\"a\": undefined, \"b\": [Undefined, 1]}",
+            "{'a': None, 'b': [None, 1]}",
+        );
+        assert_eq!(kinds(&opts, "[undefined]"), [RepairKind::Literal]);
+        assert_rejects(py, &RepairOptions::default(), "[undefined]");
+        // 字面量之后紧跟标识符字符时整体不算字面量，不会只吃掉前半截
+        for bad in [
+            "[undefinedValue]",
+            "[trueValue]",
+            "[null_id]",
+            "[None$]",
+            "[infinite]",
+        ] {
+            assert_rejects(py, &opts, bad);
+        }
+        assert_repairs(py, &opts, "[undefined,true]", "[None, True]");
+    })
+}
+
+#[test]
+fn test_non_finite_literal_casing() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions::default();
+        let value = repair_to_value("[NaN, nan, NAN, Nan]", &opts)
+            .unwrap()
+            .value;
+        let Json::Array(items) = value else {
+            panic!("{value:?}")
+        };
+        assert!(items
+            .iter()
+            .all(|item| matches!(item, Json::Float(f) if f.is_nan())));
+        let value = repair_to_value("[Infinity, infinity, inf, Inf, INF]", &opts)
+            .unwrap()
+            .value;
+        assert_eq!(value, Json::Array(vec![Json::Float(f64::INFINITY); 5]));
+        // 字面量都不区分大小写，但仍要停在词边界上
+        assert_repairs(py, &opts, "[TRUE, NULL, none]", "[True, None, None]");
+        for bad in ["[nanny]", "[inferno]"] {
+            assert_rejects(py, &opts, bad);
+        }
+    })
+}
+
+#[test]
+fn test_extra_commas() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let ignore = RepairOptions::default();
+        assert_repairs(py, &ignore, "{\"a\": 1,,}", "{'a': 1}");
+        assert_repairs(py, &ignore, "{,}", "{}");
+        assert_repairs(py, &ignore, "[,1,,2]", "[1, 2]");
+
+        let strict = RepairOptions {
+            extra_commas: ExtraCommas::Error,
+            ..Default::default()
+        };
+        let err = repair_to_value("{\"a\": 1,,}", &strict).unwrap_err();
+        assert_eq!(err.offset, 8);
+        assert!(
+            err.message.ends_with("Unexpected extra ',')"),
+            "{}",
+            err.message
+        );
+        for bad in ["{,}", "{, \"a\": 1}", "[,1]", "[1,,2]", "[1, /* c */ ,]"] {
+            assert!(repair_to_value(bad, &strict).is_err(), "{bad:?}");
+        }
+        // 单个尾逗号仍然接受
+        assert_repairs(py, &strict, "{\"a\": [1, 2,],}", "{'a': [1, 2]}");
+    })
+}
+
+#[test]
+fn test_bad_unicode_policy() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let policy = |bad_unicode_policy| RepairOptions {
+            bad_unicode_policy,
+            ..Default::default()
+        };
+        let input = r#"["a\u12G4b", "\uD800", "ok\u00e9"]"#;
+        assert_repairs(
+            py,
+            &policy(BadUnicodePolicy::Preserve),
+            input,
+            r"['a\\u12G4b', '\\uD800', 'ok\u00e9']",
+        );
+        assert_repairs(
+            py,
+            &policy(BadUnicodePolicy::Replace),
+            input,
+            "['a\u{FFFD}b', '\u{FFFD}', 'ok\u{e9}']",
+        );
+        assert_eq!(
+            kinds(&policy(BadUnicodePolicy::Replace), r#""\u12""#),
+            [RepairKind::InvalidEscape, RepairKind::ClosedString]
+        );
+        let err = repair_to_value(r#""x\uZZZZ""#, &policy(BadUnicodePolicy::Error)).unwrap_err();
+        assert_eq!(err.offset, 2);
+        assert!(
+            err.message.contains(r#"Invalid unicode escape "\\uZZZZ""#),
+            "{}",
+            err.message
+        );
+        assert_repairs(
+            py,
+            &policy(BadUnicodePolicy::Error),
+            r#""\u00e9""#,
+            r"'\u00e9'",
+        );
+    })
+}
+
+#[test]
+fn test_lone_surrogates() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let policy = |bad_unicode_policy| RepairOptions {
+            bad_unicode_policy,
+            ..Default::default()
+        };
+        // 单独的高代理项、单独的低代理项、高代理项后跟非低代理项；后者照常解码
+        let input = r#"["a\uD83Db", "\uDE00x", "\uD83D\u0041", "\uD83D\uD83D\uDE00"]"#;
+        assert_repairs(
+            py,
+            &policy(BadUnicodePolicy::Preserve),
+            input,
+            r"['a\\uD83Db', '\\uDE00x', '\\uD83DA', '\\uD83D\U0001F600']",
+        );
+        assert_repairs(
+            py,
+            &policy(BadUnicodePolicy::Replace),
+            input,
+            "['a\u{FFFD}b', '\u{FFFD}x', '\u{FFFD}A', '\u{FFFD}\u{1F600}']",
+        );
+        assert_eq!(
+            kinds(&policy(BadUnicodePolicy::Replace), r#""\uDE00\uD83D""#),
+            [RepairKind::InvalidEscape, RepairKind::InvalidEscape]
+        );
+        for (input, offset) in [
+            (r#""a\uD83Db""#, 2),
+            (r#""\uDE00""#, 1),
+            (r#""\uD83D\u0041""#, 1),
+        ] {
+            let err = repair_to_value(input, &policy(BadUnicodePolicy::Error)).unwrap_err();
+            assert_eq!(err.offset, offset, "{input}");
+            assert!(
+                err.message.contains("Unpaired surrogate"),
+                "{}",
+                err.message
+            );
+        }
+        // 成对的代理项在 error 策略下也照常合成
+        assert_repairs(
+            py,
+            &policy(BadUnicodePolicy::Error),
+            r#""\uD83D\uDE00""#,
+            "'\u{1F600}'",
+        );
+    })
+}
+
+#[test]
+fn test_truncated_numbers() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions::default();
+    Python::with_gil(|py| {
+        assert_repairs(py, &opts, r#"{"score": 3."#, "{'score': 3.0}");
+        assert_repairs(py, &opts, "[1, 3e", "[1, 3]");
+        assert_repairs(py, &opts, "[2.5e-", "[2.5]");
+        assert_repairs(
+            py,
+            &opts,
+            "{\"n\": 12345678901234567890e+",
+            "{'n': 12345678901234567890}",
+        );
+    });
+    let repaired = repair_to_value(r#"{"score": 3."#, &opts).unwrap();
+    assert!(!repaired.was_complete());
+    assert_eq!(repaired.repairs[0].kind, RepairKind::TruncatedNumber);
+    assert_eq!(repaired.repairs[0].detail, "3.");
+    // 只有紧贴 EOF 的数字才算截断
+    assert!(repair_to_value("[3., 4]", &opts).is_err());
+    assert!(repair_to_value("[1, -", &opts).is_err());
+}
+
+#[test]
+fn test_raw_numbers() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions {
+        raw_numbers: true,
+        json5_numbers: true,
+        ..Default::default()
+    };
+    Python::with_gil(|py| {
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"score": 0.300, "n": -12, "big": 123456789012345678901234, "h": 0x1F, "e": 1E+05}"#,
+            "{'score': '0.300', 'n': '-12', 'big': '123456789012345678901234', 'h': '0x1F', 'e': '1E+05'}",
+        );
+        // 字面量与字符串不受影响，截断的数字保留写出的部分
+        assert_repairs(
+            py,
+            &opts,
+            r#"[Infinity, "1.50", true, 2."#,
+            "[float('inf'), '1.50', True, '2.']",
+        );
+    });
+    assert!(repair_to_value("[1.2.3]", &opts).is_err());
+}
+
+#[test]
+fn test_strip_markdown_emphasis() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions {
+        strip_markdown_emphasis: true,
+        ..Default::default()
+    };
+    Python::with_gil(|py| {
+        assert_repairs(
+            py,
+            &opts,
+            r#"{**"name"**: "x", *"age"*: 3, __'city'__: "Oslo"}"#,
+            "{'name': 'x', 'age': 3, 'city': 'Oslo'}",
+        );
+        // 只有一侧有强调符也跳过；值里的 `*` 不受影响
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"a"**: "**bold**", **"b": 1}"#,
+            "{'a': '**bold**', 'b': 1}",
+        );
+        // 不紧跟引号的前导 `_` 属于裸 key，不会被吞掉
+        let wrapped = RepairOptions {
+            assume_object: true,
+            ..opts.clone()
+        };
+        assert_repairs(py, &wrapped, "_id: 1", "{'_id': 1}");
+        assert_rejects(py, &RepairOptions::default(), r#"{**"name"**: "x"}"#);
+    });
+    let repaired = repair_to_value(r#"{**"a"**: 1}"#, &opts).unwrap();
+    assert_eq!(
+        repaired
+            .repairs
+            .iter()
+            .map(|r| (r.offset, r.kind, r.detail.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (1, RepairKind::StrippedEmphasis, "**"),
+            (6, RepairKind::StrippedEmphasis, "**"),
+        ]
+    );
+}
+
+#[test]
+fn test_lenient_separators() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions {
+        lenient_separators: true,
+        ..Default::default()
+    };
+    Python::with_gil(|py| {
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"a" => 1, "b" = 2, "c": 3}"#,
+            "{'a': 1, 'b': 2, 'c': 3}",
+        );
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"op" => "=", "eq" = "a=>b"}"#,
+            "{'op': '=', 'eq': 'a=>b'}",
+        );
+        let wrapped = RepairOptions {
+            assume_object: true,
+            ..opts.clone()
+        };
+        assert_repairs(py, &wrapped, "a = 1, b => [2]", "{'a': 1, 'b': [2]}");
+        // 值位置上的 `=` 与 `==` 分隔符都不接受
+        assert_rejects(py, &opts, r#"{"a": = 1}"#);
+        assert_rejects(py, &opts, r#"{"a" == 1}"#);
+        assert_rejects(py, &RepairOptions::default(), r#"{"a" => 1}"#);
+    });
+    let repaired = repair_to_value(r#"{"a" => 1, "b" = 2}"#, &opts).unwrap();
+    assert_eq!(
+        repaired
+            .repairs
+            .iter()
+            .map(|r| (r.offset, r.kind, r.detail.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (5, RepairKind::KeySeparator, "=>"),
+            (15, RepairKind::KeySeparator, "="),
+        ]
+    );
+}
+
+#[test]
+fn test_skip_leading_garbage() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions {
+        skip_leading_garbage: true,
+        ..Default::default()
+    };
+    Python::with_gil(|py| {
+        assert_repairs(py, &opts, r#"}{"a":1}"#, "{'a': 1}");
+        assert_repairs(py, &opts, "] , }\n42", "42");
+        assert_repairs(py, &opts, r#"}, "tail""#, "'tail'");
+        // 只在顶层跳过，容器内部的多余闭括号不受影响
+        assert_repairs(py, &opts, "[1, 2]]", "[1, 2]");
+        assert_rejects(py, &RepairOptions::default(), "] 42");
+    });
+    let repaired = repair_to_value("} ]\n[1]", &opts).unwrap();
+    assert_eq!(
+        repaired
+            .repairs
+            .iter()
+            .map(|r| (r.offset, r.kind, r.detail.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (0, RepairKind::SkippedGarbage, "}"),
+            (2, RepairKind::SkippedGarbage, "]"),
+        ]
+    );
+    // 首尾相接的多个值之间的残留也一并跳过
+    let values = repair_to_values(r#"{"a":1}}{"b":2}"#, &opts).unwrap();
+    assert_eq!(
+        values.value,
+        Json::Array(vec![
+            Json::Object(vec![("a".into(), Json::Int(1))]),
+            Json::Object(vec![("b".into(), Json::Int(2))]),
+        ])
+    );
+}
+
+#[test]
+fn test_strip_html() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions {
+        strip_html: true,
+        ..Default::default()
+    };
+    Python::with_gil(|py| {
+        assert_repairs(py, &opts, r#"<pre>{"a": 1}</pre>"#, "{'a': 1}");
+        assert_repairs(
+            py,
+            &opts,
+            "<output lang=\"json\">\n<code>[1, \"<b>kept</b>\"]</code>\n</output>",
+            "[1, '<b>kept</b>']",
+        );
+        assert_repairs(py, &opts, r#"{"a": <br/> 2}"#, "{'a': 2}");
+    });
+    let repaired = repair_to_value(r#"<pre>{"a": 1}</pre>"#, &opts).unwrap();
+    assert_eq!(
+        repaired
+            .repairs
+            .iter()
+            .map(|r| (r.offset, r.kind, r.detail.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (0, RepairKind::SkippedHtmlTag, "<pre>"),
+            (13, RepairKind::SkippedHtmlTag, "</pre>"),
+        ]
+    );
+    // 不是标签的 `<` 照常报错；未开启时标签也不会被跳过
+    assert!(repair_to_value("< 3", &opts).is_err());
+    assert!(
+        repair_to_value(r#"<pre>{"a": 1}"#, &RepairOptions::default())
+            .unwrap()
+            .repairs
+            .iter()
+            .any(|r| r.kind == RepairKind::SkippedPrefix)
+    );
+}
+
+#[test]
+fn test_escaped_keys() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions::default();
+        assert_repairs(py, &opts, r#"{"a\u0062c": 1}"#, "{'abc': 1}");
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"line\nbreak": 1, "tab\there": 2, "q\"k": 3, "s\/l": 4, 'it\'s': 5}"#,
+            r#"{'line\nbreak': 1, 'tab\there': 2, 'q"k': 3, 's/l': 4, "it's": 5}"#,
+        );
+        assert_repairs(py, &opts, r#"{"\ud83d\ude00": 1}"#, "{'\\U0001F600': 1}");
+        // 转义写法与直接写法是同一个 key，后者覆盖前者
+        assert_repairs(py, &opts, r#"{"abc": 1, "a\u0062c": 2}"#, "{'abc': 2}");
+        let interned = RepairOptions {
+            intern_keys: true,
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &interned,
+            r#"[{"ab": 1}, {"\u0061b": 2}]"#,
+            "[{'ab': 1}, {'ab': 2}]",
+        );
+    });
+
+    // key 与字符串值走同一条解码路径
+    for literal in [
+        r#""a\u0062c""#,
+        r#""x\n\t\r\b\f""#,
+        r#""q\"\\\/""#,
+        r#""\ud83d\ude00""#,
+        r#""\q kept""#,
+        r#"'single \' quote'"#,
+    ] {
+        let repaired = repair_to_value(
+            &format!("{{{literal}: {literal}}}"),
+            &RepairOptions::default(),
+        )
+        .unwrap_or_else(|e| panic!("{literal}: {e}"));
+        let Json::Object(members) = repaired.value else {
+            panic!("{literal}: not an object");
+        };
+        assert_eq!(Json::Str(members[0].0.clone()), members[0].1, "{literal}");
+    }
+
+    // sort_keys 按解码后的 key 排序
+    let sorted = RepairOptions {
+        sort_keys: true,
+        ..Default::default()
+    };
+    let repaired = repair_to_value(r#"{"\u0062": 1, "a": 2}"#, &sorted).unwrap();
+    assert_eq!(repaired.value.to_minified_string(false), r#"{"a":2,"b":1}"#);
+}
+
+#[test]
+fn test_object_pairs_hook() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = RepairOptions {
+            object_pairs_hook: Some(py.eval("lambda pairs: pairs", None, None).unwrap().into()),
+            ..Default::default()
+        };
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"b": 1, "a": {"x": null}, "b": [2]}"#,
+            "[('b', 1), ('a', [('x', None)]), ('b', [2])]",
+        );
+        assert_repairs(py, &opts, "[{}]", "[[]]");
+
+        let failing = RepairOptions {
+            object_pairs_hook: Some(py.eval("lambda pairs: 1 / 0", None, None).unwrap().into()),
+            ..Default::default()
+        };
+        let err = repair_json(py, "{\"a\": 1}", &failing).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyZeroDivisionError>(py));
+        let err = repair_to_json_str(py, "{}", &opts, None).unwrap_err();
+        assert!(err.to_string().contains("object_pairs_hook"));
+    })
+}
+
+#[test]
+fn test_parse_into() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let classes = pyo3::types::PyDict::new(py);
+        py.run(
+            "import dataclasses
+@dataclasses.dataclass
+class Point:
+    x: int
+    y: int = 0
+class Model:
+    @classmethod
+    def model_validate(cls, value):
+        return ('validated', value)",
+            None,
+            Some(classes),
+        )
+        .unwrap();
+        let class = |name| classes.get_item(name).unwrap().unwrap();
+        let opts = RepairOptions::default();
+
+        let point = parse_into(py, "{'x': 1, 'y': 2,}", class("Point"), &opts).unwrap();
+        let point = point.as_ref(py);
+        assert_eq!(point.getattr("x").unwrap().extract::<i64>().unwrap(), 1);
+        assert_eq!(point.getattr("y").unwrap().extract::<i64>().unwrap(), 2);
+
+        // 有 model_validate 时交给它，顶层不必是对象
+        let got = parse_into(py, "[1, 2", class("Model"), &opts).unwrap();
+        let want = py.eval("('validated', [1, 2])", None, None).unwrap();
+        assert!(got.as_ref(py).eq(want).unwrap());
+
+        let err = parse_into(py, "[1]", class("Point"), &opts).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+        assert!(
+            err.to_string().contains("to build Point, got list"),
+            "{err}"
+        );
+        // 构造函数的异常原样上抛
+        let err = parse_into(py, r#"{"z": 1}"#, class("Point"), &opts).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+        assert!(err.to_string().contains("'z'"), "{err}");
+    })
+}
+
+#[test]
+fn test_repair_report() {
+    let opts = RepairOptions::default();
+    assert_eq!(
+        RepairReport::scan(r#"{"a": [1, {"b": null}], "c": "x"}"#, &opts),
+        RepairReport {
+            valid: true,
+            truncated: false,
+            depth: 3,
+            value_count: 6,
+        }
+    );
+    let truncated = RepairReport::scan(r#"[{"a": 1"#, &opts);
+    assert!(truncated.valid && truncated.truncated);
+    assert_eq!((truncated.depth, truncated.value_count), (2, 3));
+    assert_eq!(RepairReport::scan("42", &opts).depth, 0);
+    assert_eq!(RepairReport::scan("", &opts), RepairReport::default());
+}
+
+#[test]
+fn test_skip_zero_width() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions {
+        skip_zero_width: true,
+        ..Default::default()
+    };
+    let input = "\u{FEFF}{\u{200B}\"a\"\u{200C}:\u{2060} [1,\u{200D} 2]}";
+    Python::with_gil(|py| {
+        assert_repairs(py, &opts, input, "{'a': [1, 2]}");
+        // 字符串内部的零宽字符原样保留
+        assert_repairs(py, &opts, "[\"a\u{200B}b\"]", "['a\u{200B}b']");
+    });
+    assert!(repair_to_value(input, &RepairOptions::default()).is_err());
+}
+
+#[test]
+fn test_auto_unstringify() {
+    pyo3::prepare_freethreaded_python();
+    let opts = RepairOptions {
+        auto_unstringify: true,
+        ..Default::default()
+    };
+    Python::with_gil(|py| {
+        assert_repairs(
+            py,
+            &opts,
+            r#"{"data": "{\"a\": 1, \"b\": \"[1, 2]\"}", "list": " [true] "}"#,
+            "{'data': {'a': 1, 'b': [1, 2]}, 'list': [True]}",
+        );
+        // 不是完整 JSON 的字符串保持原样
+        assert_repairs(
+            py,
+            &opts,
+            r#"["[citation needed]", "{not json", "see {x}", "[1] and [2]"]"#,
+            "['[citation needed]', '{not json', 'see {x}', '[1] and [2]']",
+        );
+        assert_repairs(
+            py,
+            &RepairOptions::default(),
+            r#"{"data": "[1]"}"#,
+            "{'data': '[1]'}",
+        );
+    });
+    let repaired = repair_to_value(r#"{"data": "[1]"}"#, &opts).unwrap();
+    assert_eq!(repaired.repairs[0].kind, RepairKind::Unstringified);
+    assert_eq!(
+        (
+            repaired.repairs[0].offset,
+            repaired.repairs[0].detail.as_str()
+        ),
+        (9, "array")
+    );
+}
+
+/// 把事件记成文本的 sink，用来检查解析器发出的事件序列
+#[derive(Default)]
+struct EventLog(Vec<String>);
+
+impl ValueSink for EventLog {
+    type Output = String;
+
+    fn begin_object(&mut self) {
+        self.0.push("{".into());
+    }
+    fn key(&mut self, key: String) {
+        self.0.push(format!("{key}:"));
+    }
+    fn end_object(&mut self) {
+        self.0.push("}".into());
+    }
+    fn begin_array(&mut self) {
+        self.0.push("[".into());
+    }
+    fn end_array(&mut self) {
+        self.0.push("]".into());
+    }
+    fn scalar(&mut self, value: Json) {
+        self.0.push(format!("{value:?}"));
+    }
+    fn finish(self) -> String {
+        self.0.join(" ")
+    }
+}
+
+#[test]
+fn test_value_sink_events() {
+    let events =
+        |opts: &RepairOptions, input: &str| repair_into(input, opts, EventLog::default).unwrap().0;
+    let opts = RepairOptions::default();
+    assert_eq!(
+        events(&opts, r#"{"a": [1, "x"], "b": {"c": null}}"#),
+        r#"{ a: [ Int(1) Str("x") ] b: { c: Null } }"#
+    );
+    // 补全的容器与缺失的值同样以良构事件给出
+    let lenient = RepairOptions {
+        lenient_missing_values: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        events(&lenient, r#"{"a": , "b": [true"#),
+        "{ a: Null b: [ Bool(true) ] }"
+    );
+    let sorted = RepairOptions {
+        sort_keys: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        events(&sorted, r#"{"b": 1, "a": {"d": 2, "c": 3}}"#),
+        "{ a: { c: Int(3) d: Int(2) } b: Int(1) }"
+    );
+
+    // 失败的起点拿到的 sink 被丢弃，只有成功起点的 sink 被 finish
+    let mut created = 0;
+    let (log, repairs) = repair_into("see {oops [1] and [2]", &opts, || {
+        created += 1;
+        EventLog::default()
+    })
+    .unwrap();
+    assert_eq!(log, "[ Int(1) ]");
+    assert!(created > 1);
+    assert_eq!(repairs[0].kind, RepairKind::SkippedPrefix);
+}
+
+#[test]
+fn test_py_builder_matches_materialized_value() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let hook = py.eval("lambda v, tag: f'{tag}:{v}'", None, None).unwrap();
+        let pairs_hook = py.eval("lambda pairs: tuple(pairs)", None, None).unwrap();
+        let opts = RepairOptions {
+            value_hook: Some(hook.into()),
+            object_pairs_hook: Some(pairs_hook.into()),
+            intern_keys: true,
+            ..Default::default()
+        };
+        let input =
+            r#"noise {"a": [1, 2.5, "s"], "b": {"a": null}, "big": 123456789012345678901234}"#;
+        let (direct, _) = repair_into(input, &opts, || {
+            PyBuilder::new(py, Some(hook), Some(pairs_hook), true)
+        })
+        .unwrap();
+        let direct = direct.unwrap();
+        let materialized = repair_json(py, input, &opts).unwrap();
+        assert!(
+            direct.as_ref(py).eq(materialized.as_ref(py)).unwrap(),
+            "{}",
+            direct.as_ref(py).repr().unwrap()
+        );
+
+        // hook 抛出的异常由 finish 返回
+        let failing = py.eval("lambda v, tag: 1 / 0", None, None).unwrap();
+        let (result, _) = repair_into("[1, 2]", &opts, || {
+            PyBuilder::new(py, Some(failing), None, false)
+        })
+        .unwrap();
+        assert!(result
+            .unwrap_err()
+            .is_instance_of::<pyo3::exceptions::PyZeroDivisionError>(py));
+    })
+}
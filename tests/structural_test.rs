@@ -0,0 +1,1198 @@
+use llm_json_utils::repair::RepairKind;
+use llm_json_utils::structural::options::{DropMode, ExtractOptions, KeyNormalization};
+use llm_json_utils::structural::parser::{ParseError, PathSegment};
+use llm_json_utils::structural::schema::SchemaNode;
+use llm_json_utils::structural::{compiler, parser, validate};
+use llm_json_utils::utils::cursor::Cursor;
+use pyo3::prelude::*;
+
+/// 用 Python 字面量 `schema` 编译 Schema，按 `opts` 从 `input` 开头解析
+fn extract_with(
+    py: Python,
+    opts: &ExtractOptions,
+    schema: &str,
+    input: &str,
+) -> Result<PyObject, ParseError> {
+    let schema = compiler::compile(py.eval(schema, None, None).unwrap()).unwrap();
+    let mut cursor = Cursor::new(input.as_bytes());
+    parser::parse_node(&mut cursor, &schema, opts, py, 0)
+}
+
+fn extract(py: Python, schema: &str, input: &str) -> Result<PyObject, ParseError> {
+    extract_with(py, &ExtractOptions::default(), schema, input)
+}
+
+fn assert_extracts(py: Python, schema: &str, input: &str, expected: &str) {
+    let got = extract(py, schema, input)
+        .unwrap_or_else(|e| panic!("extraction failed for {input:?}: {e}"));
+    let want = py.eval(expected, None, None).unwrap();
+    assert!(
+        got.as_ref(py).eq(want).unwrap(),
+        "{input:?}: got {}, want {expected}",
+        got.as_ref(py).repr().unwrap()
+    );
+}
+
+fn assert_fails(py: Python, schema: &str, input: &str) {
+    if let Ok(got) = extract(py, schema, input) {
+        panic!(
+            "expected failure for {input:?}, got {}",
+            got.as_ref(py).repr().unwrap()
+        );
+    }
+}
+
+const FLAGS: &str =
+    "{'type': 'object', 'properties': {'ok': {'type': 'boolean'}, 'gone': {'type': 'null'}}}";
+
+#[test]
+fn test_boolean_leaf() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(py, FLAGS, r#"{"ok": true}"#, "{'ok': True}");
+        assert_extracts(py, FLAGS, r#"{"ok": False}"#, "{'ok': False}");
+        assert_extracts(py, FLAGS, r#"{"ok": "true"}"#, "{'ok': True}");
+        assert_extracts(py, FLAGS, r#"{'ok': 'false'}"#, "{'ok': False}");
+        assert_fails(py, FLAGS, r#"{"ok": "maybe"}"#);
+        assert_fails(py, FLAGS, r#"{"ok": 1}"#);
+        // 字面量之后紧跟标识符字符时不算布尔值
+        assert_fails(py, FLAGS, r#"{"ok": truex}"#);
+        assert_fails(py, FLAGS, r#"{"ok": trueish}"#);
+        assert_fails(py, FLAGS, r#"{"ok": False_}"#);
+        assert_extracts(py, FLAGS, r#"{"ok":true}"#, "{'ok': True}");
+    })
+}
+
+#[test]
+fn test_null_leaf() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(py, FLAGS, r#"{"gone": null}"#, "{'gone': None}");
+        assert_extracts(
+            py,
+            FLAGS,
+            r#"{"gone": None, "ok": true}"#,
+            "{'gone': None, 'ok': True}",
+        );
+        assert_fails(py, FLAGS, r#"{"gone": 0}"#);
+        assert_fails(py, FLAGS, r#"{"gone": "null"}"#);
+        assert_fails(py, FLAGS, r#"{"gone": nullx}"#);
+        assert_fails(py, FLAGS, r#"{"gone": Nones}"#);
+    })
+}
+
+const NUM_OR_LIST: &str = "{'type': 'object', 'properties': {'v': {'anyOf': [
+    {'type': 'array', 'items': {'type': 'number'}},
+    {'type': 'number'},
+    {'type': 'null'}]}}}";
+
+#[test]
+fn test_any_of_takes_first_match() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(py, NUM_OR_LIST, r#"{"v": [1, 2]}"#, "{'v': [1.0, 2.0]}");
+        assert_extracts(py, NUM_OR_LIST, r#"{"v": 3}"#, "{'v': 3.0}");
+        assert_extracts(py, NUM_OR_LIST, r#"{"v": null}"#, "{'v': None}");
+        assert_fails(py, NUM_OR_LIST, r#"{"v": true}"#);
+        // 失败的分支不会移动游标，后续字段照常解析
+        let schema = "{'type': 'object', 'properties': {
+            'v': {'anyOf': [{'type': 'boolean'}, {'type': 'string'}]},
+            'n': {'type': 'number'}}}";
+        assert_extracts(
+            py,
+            schema,
+            r#"{"v": "text", "n": 1}"#,
+            "{'v': 'text', 'n': 1.0}",
+        );
+    })
+}
+
+#[test]
+fn test_type_list() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let nullable = "{'type': 'object', 'properties': {'name': {'type': ['string', 'null']}}}";
+        assert_extracts(py, nullable, r#"{"name": "Ann"}"#, "{'name': 'Ann'}");
+        // 无论声明顺序如何，null 都先于宽松的字符串叶子尝试
+        assert_extracts(py, nullable, r#"{"name": null}"#, "{'name': None}");
+        assert_extracts(py, "{'type': ['integer', 'number']}", "3", "3");
+        assert_extracts(py, "{'type': ['number', 'integer']}", "3.5", "3.5");
+        let list_or_null = "{'type': ['array', 'null'], 'items': {'type': 'integer'}}";
+        assert_extracts(py, list_or_null, "[1, 2]", "[1, 2]");
+        assert_extracts(py, list_or_null, "null", "None");
+        assert_fails(py, "{'type': ['boolean', 'null']}", "7");
+        assert_extracts(py, "{'type': ['boolean']}", "true", "True");
+
+        for bad in ["{'type': []}", "{'type': ['string', 3]}"] {
+            assert!(compiler::compile(py.eval(bad, None, None).unwrap()).is_err());
+        }
+    })
+}
+
+const ALL_OF: &str = "{'$defs': {'base': {'type': 'object', 'required': ['id'],
+        'properties': {'id': {'type': 'integer'}, 'n': {'type': 'number'}}}},
+    'allOf': [{'$ref': '#/$defs/base'},
+        {'properties': {'name': {'type': 'string'}, 'n': {'type': 'integer'}}, 'required': ['name']}]}";
+
+#[test]
+fn test_all_of() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(
+            py,
+            ALL_OF,
+            r#"{"id": 1, "name": "a", "n": 2}"#,
+            "{'id': 1, 'name': 'a', 'n': 2}",
+        );
+        assert_fails(py, ALL_OF, r#"{"id": 1}"#);
+        assert_fails(py, ALL_OF, r#"{"name": "a"}"#);
+        let bounded = "{'allOf': [{'type': 'array', 'items': {'type': 'integer'}, 'maxItems': 3},
+            {'maxItems': 2}, {'type': ['array', 'null']}]}";
+        assert_extracts(py, bounded, "[1, 2]", "[1, 2]");
+        assert_fails(py, bounded, "[1, 2, 3]");
+
+        let compile_err = |schema: &str| {
+            compiler::compile(py.eval(schema, None, None).unwrap())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            compile_err("{'allOf': [{'type': 'string'}, {'type': ['object', 'null']}]}"),
+            r#"ValueError: Conflicting types in allOf: ["string"] and ["object", "null"]"#
+        );
+        assert!(compile_err(
+            "{'$defs': {'a': {'allOf': [{'$ref': '#/$defs/a'}]}}, '$ref': '#/$defs/a'}"
+        )
+        .contains("Recursive $ref"));
+        assert!(compile_err("{'allOf': [{'enum': ['a']}, {'enum': ['b']}]}").contains("enum"));
+    })
+}
+
+const LABELS: &str = "{'type': 'object', 'propertyNames': {'pattern': '^[a-z_]+$'},
+    'additionalProperties': {'type': 'integer'}}";
+
+#[test]
+fn test_property_names() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(
+            py,
+            LABELS,
+            r#"{"first_try": 1, 'second': 2, bare: 3,}"#,
+            "{'first_try': 1, 'second': 2, 'bare': 3}",
+        );
+        assert_extracts(py, LABELS, "{}", "{}");
+        let err = extract(py, LABELS, r#"{"ok": 1, "Bad-Key": 2}"#).unwrap_err();
+        assert!(matches!(err.cause(), ParseError::InvalidPropertyName(k) if k == "Bad-Key"));
+        assert_eq!(
+            err.to_string(),
+            r#"validation failed at data["Bad-Key"]: Property name "Bad-Key" does not match propertyNames"#
+        );
+        // 值仍按 additionalProperties 校验
+        assert_fails(py, LABELS, r#"{"ok": "x"}"#);
+
+        // 没有 additionalProperties 时值可以是任意 JSON
+        let names = "{'type': 'object', 'propertyNames': {'enum': ['a', 'b'], 'maxLength': 1}}";
+        assert_extracts(
+            py,
+            names,
+            r#"{"a": [1, 2.5, null, {"n": true}, "x"], "b": -3}"#,
+            "{'a': [1, 2.5, None, {'n': True}, 'x'], 'b': -3}",
+        );
+        assert_fails(py, names, r#"{"c": 1}"#);
+
+        // 有 properties 时只会输出声明的字段，在编译期检查
+        let declared = "{'type': 'object', 'propertyNames': {'minLength': 2},
+            'properties': {'x': {'type': 'string'}}}";
+        assert!(compiler::compile(py.eval(declared, None, None).unwrap()).is_err());
+        let bad_pattern = "{'type': 'object', 'propertyNames': {'pattern': '[a-z'}}";
+        assert!(compiler::compile(py.eval(bad_pattern, None, None).unwrap()).is_err());
+    })
+}
+
+#[test]
+fn test_one_of_requires_exactly_one_match() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let schema = "{'oneOf': [{'type': 'boolean'}, {'type': 'null'}]}";
+        assert_extracts(py, schema, "true", "True");
+        assert_extracts(py, schema, "None", "None");
+        assert!(matches!(
+            extract(py, schema, "7"),
+            Err(ParseError::NoAlternativeMatched)
+        ));
+        // 宽松的字符串叶子能接住 true，与布尔分支冲突
+        let ambiguous = "{'oneOf': [{'type': 'boolean'}, {'type': 'string'}]}";
+        assert!(matches!(
+            extract(py, ambiguous, "true"),
+            Err(ParseError::AmbiguousAlternatives)
+        ));
+    })
+}
+
+#[test]
+fn test_errors_carry_location() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let schema = compiler::compile(py.eval(FLAGS, None, None).unwrap()).unwrap();
+        let input = "{\n  \"gone\": null,\n  \"ok\": maybe\n}";
+        let mut cursor = Cursor::new(input.as_bytes());
+        let err = parser::parse_root(&mut cursor, &schema, &Default::default(), py).unwrap_err();
+        assert!(matches!(err.error, ParseError::TypeMismatch("boolean")));
+        assert_eq!((err.offset, err.line, err.column), (26, 3, 9));
+        assert_eq!(
+            err.to_string(),
+            "validation failed at data.ok: Expected boolean at line 3, column 9 (offset 26)"
+        );
+    })
+}
+
+#[test]
+fn test_errors_carry_path() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let schema = "{'type': 'object', 'properties': {
+            'rows': {'type': 'array', 'items': {'type': 'object', 'properties': {
+                'id': {'type': 'integer'}, 'full name': {'type': 'string'}}}}}}";
+        let schema = compiler::compile(py.eval(schema, None, None).unwrap()).unwrap();
+        let input = r#"{"rows": [{"id": 1}, {"id": 2.5}]}"#;
+        let mut cursor = Cursor::new(input.as_bytes());
+        let err = parser::parse_root(&mut cursor, &schema, &Default::default(), py).unwrap_err();
+        assert!(matches!(err.error, ParseError::NotAnInteger(_)));
+        assert_eq!(
+            err.path,
+            [
+                PathSegment::Key("rows".into()),
+                PathSegment::Index(1),
+                PathSegment::Key("id".into())
+            ]
+        );
+        assert_eq!(
+            err.to_string(),
+            "validation failed at data.rows[1].id: Expected integer, got 2.5 \
+             at line 1, column 32 (offset 31)"
+        );
+        assert_eq!(
+            parser::format_path(&[PathSegment::Key("full name".into()), PathSegment::Index(0)]),
+            r#"data["full name"][0]"#
+        );
+    })
+}
+
+const PROFILE: &str = "{'type': 'object', 'properties': {'profile': {'type': 'object',
+    'properties': {'a': {'type': 'integer'}, 'b': {'type': 'integer'}, 'c': {'type': 'integer'}},
+    'minProperties': 1, 'maxProperties': 2}}}";
+
+#[test]
+fn test_property_bounds() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(
+            py,
+            PROFILE,
+            r#"{"profile": {"a": 1, "b": 2}}"#,
+            "{'profile': {'a': 1, 'b': 2}}",
+        );
+        let err = extract(py, PROFILE, r#"{"profile": {}}"#).unwrap_err();
+        assert!(matches!(err.cause(), ParseError::TooFewProperties(1, 0)));
+        assert_eq!(
+            err.to_string(),
+            "validation failed at data.profile: Expected at least 1 properties, found 0"
+        );
+        let err = extract(py, PROFILE, r#"{"profile": {"a": 1, "b": 2, "c": 3}}"#).unwrap_err();
+        assert!(matches!(err.cause(), ParseError::TooManyProperties(2, 3)));
+
+        // 动态对象同样生效，重复 key 只算一次
+        let labels =
+            "{'type': 'object', 'propertyNames': {'pattern': '^[a-z]+$'}, 'maxProperties': 1}";
+        assert_extracts(py, labels, r#"{"a": 1, "a": 2}"#, "{'a': 2}");
+        assert!(matches!(
+            extract(py, labels, r#"{"a": 1, "b": 2}"#),
+            Err(ParseError::TooManyProperties(1, 2))
+        ));
+    })
+}
+
+const TAGS: &str = "{'type': 'array', 'items': {'type': 'string'},
+    'minItems': 1, 'maxItems': 3, 'uniqueItems': True}";
+
+#[test]
+fn test_array_bounds() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(py, TAGS, r#"["a", "b"]"#, "['a', 'b']");
+        assert_extracts(py, TAGS, r#"["a", "b", "c"]"#, "['a', 'b', 'c']");
+        assert!(matches!(
+            extract(py, TAGS, "[]"),
+            Err(ParseError::TooFewItems(1, 0))
+        ));
+        assert!(matches!(
+            extract(py, TAGS, r#"["a", "b", "c", "d"]"#),
+            Err(ParseError::TooManyItems(3, 4))
+        ));
+        let err = extract(py, TAGS, r#"["a", "b", "a"]"#).unwrap_err();
+        assert!(matches!(err, ParseError::DuplicateItems(0, 2)));
+        assert_eq!(
+            err.to_string(),
+            "Array items must be unique: item 2 duplicates item 0"
+        );
+        // 不声明 uniqueItems 时允许重复
+        let plain = "{'type': 'array', 'items': {'type': 'number'}}";
+        assert_extracts(py, plain, "[1, 1]", "[1.0, 1.0]");
+
+        // 紧凑写法的逗号同样是分隔符，只有规整的 `,ddd` 算千分位
+        let ints = "{'type': 'object', 'properties': {'a': {'type': 'array', 'items': {'type': 'integer'}}}}";
+        assert_extracts(py, ints, r#"{"a": [1,2]}"#, "{'a': [1, 2]}");
+        assert_extracts(py, ints, r#"{"a": [1,2345,6]}"#, "{'a': [1, 2345, 6]}");
+        assert_extracts(py, ints, r#"{"a": [1,000,2]}"#, "{'a': [1000, 2]}");
+        let one = "{'type': 'array', 'items': {'type': 'integer'}, 'maxItems': 1}";
+        assert!(matches!(
+            extract(py, one, "[1,2]"),
+            Err(ParseError::TooManyItems(1, 2))
+        ));
+        let unique = "{'type': 'array', 'items': {'type': 'integer'}, 'uniqueItems': True}";
+        assert!(matches!(
+            extract(py, unique, "[1,1]"),
+            Err(ParseError::DuplicateItems(0, 1))
+        ));
+        assert_extracts(py, plain, "[1.5,2e3,-3]", "[1.5, 2000.0, -3.0]");
+    })
+}
+
+const STATUS: &str = "{'enum': ['approved', 'rejected', 'pending']}";
+
+fn assert_enum(py: Python, opts: &ExtractOptions, input: &str, expected: Option<&str>) {
+    let got = extract_with(py, opts, STATUS, input)
+        .ok()
+        .map(|v| v.extract::<String>(py).unwrap());
+    assert_eq!(got.as_deref(), expected, "{input:?}");
+}
+
+#[test]
+fn test_enum_matching() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let strict = ExtractOptions::default();
+        assert_enum(py, &strict, r#""approved""#, Some("approved"));
+        assert_enum(py, &strict, r#""Approved""#, None);
+
+        let fuzzy = ExtractOptions {
+            enum_fuzzy: true,
+            ..Default::default()
+        };
+        assert_enum(py, &fuzzy, r#""REJECTED ""#, Some("rejected"));
+        assert_enum(py, &fuzzy, "Pending", Some("pending"));
+        assert_enum(py, &fuzzy, r#""Approve""#, None);
+
+        let snapping = ExtractOptions {
+            enum_fuzzy: true,
+            enum_max_distance: 2,
+            ..Default::default()
+        };
+        assert_enum(py, &snapping, r#""Approve""#, Some("approved"));
+        assert_enum(py, &snapping, r#""rejectd""#, Some("rejected"));
+        assert_enum(py, &snapping, r#""unknown""#, None);
+
+        let err = extract(py, STATUS, r#""maybe""#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Value "maybe" is not one of the enum values"#
+        );
+    })
+}
+
+#[test]
+fn test_integer_leaf() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let schema = "{'type': 'integer'}";
+        for (input, expected) in [
+            ("3", 3),
+            ("3.0", 3),
+            ("3.00", 3),
+            ("-2e2", -200),
+            ("1,000", 1000),
+        ] {
+            let got = extract(py, schema, input).unwrap();
+            assert!(
+                got.as_ref(py).is_instance_of::<pyo3::types::PyLong>(),
+                "{input:?}"
+            );
+            assert_eq!(got.extract::<i64>(py).unwrap(), expected, "{input:?}");
+        }
+        let err = extract(py, schema, "3.5").unwrap_err();
+        assert_eq!(err.to_string(), "Expected integer, got 3.5");
+        assert_fails(py, schema, "1e400");
+        assert_fails(py, schema, "abc");
+        // 超出 i64 的整数按 token 精确转成 Python int
+        for (input, expected) in [
+            ("12345678901234567890", "12345678901234567890"),
+            ("-12345678901234567890", "-12345678901234567890"),
+            ("1e20", "100000000000000000000"),
+            ("1.5E20", "150000000000000000000"),
+            ("12345678901234567890.000", "12345678901234567890"),
+        ] {
+            let got = extract(py, schema, input).unwrap();
+            assert_eq!(got.as_ref(py).str().unwrap().to_str().unwrap(), expected);
+        }
+        let err = extract(py, schema, "12345678901234567890.5").unwrap_err();
+        assert!(matches!(err, ParseError::NotAnInteger(_)), "{err:?}");
+        let even = "{'type': 'integer', 'multipleOf': 2}";
+        assert!(extract(py, even, "12345678901234567890").is_ok());
+        assert!(matches!(
+            extract(py, even, "12345678901234567891"),
+            Err(ParseError::NotMultipleOf(..))
+        ));
+        // number 叶子保持 float
+        assert_extracts(py, "{'type': 'number'}", "3", "3.0");
+    })
+}
+
+#[test]
+fn test_grouped_numbers() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let grouped = ExtractOptions {
+            parse_grouped_numbers: true,
+            ..Default::default()
+        };
+        let check = |schema: &str, input: &str, expected: &str| {
+            let got = extract_with(py, &grouped, schema, input)
+                .unwrap_or_else(|e| panic!("extraction failed for {input:?}: {e}"));
+            let want = py.eval(expected, None, None).unwrap();
+            assert!(
+                got.as_ref(py).eq(want).unwrap(),
+                "{input:?}: got {}, want {expected}",
+                got.as_ref(py).repr().unwrap()
+            );
+        };
+        let number = "{'type': 'number'}";
+        check(number, "1,234.56", "1234.56");
+        check(number, "1 234,56", "1234.56");
+        check(number, "-12,345,678", "-12345678.0");
+        check(number, "2e3", "2000.0");
+        check("{'type': 'integer'}", "1 000 000", "1000000");
+        check(
+            "{'type': 'object', 'properties': {'a': {'type': 'number'}, 'b': {'type': 'integer'}}}",
+            r#"{"a": 1 234,5, "b": 2}"#,
+            "{'a': 1234.5, 'b': 2}",
+        );
+        // 不成组的逗号是分隔符，不会把数组元素拼起来
+        let numbers = "{'type': 'array', 'items': {'type': 'number'}}";
+        check(numbers, "[1,2,3]", "[1.0, 2.0, 3.0]");
+        check(numbers, "[1,2345]", "[1.0, 2345.0]");
+    })
+}
+
+const PERSON: &str = "{
+    '$defs': {
+        'Address': {'type': 'object', 'properties': {'city': {'type': 'string'}}},
+        'Node': {'type': 'object', 'properties': {
+            'v': {'type': 'integer'},
+            'children': {'type': 'array', 'items': {'$ref': '#/$defs/Node'}}}},
+    },
+    'type': 'object',
+    'properties': {
+        'home': {'$ref': '#/$defs/Address'},
+        'work': {'anyOf': [{'$ref': '#/$defs/Address'}, {'type': 'null'}]},
+        'tree': {'$ref': '#/$defs/Node'},
+    },
+}";
+
+#[test]
+fn test_local_refs() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(
+            py,
+            PERSON,
+            r#"{"home": {"city": "Oslo"}, "work": null}"#,
+            "{'home': {'city': 'Oslo'}, 'work': None}",
+        );
+        assert_extracts(
+            py,
+            PERSON,
+            r#"{"work": {"city": "Rome"}}"#,
+            "{'work': {'city': 'Rome'}}",
+        );
+        // 递归定义：编译期不展开，解析期按输入深度递归
+        assert_extracts(
+            py,
+            PERSON,
+            r#"{"tree": {"v": 1, "children": [{"v": 2, "children": []}, {"v": 3}]}}"#,
+            "{'tree': {'v': 1, 'children': [{'v': 2, 'children': []}, {'v': 3}]}}",
+        );
+    })
+}
+
+const COMMENT: &str = "{'type': 'object', 'required': ['text'], 'properties': {
+    'text': {'type': 'string'},
+    'replies': {'type': 'array', 'items': {'$ref': '#'}}}}";
+
+#[test]
+fn test_root_self_ref() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(
+            py,
+            COMMENT,
+            r#"{"text": "a", "replies": [{"text": "b", "replies": [{"text": "c"}]}, {"text": "d"}]}"#,
+            "{'text': 'a', 'replies': [{'text': 'b', 'replies': [{'text': 'c'}]}, {'text': 'd'}]}",
+        );
+        // 所有层共用根节点的同一个槽位
+        let SchemaNode::Document { slots, .. } =
+            compiler::compile(py.eval(COMMENT, None, None).unwrap()).unwrap()
+        else {
+            panic!("self-referential schema compiles to a Document");
+        };
+        assert_eq!(slots.len(), 1);
+
+        // 病态的深层输入被深度上限挡住，而不是耗尽栈
+        let deep = format!(
+            "{}{{\"text\": \"x\"}}{}",
+            r#"{"text": "x", "replies": ["#.repeat(200),
+            "]}".repeat(200)
+        );
+        let err = extract(py, COMMENT, &deep).unwrap_err();
+        assert!(matches!(err.cause(), ParseError::RecursionLimit));
+        let (path, _) = err.into_parts();
+        assert_eq!(
+            path[..3],
+            [
+                PathSegment::Key("replies".into()),
+                PathSegment::Index(0),
+                PathSegment::Key("replies".into()),
+            ]
+        );
+    })
+}
+
+#[test]
+fn test_bad_refs_fail_to_compile() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        for schema in [
+            "{'$ref': '#/$defs/Missing'}",
+            "{'$ref': 'other.json#/a'}",
+            "{'$defs': {'a': 1}, '$ref': '#/$defs/a/b'}",
+        ] {
+            assert!(compiler::compile(py.eval(schema, None, None).unwrap()).is_err());
+        }
+        // 只引用自己的定义能编译，解析时受递归深度保护
+        let schema = "{'$defs': {'a': {'$ref': '#/$defs/a'}}, '$ref': '#/$defs/a'}";
+        assert!(matches!(
+            extract(py, schema, "1"),
+            Err(ParseError::RecursionLimit)
+        ));
+    })
+}
+
+const SHAPE: &str = "{'oneOf': [
+    {'type': 'object', 'properties': {'kind': {'const': 'circle'}, 'r': {'type': 'number'}}},
+    {'type': 'object', 'properties': {'kind': {'const': 'square'}, 'side': {'type': 'number'}}},
+]}";
+
+#[test]
+fn test_const() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        assert_extracts(py, "{'const': 'v1'}", r#""v1""#, "'v1'");
+        assert_extracts(py, "{'const': 3}", "3.0", "3");
+        assert_extracts(py, "{'const': True}", "true", "True");
+        assert_extracts(py, "{'const': None}", "null", "None");
+        let err = extract(py, "{'const': 'v1'}", r#""v2""#).unwrap_err();
+        assert_eq!(err.to_string(), "Expected const value 'v1'");
+        assert_fails(py, "{'const': 3}", "4");
+
+        // 判别字段选出 oneOf 的分支
+        assert_extracts(
+            py,
+            SHAPE,
+            r#"{"kind": "square", "side": 2}"#,
+            "{'kind': 'square', 'side': 2.0}",
+        );
+        assert_extracts(
+            py,
+            SHAPE,
+            r#"{"kind": "circle", "r": 1.5}"#,
+            "{'kind': 'circle', 'r': 1.5}",
+        );
+        assert_fails(py, SHAPE, r#"{"kind": "hexagon"}"#);
+
+        assert!(compiler::compile(py.eval("{'const': [1]}", None, None).unwrap()).is_err());
+    })
+}
+
+const CONTACT: &str = "{'type': 'object', 'properties': {
+    'first_name': {'type': 'string'},
+    'id': {'type': 'integer'},
+}, 'required': ['first_name']}";
+
+fn key_matching(py: Python, mode: KeyNormalization, input: &str) -> Option<String> {
+    let opts = ExtractOptions {
+        key_normalization: mode,
+        ..Default::default()
+    };
+    extract_with(py, &opts, CONTACT, input)
+        .ok()
+        .map(|v| v.as_ref(py).repr().unwrap().to_string())
+}
+
+#[test]
+fn test_key_normalization() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let padded = r#"{" first_name ": "Ada", " id": 7}"#;
+        assert_eq!(key_matching(py, KeyNormalization::Exact, padded), None);
+        assert_eq!(
+            key_matching(py, KeyNormalization::Trim, padded).as_deref(),
+            Some("{'first_name': 'Ada', 'id': 7}")
+        );
+
+        let shouted = r#"{"FIRST_NAME": "Ada"}"#;
+        assert_eq!(key_matching(py, KeyNormalization::Trim, shouted), None);
+        assert_eq!(
+            key_matching(py, KeyNormalization::Lower, shouted).as_deref(),
+            Some("{'first_name': 'Ada'}")
+        );
+
+        for input in [
+            r#"{"First Name": "Ada"}"#,
+            r#"{'firstName': 'Ada'}"#,
+            r#"{"first-name" : "Ada"}"#,
+        ] {
+            assert_eq!(
+                key_matching(py, KeyNormalization::Snake, input).as_deref(),
+                Some("{'first_name': 'Ada'}"),
+                "{input:?}"
+            );
+        }
+
+        // 字符串值里形似 key 的内容不会被误认
+        let decoy = r#"{"note": "First Name", "First Name": "Ada"}"#;
+        assert_eq!(
+            key_matching(py, KeyNormalization::Snake, decoy).as_deref(),
+            Some("{'first_name': 'Ada'}")
+        );
+    })
+}
+
+#[test]
+fn test_normalize_unicode_digits() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let opts = ExtractOptions {
+            normalize_unicode_digits: true,
+            ..Default::default()
+        };
+        let number = |input: &str| {
+            extract_with(py, &opts, "{'type': 'number'}", input)
+                .ok()
+                .map(|v| v.extract::<f64>(py).unwrap())
+        };
+        assert_eq!(number("１２３"), Some(123.0));
+        assert_eq!(number("－１,２３４．５"), Some(-1234.5));
+        assert_eq!(number("＋"), None);
+        let integer = extract_with(py, &opts, "{'type': 'integer'}", "４２").unwrap();
+        assert_eq!(integer.extract::<i64>(py).unwrap(), 42);
+
+        assert!(extract(py, "{'type': 'number'}", "１２３").is_err());
+    })
+}
+
+const EVENT: &str = "{'type': 'object', 'properties': {
+    'at': {'type': 'string', 'format': 'date-time'},
+    'day': {'type': 'string', 'format': 'date'},
+    'id': {'type': 'string', 'format': 'uuid'},
+    'contact': {'type': 'string', 'format': 'email'},
+    'tag': {'type': 'string', 'format': 'hostname'}}}";
+
+#[test]
+fn test_coerce_formats() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let coerce = ExtractOptions {
+            coerce_formats: true,
+            ..Default::default()
+        };
+        let input = r#"{"at": "2024-05-01T12:30:00Z", "day": "2024-05-01",
+            "id": "123e4567-e89b-12d3-a456-426614174000", "contact": "a.b@example.com",
+            "tag": "not checked"}"#;
+        let got = extract_with(py, &coerce, EVENT, input).unwrap();
+        let want = py
+            .eval(
+                "{'at': __import__('datetime').datetime(2024, 5, 1, 12, 30,
+                    tzinfo=__import__('datetime').timezone.utc),
+                  'day': __import__('datetime').date(2024, 5, 1),
+                  'id': '123e4567-e89b-12d3-a456-426614174000',
+                  'contact': 'a.b@example.com', 'tag': 'not checked'}",
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(got.as_ref(py).eq(want).unwrap());
+
+        let err = extract_with(py, &coerce, EVENT, r#"{"id": "123-nope"}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"validation failed at data.id: Value "123-nope" is not a valid uuid"#
+        );
+        for bad in [
+            r#"{"at": "yesterday"}"#,
+            r#"{"day": "2024-13-01"}"#,
+            r#"{"contact": "no-at-sign"}"#,
+            r#"{"contact": "a@b"}"#,
+        ] {
+            assert!(extract_with(py, &coerce, EVENT, bad).is_err(), "{bad}");
+        }
+        // 超长的值按长度直接拒绝，内存中校验同样如此
+        let long = "a".repeat(200_000);
+        for field in ["contact", "id"] {
+            let input = format!(r#"{{"{field}": "{long}"}}"#);
+            assert!(extract_with(py, &coerce, EVENT, &input).is_err(), "{field}");
+        }
+        let schema = compiler::compile(py.eval(EVENT, None, None).unwrap()).unwrap();
+        let value = pyo3::types::PyDict::new(py);
+        value
+            .set_item("contact", format!("{long}@example.com"))
+            .unwrap();
+        assert!(matches!(
+            validate::validate(value, &schema, &coerce, 0)
+                .unwrap_err()
+                .cause(),
+            ParseError::InvalidFormat("email", _)
+        ));
+        // 关闭时不校验、不转换
+        assert_extracts(py, EVENT, r#"{"at": "yesterday"}"#, "{'at': 'yesterday'}");
+    })
+}
+
+const EXAMPLED: &str = "{'type': 'object', 'properties': {
+    'day': {'type': 'string', 'examples': ['2024-01-31', '1999-12-01']},
+    'at': {'type': 'string', 'examples': ['2024-05-01T12:30:00Z', '2024-05-01 08:00']},
+    'id': {'type': 'string', 'examples': ['123e4567-e89b-12d3-a456-426614174000']},
+    'mixed': {'type': 'string', 'examples': ['2024-01-31', 'soon']},
+    'loose': {'type': 'string', 'examples': ['2024-01-31', 7]},
+    'none': {'type': 'string', 'examples': []}}}";
+
+#[test]
+fn test_formats_inferred_from_examples() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        // 例子格式一致时，不开 coerce_formats 也会转换
+        assert_extracts(
+            py,
+            EXAMPLED,
+            r#"{"day": "2024-05-01", "at": "2024-05-01T12:30:00Z"}"#,
+            "{'day': __import__('datetime').date(2024, 5, 1),
+              'at': __import__('datetime').datetime(2024, 5, 1, 12, 30,
+                  tzinfo=__import__('datetime').timezone.utc)}",
+        );
+        let err = extract(py, EXAMPLED, r#"{"id": "123-nope"}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"validation failed at data.id: Value "123-nope" is not a valid uuid"#
+        );
+        assert!(extract(py, EXAMPLED, r#"{"day": "someday"}"#).is_err());
+        // 例子不一致、含非字符串或为空时保持普通字符串
+        assert_extracts(
+            py,
+            EXAMPLED,
+            r#"{"mixed": "x", "loose": "y", "none": "z"}"#,
+            "{'mixed': 'x', 'loose': 'y', 'none': 'z'}",
+        );
+        // 显式 format 优先于例子
+        assert_extracts(
+            py,
+            "{'type': 'string', 'format': 'hostname', 'examples': ['2024-01-31']}",
+            r#""not a date""#,
+            "'not a date'",
+        );
+
+        let schema = py
+            .eval("{'type': 'string', 'examples': '2024-01-31'}", None, None)
+            .unwrap();
+        let err = compiler::compile(schema).unwrap_err();
+        assert!(
+            err.to_string().contains("'examples' must be a list"),
+            "{err}"
+        );
+    })
+}
+
+#[test]
+fn test_bool_words() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let words = ExtractOptions {
+            truthy_words: vec!["yes".into(), "on".into(), "1".into()],
+            falsy_words: vec!["no".into(), "off".into(), "0".into()],
+            ..Default::default()
+        };
+        let schema = "{'type': 'object', 'properties': {'a': {'type': 'boolean'},
+            'b': {'type': 'boolean'}, 'c': {'type': 'boolean'}, 'd': {'type': 'boolean'}}}";
+        let got = extract_with(
+            py,
+            &words,
+            schema,
+            r#"{"a": YES, "b": "off", "c": 1, "d": true}"#,
+        )
+        .unwrap();
+        let want = py
+            .eval("{'a': True, 'b': False, 'c': True, 'd': True}", None, None)
+            .unwrap();
+        assert!(got.as_ref(py).eq(want).unwrap());
+        // 整个 token 必须与同义词一致
+        assert!(extract_with(py, &words, "{'type': 'boolean'}", "10").is_err());
+        assert!(extract_with(py, &words, "{'type': 'boolean'}", "maybe").is_err());
+        // 未配置时保持原样
+        assert_fails(py, "{'type': 'boolean'}", "yes");
+        // 只作用于 boolean 叶子
+        let text = extract_with(py, &words, "{'type': 'string'}", "yes").unwrap();
+        assert_eq!(text.extract::<String>(py).unwrap(), "yes");
+    })
+}
+
+#[test]
+fn test_coerce_scalar_to_array() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let coerce = ExtractOptions {
+            coerce_scalar_to_array: true,
+            ..Default::default()
+        };
+        let schema = "{'type': 'object', 'properties': {
+            'tags': {'type': 'array', 'items': {'type': 'string'}},
+            'owners': {'type': 'array', 'items': {'type': 'object',
+                'properties': {'name': {'type': 'string'}}}},
+            'n': {'type': 'integer'}}}";
+        let got = extract_with(
+            py,
+            &coerce,
+            schema,
+            r#"{"tags": "urgent", "owners": {"name": "ann"}, "n": 3}"#,
+        )
+        .unwrap();
+        let want = py
+            .eval(
+                "{'tags': ['urgent'], 'owners': [{'name': 'ann'}], 'n': 3}",
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(got.as_ref(py).eq(want).unwrap());
+
+        // 真正的数组不受影响
+        let list = "{'type': 'array', 'items': {'type': 'integer'}}";
+        let got = extract_with(py, &coerce, list, "[1, 2]").unwrap();
+        assert_eq!(got.extract::<Vec<i64>>(py).unwrap(), [1, 2]);
+
+        // 包进去的值仍按 items 校验，路径指向下标 0
+        let err = extract_with(py, &coerce, list, "true").unwrap_err();
+        assert!(
+            err.to_string().starts_with("validation failed at data[0]"),
+            "{err}"
+        );
+
+        // 包装后的列表同样受 minItems 约束
+        let pair = "{'type': 'array', 'items': {'type': 'integer'}, 'minItems': 2}";
+        assert!(extract_with(py, &coerce, pair, "7").is_err());
+    })
+}
+
+#[test]
+fn test_nullable() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let schema = "{'type': 'object', 'properties': {
+            'name': {'type': 'string', 'nullable': True},
+            'age': {'type': 'integer', 'nullable': True},
+            'tags': {'type': 'array', 'items': {'type': 'string'}, 'nullable': True}}}";
+        assert_extracts(
+            py,
+            schema,
+            r#"{"name": null, "age": None, "tags": null}"#,
+            "{'name': None, 'age': None, 'tags': None}",
+        );
+        assert_extracts(
+            py,
+            schema,
+            r#"{"name": "ann", "age": 3, "tags": ["x"]}"#,
+            "{'name': 'ann', 'age': 3, 'tags': ['x']}",
+        );
+        // 非 null 的值仍按原类型校验
+        assert_fails(py, "{'type': 'integer', 'nullable': True}", "true");
+        // `nullable: false` 与不写相同
+        assert_fails(py, "{'type': 'integer', 'nullable': False}", "null");
+        let bad = py
+            .eval("{'type': 'integer', 'nullable': 'yes'}", None, None)
+            .unwrap();
+        let err = compiler::compile(bad).unwrap_err();
+        assert!(
+            err.to_string().contains("'nullable' must be a boolean"),
+            "{err}"
+        );
+    })
+}
+
+#[test]
+fn test_multiple_of() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let schema = "{'type': 'object', 'properties': {
+            'qty': {'type': 'integer', 'multipleOf': 5},
+            'price': {'type': 'number', 'multipleOf': 0.01}}}";
+        assert_extracts(
+            py,
+            schema,
+            r#"{"qty": 15, "price": 19.99}"#,
+            "{'qty': 15, 'price': 19.99}",
+        );
+        // 浮点误差不应误判：0.3 / 0.1 = 2.9999999999999996
+        assert_extracts(py, "{'type': 'number', 'multipleOf': 0.1}", "0.3", "0.3");
+        assert_extracts(py, "{'type': 'integer', 'multipleOf': 5}", "-10", "-10");
+        assert_extracts(py, "{'type': 'integer', 'multipleOf': 5}", "20.0", "20");
+        // 大整数走取模，不受 f64 精度影响
+        assert_fails(
+            py,
+            "{'type': 'integer', 'multipleOf': 2}",
+            "9007199254740993",
+        );
+        assert_fails(py, "{'type': 'number', 'multipleOf': 0.5}", "1.2");
+
+        let err = extract(py, schema, r#"{"qty": 12, "price": 1}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "validation failed at data.qty: Expected a multiple of 5, found 12"
+        );
+
+        for bad in ["0", "-2", "'3'", "True"] {
+            let schema = py
+                .eval(
+                    &format!("{{'type': 'number', 'multipleOf': {bad}}}"),
+                    None,
+                    None,
+                )
+                .unwrap();
+            let err = compiler::compile(schema).unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("'multipleOf' must be a positive number"),
+                "{bad}: {err}"
+            );
+        }
+    })
+}
+
+#[test]
+fn test_field_fallbacks() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let mut opts = ExtractOptions::default();
+        for (name, value) in [
+            ("age", py.eval("-1", None, None)),
+            ("tags", py.eval("[]", None, None)),
+        ] {
+            opts.field_fallbacks
+                .insert(name.to_string(), value.unwrap().into());
+        }
+        let schema = compiler::compile(
+            py.eval(
+                "{'type': 'object', 'properties': {
+                    'name': {'type': 'string'},
+                    'age': {'type': 'integer'},
+                    'ok': {'type': 'boolean'},
+                    'tags': {'type': 'array', 'items': {'type': 'string'}, 'minItems': 1}},
+                  'required': ['name', 'age']}",
+                None,
+                None,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let parse = |input: &str| {
+            let mut cursor = Cursor::new(input.as_bytes());
+            parser::parse_root_verbose(&mut cursor, &schema, &opts, py)
+        };
+
+        // 坏值整体跳过（含嵌套括号与字符串里的逗号），后面的字段照常解析
+        let input = r#"{"age": {"years": "3, maybe"}, "tags": [], "name": "ann"}"#;
+        let (value, repairs) = parse(input).unwrap();
+        let want = py
+            .eval("{'age': -1, 'tags': [], 'name': 'ann'}", None, None)
+            .unwrap();
+        assert!(value.as_ref(py).eq(want).unwrap());
+        let got: Vec<_> = repairs
+            .iter()
+            .map(|r| (r.offset, r.detail.as_str()))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                (8, "age: Expected number"),
+                (39, "tags: Expected at least 1 array items, found 0")
+            ]
+        );
+        assert!(repairs.iter().all(|r| r.kind == RepairKind::FieldFallback));
+
+        // 没有兜底值的字段、缺失的必填字段仍然报错；成功解析不留记录
+        assert!(parse(r#"{"name": "ann", "age": 1, "ok": maybe}"#).is_err());
+        assert!(parse(r#"{"name": "ann"}"#).is_err());
+        assert!(parse(r#"{"name": "ann", "age": 1}"#).unwrap().1.is_empty());
+
+        // 被回滚的 anyOf 分支、整体换成兜底值的外层字段里记下的兜底都不留下
+        opts.field_fallbacks
+            .insert("profile".to_string(), py.None());
+        let schema = compiler::compile(
+            py.eval(
+                "{'type': 'object', 'properties': {
+                    'pet': {'anyOf': [
+                        {'type': 'object', 'properties': {
+                            'age': {'type': 'integer'}, 'kind': {'const': 'cat'}},
+                         'required': ['kind']},
+                        {'type': 'object', 'properties': {
+                            'age': {'type': 'string'}, 'kind': {'type': 'string'}}}]},
+                    'profile': {'type': 'object', 'properties': {
+                        'age': {'type': 'integer'}, 'name': {'type': 'string'}},
+                     'required': ['name']}}}",
+                None,
+                None,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let input = r#"{"pet": {"age": "three", "kind": "dog"}, "profile": {"age": "x"}}"#;
+        let mut cursor = Cursor::new(input.as_bytes());
+        let (value, repairs) = parser::parse_root_verbose(&mut cursor, &schema, &opts, py).unwrap();
+        let want = py
+            .eval(
+                "{'pet': {'age': 'three', 'kind': 'dog'}, 'profile': None}",
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(value.as_ref(py).eq(want).unwrap());
+        let got: Vec<_> = repairs.iter().map(|r| r.offset).collect();
+        assert_eq!(got, [input.find("{\"age\": \"x").unwrap()]);
+    })
+}
+
+#[test]
+fn test_drop_mode() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let schema = "{'type': 'object', 'properties': {
+            'id': {'type': 'integer', 'readOnly': True},
+            'password': {'type': 'string', 'writeOnly': True},
+            'nick': {'type': 'string', 'deprecated': True},
+            'name': {'type': 'string'}},
+            'required': ['id', 'password', 'name']}";
+        let input = r#"{"id": "not a number", "password": "pw", "nick": "a", "name": "ann"}"#;
+        let with = |drop_mode, drop_deprecated| ExtractOptions {
+            drop_mode,
+            drop_deprecated,
+            ..Default::default()
+        };
+        let check = |opts: &ExtractOptions, input: &str, expected: &str| {
+            let got = extract_with(py, opts, schema, input).unwrap();
+            let want = py.eval(expected, None, None).unwrap();
+            assert!(got.as_ref(py).eq(want).unwrap(), "{expected}");
+        };
+
+        // 写方向丢弃 readOnly：坏值不再校验，缺失也不算缺少必填字段
+        let write = with(Some(DropMode::Write), false);
+        check(
+            &write,
+            input,
+            "{'password': 'pw', 'nick': 'a', 'name': 'ann'}",
+        );
+        check(
+            &write,
+            r#"{"password": "pw", "name": "ann"}"#,
+            "{'password': 'pw', 'name': 'ann'}",
+        );
+        // 读方向丢弃 writeOnly，readOnly 照常校验
+        let read = with(Some(DropMode::Read), true);
+        check(
+            &read,
+            r#"{"id": 1, "password": "pw", "nick": "a", "name": "ann"}"#,
+            "{'id': 1, 'name': 'ann'}",
+        );
+        assert!(extract_with(py, &read, schema, input).is_err());
+        // 默认不丢弃任何字段
+        assert!(extract_with(py, &ExtractOptions::default(), schema, input).is_err());
+    })
+}
+
+#[test]
+fn test_validate_repaired_value() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let schema = compiler::compile(
+            py.eval(
+                "{'type': 'object',
+                  'properties': {
+                    'name': {'type': 'string'},
+                    'age': {'type': 'integer'},
+                    'ok': {'type': 'boolean'},
+                    'tags': {'type': 'array', 'items': {'type': 'string'}}},
+                  'required': ['name']}",
+                None,
+                None,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let opts = ExtractOptions::default();
+        let check = |value: &str| {
+            validate::validate(py.eval(value, None, None).unwrap(), &schema, &opts, 0)
+        };
+
+        // 数字字符串与小数部分为零的 float 收敛为 int，数字转文本，未声明的字段丢弃
+        let got = check("{'name': 7, 'age': '42', 'ok': 'false', 'tags': ['a', 1], 'extra': None}")
+            .unwrap();
+        let want = py
+            .eval(
+                "{'name': '7', 'age': 42, 'ok': False, 'tags': ['a', '1']}",
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(got.as_ref(py).eq(want).unwrap());
+        let got = check("{'name': 'ann', 'age': 3.0}").unwrap();
+        assert!(got
+            .as_ref(py)
+            .eq(py.eval("{'name': 'ann', 'age': 3}", None, None).unwrap())
+            .unwrap());
+
+        // 错误带着从根开始的路径
+        let (path, _) = check("{'name': 'ann', 'tags': ['a', None]}")
+            .unwrap_err()
+            .into_parts();
+        assert_eq!(
+            path,
+            [PathSegment::Key("tags".to_string()), PathSegment::Index(1)]
+        );
+        assert!(matches!(check("{'age': 1}"), Err(ParseError::MissingField(f)) if f == "name"));
+        assert!(matches!(
+            check("{'name': 'ann', 'age': 2.5}").unwrap_err().cause(),
+            ParseError::NotAnInteger(_)
+        ));
+        assert!(check("['name']").is_err());
+
+        // 只是看起来像数字的字符串不会被当成 0
+        let number = compiler::compile(py.eval("{'type': 'number'}", None, None).unwrap()).unwrap();
+        for bad in ["1.2.3", "1-2", "1e", "-", "."] {
+            let value = pyo3::types::PyString::new(py, bad);
+            assert!(
+                matches!(
+                    validate::validate(value, &number, &opts, 0),
+                    Err(ParseError::TypeMismatch("number"))
+                ),
+                "{bad}"
+            );
+            assert_fails(py, "{'type': 'number'}", bad);
+        }
+        assert!(check("{'name': 'ann', 'age': '1.2.3'}").is_err());
+    })
+}